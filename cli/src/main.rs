@@ -0,0 +1,1072 @@
+mod tour;
+mod tui;
+
+use rand::RngCore;
+use slider_puzzle::config::Config;
+use slider_puzzle::filter::{Filter, Reply};
+use slider_puzzle::glyphs::GlyphSet;
+use slider_puzzle::history::{self, ScrambleHistory};
+use slider_puzzle::pdb::{self, PatternDatabase};
+use slider_puzzle::registry::{check_optimal, SolveFn, SolverRegistry, INTERRUPTIBLE_ENGINES};
+use slider_puzzle::solution::Solution;
+use slider_puzzle::tablebase::Tablebase;
+use slider_puzzle::{
+    batch, bench, bounds, catalog, competition, curriculum, daily, dedup, design, determinism,
+    explain, first_moves, json, ladder, oracle, pareto, repro, schema, tune, Puzzle,
+};
+use slider_puzzle_embedded as embedded;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("batch") => batch_command(&args[1..]),
+        Some("bench") => bench_command(&args[1..]),
+        Some("crossval") => crossval_command(&args[1..]),
+        Some("curriculum") => curriculum_command(&args[1..]),
+        Some("determinism") => determinism_command(&args[1..]),
+        Some("duel") => duel_command(&args[1..]),
+        Some("engines") => engines_command(&args[1..]),
+        Some("explain") => explain_command(&args[1..]),
+        Some("filter") => filter_command(&args[1..]),
+        Some("first-moves") => first_moves_command(&args[1..]),
+        Some("hardest") => hardest_command(&args[1..]),
+        Some("ladder") => ladder_command(&args[1..]),
+        Some("migrate") => migrate_command(&args[1..]),
+        Some("pareto") => pareto_command(&args[1..]),
+        Some("pdb") => pdb_command(&args[1..]),
+        Some("repro") => repro_command(&args[1..]),
+        Some("scramble") => scramble_command(&args[1..]),
+        #[cfg(feature = "scripting")]
+        Some("script") => script_command(&args[1..]),
+        Some("tablebase") => tablebase_command(&args[1..]),
+        Some("tour") => tour_command(),
+        Some("tune") => tune_command(&args[1..]),
+        _ => solve_command(&args),
+    }
+}
+
+/// Reads a board from the arguments, or from stdin if none are given.
+fn read_puzzle(args: &[String]) -> Puzzle {
+    let text = if args.is_empty() {
+        io::read_to_string(io::stdin()).unwrap_or_default()
+    } else {
+        args.join(" ")
+    };
+
+    text.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid board: {}", err);
+        process::exit(1);
+    })
+}
+
+fn solved_puzzle(size: usize) -> Puzzle {
+    Puzzle::new(size).unwrap_or_else(|err| {
+        eprintln!("Invalid board size: {}", err);
+        process::exit(1);
+    })
+}
+
+fn duel_command(args: &[String]) {
+    let config = parse_options(args, &["size", "left", "right"]);
+    let size = config.get("size").and_then(|s| s.parse().ok()).unwrap_or(3);
+    let engine = |key: &str, default: &'static str| {
+        let name = config.get(key).unwrap_or(default);
+        tui::DUEL_ENGINES
+            .into_iter()
+            .find(|&engine| engine == name)
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "Unknown duel engine {}, available: {}",
+                    name,
+                    tui::DUEL_ENGINES.join(", ")
+                );
+                process::exit(1);
+            })
+    };
+    let (left, right) = (
+        engine("left", "ida-star"),
+        engine("right", "weighted-a-star"),
+    );
+
+    let mut puzzle = solved_puzzle(size);
+    puzzle.shuffle();
+
+    if let Err(err) = tui::duel(&puzzle, left, right) {
+        eprintln!("Terminal error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn explain_command(args: &[String]) {
+    let puzzle = read_puzzle(args);
+    println!("{}", explain::explain(&puzzle));
+}
+
+fn filter_command(args: &[String]) {
+    let config = parse_options(args, &["reply", "budget_ms"]);
+    let reply = match config.get("reply") {
+        None | Some("move") => Reply::NextMove,
+        Some("solution") => Reply::Solution,
+        Some(other) => {
+            eprintln!("Unknown reply {}, expected move or solution", other);
+            process::exit(1);
+        }
+    };
+    let budget = Duration::from_millis(config.parse_or("budget_ms", 100));
+
+    let mut filter = Filter::new(reply, budget);
+    if let Err(err) = filter.run(io::stdin().lock(), io::stdout().lock()) {
+        eprintln!("Could not run filter: {}", err);
+        process::exit(1);
+    }
+}
+
+fn first_moves_command(args: &[String]) {
+    let config = parse_options(args, &["size", "count", "seed", "heuristic"]);
+    let size = config.parse_or("size", 3);
+    let count = config.parse_or("count", 1000);
+    let seed = config.get("seed").unwrap_or("first-moves");
+    let heuristic = config.get("heuristic").unwrap_or("linear-conflict");
+
+    match first_moves::analyze(size, count, seed, heuristic) {
+        Ok(report) => println!("{}", report),
+        Err(err) => {
+            eprintln!("Could not analyze first moves: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn hardest_command(args: &[String]) {
+    let config = parse_options(args, &["board", "moves", "heuristic"]);
+    let Some(board) = config.get("board") else {
+        eprintln!("Usage: hardest --board TILES [--moves K] [--heuristic NAME]");
+        process::exit(1);
+    };
+    let puzzle: Puzzle = board.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid board: {}", err);
+        process::exit(1);
+    });
+    let moves = config.parse_or("moves", 10);
+    let hardness = match config.get("heuristic") {
+        Some(name) => design::Hardness::Heuristic(name),
+        None => design::Hardness::OptimalLength,
+    };
+
+    match puzzle.hardest_within(moves, hardness) {
+        Ok(nearby) => {
+            println!("{:#}", nearby.puzzle);
+            println!("Score: {}", nearby.score);
+            let path = Solution::new(nearby.path);
+            println!("Reached in {} moves: {}", path.len(), path);
+            println!("Boards examined: {}", nearby.examined);
+        }
+        Err(err) => {
+            eprintln!("Could not search nearby boards: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn repro_command(args: &[String]) {
+    let mut config = parse_options(
+        args,
+        &[
+            "case",
+            "board",
+            "solver",
+            "require_optimal",
+            "shrink",
+            "save",
+        ],
+    );
+    let mut case = match (config.get("case"), config.get("board")) {
+        (Some(path), _) => fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| json::Json::parse(&text).map_err(str::to_string))
+            .and_then(|document| repro::FailingCase::from_json(&document).map_err(str::to_string))
+            .unwrap_or_else(|err| {
+                eprintln!("Could not read case {}: {}", path, err);
+                process::exit(1);
+            }),
+        (None, Some(board)) => repro::FailingCase {
+            solver: config.get("solver").unwrap_or("ida-star").to_string(),
+            require_optimal: config.parse_or("require_optimal", false),
+            puzzle: board.parse().unwrap_or_else(|err| {
+                eprintln!("Invalid board: {}", err);
+                process::exit(1);
+            }),
+            reason: String::new(),
+        },
+        (None, None) => {
+            eprintln!(
+                "Usage: repro (--case FILE | --board TILES [--solver NAME] [--require_optimal true]) [--shrink true] [--save FILE]"
+            );
+            process::exit(1);
+        }
+    };
+    config.set("solver", &case.solver);
+    config.set("require_optimal", &case.require_optimal.to_string());
+    let (_, solve) = create_solver(&config);
+    let failure =
+        |puzzle: &Puzzle| repro::check_answer(puzzle, solve(puzzle), case.require_optimal).err();
+
+    let Some(reason) = failure(&case.puzzle) else {
+        println!("Solver {} handles the board correctly", case.solver);
+        return;
+    };
+    println!("{:#}", case.puzzle);
+    println!("Fails: {}", reason);
+    case.reason = reason;
+
+    if config.parse_or("shrink", false) {
+        match repro::Scramble::from_puzzle(&case.puzzle) {
+            Ok(scramble) => {
+                let smallest = repro::shrink(scramble, |puzzle| failure(puzzle).is_some());
+                if let Ok(puzzle) = smallest.puzzle() {
+                    case.reason = failure(&puzzle).unwrap_or_default();
+                    case.puzzle = puzzle;
+                }
+                println!("Shrunk to {} moves from solved:", smallest.moves.len());
+                println!("{:#}", case.puzzle);
+                println!("Fails: {}", case.reason);
+            }
+            Err(err) => eprintln!("Could not shrink: {}", err),
+        }
+    }
+
+    if let Some(path) = config.get("save") {
+        if let Err(err) = fs::write(path, case.to_json().to_string()) {
+            eprintln!("Could not save case {}: {}", path, err);
+        }
+    }
+    process::exit(1);
+}
+
+fn pareto_command(args: &[String]) {
+    let puzzle = read_puzzle(args);
+    match pareto::pareto_solutions(&puzzle) {
+        Ok(tradeoffs) => {
+            for tradeoff in tradeoffs {
+                println!(
+                    "{} moves, {} pushes: {}",
+                    tradeoff.moves, tradeoff.pushes, tradeoff.solution
+                );
+            }
+        }
+        Err(err) => {
+            eprintln!("Could not solve: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn migrate_command(args: &[String]) {
+    let [path] = args else {
+        eprintln!("Usage: migrate FILE");
+        process::exit(1);
+    };
+
+    let migrated = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| json::Json::parse(&text).map_err(str::to_string))
+        .and_then(|document| schema::migrate(document).map_err(str::to_string));
+
+    match migrated {
+        Ok(document) => println!("{}", document),
+        Err(err) => {
+            eprintln!("Could not migrate {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+}
+
+fn bench_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "solver",
+            "size",
+            "count",
+            "repeats",
+            "seed",
+            "timeout_secs",
+            "heuristic",
+            "weight",
+            "beam_width",
+            "table_bytes",
+            "pdb_file",
+            "pdb_partition",
+            "format",
+        ],
+    );
+    let defaults = bench::BenchConfig::default();
+    let engines = match config.get("solver") {
+        None | Some("all") => defaults.engines,
+        Some(name) => match INTERRUPTIBLE_ENGINES.into_iter().find(|&e| e == name) {
+            Some(engine) => vec![engine],
+            None => {
+                eprintln!(
+                    "Cannot benchmark {}, choose one of: {}",
+                    name,
+                    INTERRUPTIBLE_ENGINES.join(", ")
+                );
+                process::exit(1);
+            }
+        },
+    };
+    let bench_config = bench::BenchConfig {
+        engines,
+        size: config.parse_or("size", defaults.size),
+        boards: config.parse_or("count", defaults.boards),
+        repeats: config.parse_or("repeats", defaults.repeats),
+        seed: config.get("seed").unwrap_or(&defaults.seed).to_string(),
+        timeout: config
+            .get("timeout_secs")
+            .and_then(|s| s.parse().ok())
+            .map_or(defaults.timeout, Duration::from_secs),
+        engine_config: config.clone(),
+    };
+
+    match bench::run(&bench_config) {
+        Ok(report) if config.get("format") == Some("json") => println!("{}", report.to_json()),
+        Ok(report) => print!("{}", report),
+        Err(err) => {
+            eprintln!("Could not run the benchmark: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn determinism_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "threads",
+            "solver",
+            "size",
+            "count",
+            "deterministic",
+            "heuristic",
+            "weight",
+            "beam_width",
+        ],
+    );
+    if let Some(threads) = config.get("threads") {
+        compare_threads(threads, &config);
+        return;
+    }
+
+    let fingerprint = determinism::fingerprint();
+    println!("Fingerprint: {:016x}", fingerprint);
+    if fingerprint == determinism::REFERENCE_FINGERPRINT {
+        println!("Matches the reference platform");
+    } else {
+        println!(
+            "Differs from the reference {:016x}",
+            determinism::REFERENCE_FINGERPRINT
+        );
+        process::exit(1);
+    }
+}
+
+/// Checks that solving with each of the comma-separated worker counts in
+/// `threads` gives the same results.
+fn compare_threads(threads: &str, config: &Config) {
+    let Ok(worker_counts) = threads
+        .split(',')
+        .map(|count| count.trim().parse())
+        .collect::<Result<Vec<usize>, _>>()
+    else {
+        eprintln!("Invalid thread counts: {}", threads);
+        process::exit(1);
+    };
+    let engine = config.get("solver").unwrap_or("ida-star");
+    if !INTERRUPTIBLE_ENGINES.contains(&engine) {
+        eprintln!(
+            "Cannot check {}, choose one of: {}",
+            engine,
+            INTERRUPTIBLE_ENGINES.join(", ")
+        );
+        process::exit(1);
+    }
+    let size = config.parse_or("size", 3);
+    let boards: Vec<Puzzle> = (0..config.parse_or("count", 20))
+        .map(|i| {
+            Puzzle::daily_in(size, &format!("threads-{}", i), "determinism").unwrap_or_else(|err| {
+                eprintln!("Invalid board size: {}", err);
+                process::exit(1);
+            })
+        })
+        .collect();
+
+    let mismatches = determinism::compare_worker_counts(engine, config, &boards, &worker_counts);
+    for mismatch in &mismatches {
+        println!(
+            "Board {} with {} workers: expected {:?}, found {:?}",
+            mismatch.board, mismatch.workers, mismatch.expected, mismatch.found
+        );
+    }
+    if !mismatches.is_empty() {
+        process::exit(1);
+    }
+    println!(
+        "{} boards solved alike with {} workers",
+        boards.len(),
+        threads
+    );
+}
+
+fn engines_command(args: &[String]) {
+    let config = parse_options(args, &["size"]);
+    if let Some(size) = config.get("size") {
+        let Ok(size) = size.parse() else {
+            eprintln!("Invalid board size: {}", size);
+            process::exit(1);
+        };
+        for engine in catalog::engines() {
+            println!("{:<16} {}", engine.name, engine.recommended_for(size, size));
+        }
+        return;
+    }
+
+    println!("Engines:");
+    for engine in catalog::engines() {
+        println!("  {}", engine);
+    }
+    println!("Heuristics:");
+    for heuristic in catalog::heuristics() {
+        println!("  {}", heuristic);
+    }
+}
+
+fn ladder_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "solver",
+            "budget_secs",
+            "max_size",
+            "seed",
+            "weight",
+            "beam_width",
+        ],
+    );
+    let budget = Duration::from_secs(config.parse_or("budget_secs", 10));
+    let max_size = config.parse_or("max_size", 8);
+    let seed = config.get("seed").unwrap_or("");
+
+    let engines: Vec<&'static str> = match config.get("solver") {
+        None | Some("all") => ladder::LADDER_ENGINES.to_vec(),
+        Some(name) => match ladder::LADDER_ENGINES.into_iter().find(|&e| e == name) {
+            Some(engine) => vec![engine],
+            None => {
+                eprintln!(
+                    "Unknown ladder engine {}, available: {}",
+                    name,
+                    ladder::LADDER_ENGINES.join(", ")
+                );
+                process::exit(1);
+            }
+        },
+    };
+
+    for engine in engines {
+        println!(
+            "{}",
+            ladder::run_ladder(engine, &config, max_size, budget, seed)
+        );
+    }
+}
+
+fn scramble_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "size",
+            "min_distance",
+            "count",
+            "seed",
+            "history",
+            "window",
+            "radius",
+        ],
+    );
+    let size = config.parse_or("size", 4);
+    let count = config.parse_or("count", 1);
+    let Some(min_distance) = config
+        .get("min_distance")
+        .and_then(|s| s.parse().ok())
+        .or_else(|| competition::default_min_distance(size))
+    else {
+        eprintln!(
+            "No default minimum distance for size {}, pass --min_distance",
+            size
+        );
+        process::exit(1);
+    };
+    let mut rng: Box<dyn RngCore> = match config.get("seed") {
+        Some(seed) => Box::new(daily::seeded_rng(seed)),
+        None => Box::new(rand::thread_rng()),
+    };
+
+    // Without a history file, remember nothing so every draw is accepted
+    let history_path = config.get("history").map(Path::new);
+    let mut history = match history_path {
+        Some(path) => {
+            ScrambleHistory::load_or_new(path, config.parse_or("window", history::DEFAULT_WINDOW))
+                .unwrap_or_else(|err| {
+                    eprintln!("Could not read {}: {}", path.display(), err);
+                    process::exit(1);
+                })
+        }
+        None => ScrambleHistory::new(0),
+    }
+    .with_radius(config.parse_or("radius", history::DEFAULT_RADIUS));
+
+    for _ in 0..count {
+        let scramble = history.fresh(
+            || competition::competition_scramble(size, min_distance, rng.as_mut()),
+            |scramble| &scramble.puzzle,
+        );
+        match scramble {
+            Ok(scramble) => println!(
+                "{:#}  {} {} moves",
+                scramble.puzzle,
+                if scramble.exact { "=" } else { ">=" },
+                scramble.lower_bound
+            ),
+            Err(err) => {
+                eprintln!("Could not scramble: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = history_path {
+        if let Err(err) = history.save(path) {
+            eprintln!("Could not save {}: {}", path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Runs a Rhai script's `accept` to filter scrambles, its `estimate` to
+/// solve a board, or its `choose` to play one.
+#[cfg(feature = "scripting")]
+fn script_command(args: &[String]) {
+    use slider_puzzle::scripting::Script;
+
+    let config = parse_options(
+        args,
+        &[
+            "file",
+            "board",
+            "size",
+            "count",
+            "seed",
+            "attempts",
+            "max_moves",
+        ],
+    );
+    let Some(path) = config.get("file") else {
+        eprintln!(
+            "Usage: script --file SCRIPT [--board TILES] [--size N] [--count N] [--seed TEXT]"
+        );
+        process::exit(1);
+    };
+    let source = fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", path, err);
+        process::exit(1);
+    });
+    let script = Script::new(&source).unwrap_or_else(|err| {
+        eprintln!("Could not compile {}: {}", path, err);
+        process::exit(1);
+    });
+    let fail = |err: String| -> ! {
+        eprintln!("Script failed: {}", err);
+        process::exit(1);
+    };
+
+    let Some(board) = config.get("board") else {
+        if !script.defines("accept", 1) {
+            eprintln!("Without --board the script must define accept(board)");
+            process::exit(1);
+        }
+        let goal = solved_puzzle(config.parse_or("size", 4));
+        let mut rng: Box<dyn RngCore> = match config.get("seed") {
+            Some(seed) => Box::new(daily::seeded_rng(seed)),
+            None => Box::new(rand::thread_rng()),
+        };
+        for _ in 0..config.parse_or("count", 1) {
+            match script.scramble(&goal, config.parse_or("attempts", 10_000), rng.as_mut()) {
+                Ok(puzzle) => println!("{:#}", puzzle),
+                Err(err) => fail(err),
+            }
+        }
+        return;
+    };
+    let puzzle: Puzzle = board.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid board: {}", err);
+        process::exit(1);
+    });
+
+    if script.defines("estimate", 1) {
+        let estimate = script.estimate(&puzzle).unwrap_or_else(|err| fail(err));
+        println!("Estimate: {}", estimate);
+        match puzzle.solve_with_heuristic(script.clone()) {
+            Ok(moves) => {
+                let solution = Solution::new(moves);
+                println!("Solution ({} moves): {}", solution.len(), solution)
+            }
+            Err(err) => fail(err.to_string()),
+        }
+    }
+    if script.defines("choose", 2) {
+        let (moves, solved) = script
+            .play(&puzzle, config.parse_or("max_moves", 1000))
+            .unwrap_or_else(|err| fail(err));
+        let outcome = if solved { "solved" } else { "not solved" };
+        let solution = Solution::new(moves);
+        println!(
+            "Policy ({} moves, {}): {}",
+            solution.len(),
+            outcome,
+            solution
+        );
+    }
+    if !script.defines("estimate", 1) && !script.defines("choose", 2) {
+        eprintln!("With --board the script must define estimate(board) or choose(board, moves)");
+        process::exit(1);
+    }
+}
+
+/// Writes the packed table `slider_puzzle_embedded::Solver::from_table` reads,
+/// for firmware that keeps it in flash instead of generating it.
+fn export_embedded_table(path: &Path) {
+    let mut table = [0; embedded::TABLE_BYTES];
+    embedded::Solver::generate(&mut table);
+    if let Err(err) = fs::write(path, table) {
+        eprintln!("Could not write {}: {}", path.display(), err);
+        process::exit(1);
+    }
+    println!("Wrote {} bytes to {}", table.len(), path.display());
+}
+
+fn tablebase_command(args: &[String]) {
+    let config = parse_options(args, &["board", "file", "export_embedded"]);
+    if let Some(path) = config.get("export_embedded") {
+        export_embedded_table(Path::new(path));
+        return;
+    }
+    let Some(board) = config.get("board") else {
+        eprintln!(
+            "Usage: tablebase --board TILES [--file PATH] | tablebase --export_embedded PATH"
+        );
+        process::exit(1);
+    };
+    let puzzle: Puzzle = board.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid board: {}", err);
+        process::exit(1);
+    });
+    let path = Path::new(config.get("file").unwrap_or("tablebase3x3.bin"));
+
+    let table = Tablebase::load_or_build(path);
+    let Some(distance) = table.distance(&puzzle) else {
+        eprintln!("The tablebase only covers solvable 3x3 boards");
+        process::exit(1);
+    };
+    let best: Vec<String> = table
+        .best_moves(&puzzle)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    println!("Distance: {}", distance);
+    println!("Best moves: {}", best.join(", "));
+}
+
+fn pdb_command(args: &[String]) {
+    let config = parse_options(args, &["board", "file", "partition"]);
+    let path = Path::new(config.get("file").unwrap_or(pdb::DEFAULT_FILE));
+    let groups = match config.get("partition").map(pdb::parse_partition) {
+        Some(Ok(groups)) => groups,
+        Some(Err(err)) => {
+            eprintln!("Invalid partition: {}", err);
+            process::exit(1);
+        }
+        None => pdb::DEFAULT_PARTITION
+            .iter()
+            .map(|group| group.to_vec())
+            .collect(),
+    };
+    if !path.exists() {
+        println!("Building {}, this takes a while", path.display());
+    }
+    let database = PatternDatabase::load_or_build_partition(path, &groups).unwrap_or_else(|err| {
+        eprintln!("Could not build pattern database: {}", err);
+        process::exit(1);
+    });
+
+    let Some(board) = config.get("board") else {
+        println!("Pattern database ready in {}", path.display());
+        return;
+    };
+    let puzzle: Puzzle = board.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid board: {}", err);
+        process::exit(1);
+    });
+    let Some(estimate) = database.estimate(&puzzle) else {
+        eprintln!("The pattern database only covers 4x4 boards");
+        process::exit(1);
+    };
+    println!("Estimate: {}", estimate);
+    println!("Manhattan distance: {}", puzzle.manhattan_distance());
+}
+
+fn tour_command() {
+    if let Err(err) = tour::run(io::stdin().lock(), io::stdout()) {
+        eprintln!("Tour stopped: {}", err);
+        process::exit(1);
+    }
+}
+
+fn tune_command(args: &[String]) {
+    let config = parse_options(args, &["size", "count", "budget_secs", "save"]);
+    let size = config.parse_or("size", 4);
+    let count = config.parse_or("count", 5);
+    let budget = Duration::from_secs(config.parse_or("budget_secs", 60));
+
+    let samples: Vec<Puzzle> = (0..count)
+        .map(|_| {
+            let mut puzzle = solved_puzzle(size);
+            puzzle.shuffle();
+            puzzle
+        })
+        .collect();
+
+    let report = tune::tune(&samples, budget);
+    println!("{}", report);
+
+    if let Some(path) = config.get("save") {
+        let path = Path::new(path);
+        let mut saved = if path.exists() {
+            Config::load(path).unwrap_or_default()
+        } else {
+            Config::default()
+        };
+        if !report.apply_best(&mut saved) {
+            eprintln!("No setting finished within the budget, nothing saved");
+            process::exit(1);
+        }
+        if let Err(err) = saved.save(path) {
+            eprintln!("Could not save {}: {}", path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parses `--key value` pairs into a config. `--config path` loads a config
+/// file, and other keys must be listed in `keys`.
+fn parse_options(args: &[String], keys: &[&str]) -> Config {
+    let mut config = Config::default();
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1).cloned().unwrap_or_default();
+        match args[i].strip_prefix("--") {
+            Some("config") => {
+                config = Config::load(Path::new(&value)).unwrap_or_else(|err| {
+                    eprintln!("Could not read config {}: {}", value, err);
+                    process::exit(1);
+                });
+            }
+            Some(key) if keys.contains(&key) => config.set(key, &value),
+            _ => {
+                eprintln!("Unknown argument: {}", args[i]);
+                process::exit(1);
+            }
+        }
+        i += 2;
+    }
+    config
+}
+
+fn create_solver(config: &Config) -> (String, SolveFn) {
+    let registry = SolverRegistry::with_builtins();
+    let solver_name = config.get("solver").unwrap_or("ida-star");
+    let solver = registry.create(solver_name, config).unwrap_or_else(|| {
+        let names: Vec<&str> = registry.names().collect();
+        eprintln!(
+            "Unknown solver {}, available: {}",
+            solver_name,
+            names.join(", ")
+        );
+        process::exit(1);
+    });
+    if let Err(err) = check_optimal(solver_name, config) {
+        eprintln!(
+            "Cannot use solver {} with require_optimal: {}",
+            solver_name, err
+        );
+        process::exit(1);
+    }
+    (solver_name.to_string(), solver)
+}
+
+fn batch_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "input",
+            "output",
+            "checkpoint",
+            "every",
+            "solver",
+            "dedupe",
+            "quarantine",
+            "require_optimal",
+            "deterministic",
+            "pdb_file",
+            "pdb_partition",
+        ],
+    );
+    let Some(input) = config.get("input") else {
+        eprintln!(
+            "Usage: batch --input FILE [--output FILE] [--dedupe FILE] [--quarantine FILE] [--checkpoint FILE] [--every N] [--require_optimal true]"
+        );
+        process::exit(1);
+    };
+    let output = config.get("output");
+    if output.is_none() && config.get("dedupe").is_none() {
+        eprintln!("Nothing to do, pass --output and/or --dedupe");
+        process::exit(1);
+    }
+    let every = config
+        .get("every")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(100);
+    let checkpoint_path = config.get("checkpoint").map(Path::new);
+    let (_, solver) = create_solver(&config);
+
+    let text = fs::read_to_string(input).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", input, err);
+        process::exit(1);
+    });
+    let mut entries = batch::read_scrambles(&text);
+
+    // Unsolvable boards can keep the suboptimal engines busy until their node
+    // limit, so they are marked as failed before the batch starts.
+    let quarantined = batch::quarantine(&text);
+    for entry in &quarantined {
+        entries[entry.index] = Err(entry.reason);
+    }
+    if let Some(path) = config.get("quarantine") {
+        if let Err(err) = fs::write(path, batch::quarantine_csv(&quarantined)) {
+            eprintln!("Could not write {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+
+    let duplicates = dedup::find_duplicates(&entries);
+    for duplicate in &duplicates {
+        eprintln!(
+            "Entry {} {} entry {}, canonical form {}",
+            duplicate.index,
+            if duplicate.symmetric {
+                "mirrors"
+            } else {
+                "repeats"
+            },
+            duplicate.original,
+            curriculum::scramble_line(&duplicate.canonical)
+        );
+    }
+    if !duplicates.is_empty() {
+        eprintln!(
+            "{} of {} entries are duplicates",
+            duplicates.len(),
+            entries.len()
+        );
+    }
+
+    if let Some(path) = config.get("dedupe") {
+        if let Err(err) = fs::write(path, dedup::dedupe(&text, &duplicates)) {
+            eprintln!("Could not write {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+    let Some(output) = output else {
+        report_quarantine(&quarantined);
+        return;
+    };
+
+    let mut checkpoint = match checkpoint_path {
+        Some(path) => batch::Checkpoint::load(path).unwrap_or_else(|err| {
+            eprintln!("Could not read checkpoint: {}", err);
+            process::exit(1);
+        }),
+        None => batch::Checkpoint::default(),
+    };
+    if !checkpoint.is_empty() {
+        eprintln!(
+            "Resuming with {} of {} boards done",
+            checkpoint.len(),
+            entries.len()
+        );
+    }
+
+    let result = batch::run_batch(&entries, solver, &mut checkpoint, checkpoint_path, every)
+        .and_then(|_| fs::write(output, checkpoint.to_csv()));
+    if let Err(err) = result {
+        eprintln!("Batch failed: {}", err);
+        process::exit(1);
+    }
+    report_quarantine(&quarantined);
+}
+
+fn report_quarantine(quarantined: &[batch::Quarantined]) {
+    if quarantined.is_empty() {
+        return;
+    }
+
+    let mut reasons: Vec<(&str, usize)> = Vec::new();
+    for entry in quarantined {
+        match reasons
+            .iter_mut()
+            .find(|(reason, _)| *reason == entry.reason)
+        {
+            Some((_, count)) => *count += 1,
+            None => reasons.push((entry.reason, 1)),
+        }
+    }
+
+    eprintln!("{} entries quarantined:", quarantined.len());
+    for (reason, count) in reasons {
+        eprintln!("  {:>6}  {}", count, reason);
+    }
+}
+
+fn crossval_command(args: &[String]) {
+    let config = parse_options(args, &["oracle", "size", "count"]);
+    let size = config.get("size").and_then(|s| s.parse().ok()).unwrap_or(3);
+    let count = config
+        .get("count")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20);
+    let Some(path) = config.get("oracle") else {
+        eprintln!("No oracle configured, pass --oracle or set oracle in the config file");
+        process::exit(1);
+    };
+
+    let oracle = oracle::Oracle::new(Path::new(path));
+    let report = oracle.cross_validate(size, count).unwrap_or_else(|err| {
+        eprintln!("Could not run oracle {}: {}", path, err);
+        process::exit(1);
+    });
+
+    for discrepancy in &report.discrepancies {
+        println!(
+            "Mismatch: ours {:?}, oracle {}\n{}",
+            discrepancy.ours, discrepancy.theirs, discrepancy.puzzle
+        );
+    }
+    println!(
+        "Checked {} boards, {} mismatches",
+        report.checked,
+        report.discrepancies.len()
+    );
+    if !report.discrepancies.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn curriculum_command(args: &[String]) {
+    let config = parse_options(args, &["size", "distances", "count", "format"]);
+    let size = config.get("size").and_then(|s| s.parse().ok()).unwrap_or(3);
+    let count = config
+        .get("count")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10);
+    let distances: Vec<usize> = config
+        .get("distances")
+        .unwrap_or("5,10,15")
+        .split(',')
+        .filter_map(|s| s.trim().parse().ok())
+        .collect();
+
+    let curriculum =
+        curriculum::generate_curriculum(size, &distances, count).unwrap_or_else(|err| {
+            eprintln!("Invalid board size: {}", err);
+            process::exit(1);
+        });
+    if config.get("format") == Some("json") {
+        println!("{}", curriculum.to_json());
+    } else {
+        print!("{}", curriculum.to_scramble_file());
+    }
+}
+
+fn solve_command(args: &[String]) {
+    let config = parse_options(
+        args,
+        &[
+            "size",
+            "solver",
+            "require_optimal",
+            "deterministic",
+            "heuristic",
+            "table_bytes",
+            "pdb_file",
+            "pdb_partition",
+            "external_dir",
+            "external_buffer",
+            "glyphs",
+        ],
+    );
+
+    let (solver_name, solver) = create_solver(&config);
+    let glyphs = config.get("glyphs").map(|text| {
+        GlyphSet::parse(text).unwrap_or_else(|err| {
+            eprintln!("Invalid glyphs: {}", err);
+            process::exit(1);
+        })
+    });
+    let draw = |puzzle: &Puzzle| match &glyphs {
+        Some(glyphs) => glyphs.render(puzzle),
+        None => puzzle.to_string(),
+    };
+    let size = config.get("size").and_then(|s| s.parse().ok()).unwrap_or(4);
+
+    let mut puzzle = solved_puzzle(size);
+
+    println!("Initial Puzzle:\n{}", draw(&puzzle));
+
+    puzzle.shuffle();
+    let mut original = puzzle.clone();
+
+    println!("Shuffled Puzzle:\n{}", draw(&puzzle));
+
+    let output = solver(&puzzle).unwrap_or_else(|err| {
+        eprintln!("Could not solve: {}", err);
+        process::exit(1);
+    });
+    if solver_name == "ida-star" {
+        println!("Found optimal solution in with: {} moves", output.len());
+    } else {
+        println!("Found solution with: {} moves", output.len());
+    }
+    if let Ok(report) = bounds::compare(&puzzle, &output) {
+        println!("Bounds: {}", report);
+    }
+
+    for item in output {
+        original.apply_move(item);
+        println!("{}\n{}\n", item, draw(&original))
+    }
+}
@@ -0,0 +1,174 @@
+//! Guided tour of the crate for new users. Each scenario does its work
+//! through the same public API a program would use and names the calls it
+//! makes, so the tour doubles as documentation that cannot go stale.
+
+use slider_puzzle::catalog;
+use slider_puzzle::puzzle::Puzzle;
+use slider_puzzle::registry::DEFAULT_WEIGHT;
+use slider_puzzle::tablebase;
+use std::io::{self, BufRead, Write};
+
+/// Largest board the tour solves optimally; bigger ones use weighted A*.
+const MAX_OPTIMAL_SIZE: usize = 3;
+
+const MENU: &str = "
+What next?
+  1. Enter a board
+  2. Watch it solve
+  3. Compare heuristics
+  4. Generate a scramble
+  q. Quit";
+
+/// Runs the tour, reading choices from `input` until it ends or the user quits.
+pub fn run<R: BufRead, W: Write>(input: R, mut out: W) -> io::Result<()> {
+    let mut lines = input.lines();
+    let mut board = Puzzle::new(3).unwrap();
+    board.shuffle();
+
+    writeln!(out, "Welcome! Here is a shuffled 3x3 board to start with:")?;
+    writeln!(out, "{}", board)?;
+
+    loop {
+        writeln!(out, "{}", MENU)?;
+        write!(out, "> ")?;
+        out.flush()?;
+        let Some(choice) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        match choice.trim() {
+            "1" => enter_board(&mut lines, &mut out, &mut board)?,
+            "2" => watch_solve(&mut out, &board)?,
+            "3" => compare_heuristics(&mut out, &board)?,
+            "4" => generate_scramble(&mut lines, &mut out, &mut board)?,
+            "q" | "quit" => return Ok(()),
+            other => writeln!(out, "Unknown choice {:?}", other)?,
+        }
+    }
+}
+
+fn enter_board<I, W>(lines: &mut I, out: &mut W, board: &mut Puzzle) -> io::Result<()>
+where
+    I: Iterator<Item = io::Result<String>>,
+    W: Write,
+{
+    writeln!(
+        out,
+        "Type the tiles row by row with 0 for the empty space, then an empty line."
+    )?;
+    writeln!(out, "The one-line form 3:1,2,3,4,5,6,7,0,8 works too.")?;
+
+    let mut text = String::new();
+    for line in lines.by_ref() {
+        let line = line?;
+        if line.trim().is_empty() {
+            break;
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    writeln!(out, "API: text.parse::<Puzzle>()")?;
+    match text.parse::<Puzzle>() {
+        Ok(parsed) => {
+            *board = parsed;
+            writeln!(out, "Board set to:\n{}", board)?;
+            if !board.is_current_state_solvable() {
+                writeln!(
+                    out,
+                    "This board cannot be solved: swapping any two tiles would fix that."
+                )?;
+            }
+        }
+        Err(err) => writeln!(out, "Could not read that board: {}", err)?,
+    }
+    Ok(())
+}
+
+fn watch_solve<W: Write>(out: &mut W, board: &Puzzle) -> io::Result<()> {
+    let solution = if board.size() <= MAX_OPTIMAL_SIZE {
+        writeln!(out, "API: puzzle.solve_with_stats()")?;
+        let (solution, stats) = board.solve_with_stats();
+        writeln!(out, "{}", stats)?;
+        solution
+    } else {
+        writeln!(
+            out,
+            "Boards this large can take a long time to solve optimally, so this uses weighted A*."
+        )?;
+        writeln!(out, "API: puzzle.solve_weighted({})", DEFAULT_WEIGHT)?;
+        board.solve_weighted(DEFAULT_WEIGHT)
+    };
+
+    let moves = match solution {
+        Ok(moves) => moves,
+        Err(err) => return writeln!(out, "No solution: {}", err),
+    };
+    writeln!(out, "Solved in {} moves:", moves.len())?;
+    let mut state = board.clone();
+    for (i, movement) in moves.iter().enumerate() {
+        state.apply_move(*movement);
+        writeln!(out, "{}. {}\n{}", i + 1, movement, state)?;
+    }
+    Ok(())
+}
+
+fn compare_heuristics<W: Write>(out: &mut W, board: &Puzzle) -> io::Result<()> {
+    writeln!(
+        out,
+        "Heuristics estimate how many moves are left. Admissible ones never overestimate."
+    )?;
+    writeln!(out, "API: catalog::heuristics()")?;
+    for info in catalog::heuristics() {
+        if !info.sizes.contains(&board.size()) {
+            continue;
+        }
+        let estimate = match info.name {
+            "manhattan" => Some(board.manhattan_distance()),
+            "linear-conflict" => Some(board.heuristic()),
+            "tablebase" => tablebase::distance_to_goal(board),
+            _ => None,
+        };
+        if let Some(estimate) = estimate {
+            writeln!(out, "  {:>3}  {}", estimate, info)?;
+        }
+    }
+    if board.size() <= MAX_OPTIMAL_SIZE {
+        if let Ok(moves) = board.solve_optimal() {
+            writeln!(
+                out,
+                "Actual distance: {} (puzzle.solve_optimal())",
+                moves.len()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn generate_scramble<I, W>(lines: &mut I, out: &mut W, board: &mut Puzzle) -> io::Result<()>
+where
+    I: Iterator<Item = io::Result<String>>,
+    W: Write,
+{
+    write!(out, "Board size [{}]: ", board.size())?;
+    out.flush()?;
+    let answer = lines.next().transpose()?.unwrap_or_default();
+    let size = match answer.trim() {
+        "" => board.size(),
+        text => match text.parse() {
+            Ok(size) => size,
+            Err(_) => return writeln!(out, "Not a number: {}", text),
+        },
+    };
+
+    writeln!(out, "API: Puzzle::new(size)?.shuffle()")?;
+    match Puzzle::new(size) {
+        Ok(mut scramble) => {
+            scramble.shuffle();
+            *board = scramble;
+            writeln!(out, "New scramble:\n{}", board)?;
+            writeln!(out, "To share it, use the one-line form: {:#}", board)?;
+        }
+        Err(err) => writeln!(out, "Cannot make that board: {}", err)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use slider_puzzle::progress::Progress;
+use slider_puzzle::puzzle::{Move, Puzzle};
+use std::io::{self, Stdout, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Engines that report node counts and so can take part in a duel.
+pub const DUEL_ENGINES: [&str; 3] = ["ida-star", "weighted-a-star", "beam"];
+
+const FRAME: Duration = Duration::from_millis(150);
+const COLUMN_WIDTH: u16 = 32;
+
+/// The engine's outcome and how long it took, once it has finished.
+type SharedResult = Arc<Mutex<Option<(Result<Vec<Move>, &'static str>, Duration)>>>;
+
+struct Contestant {
+    engine: &'static str,
+    nodes: Arc<AtomicU64>,
+    result: SharedResult,
+    board: Puzzle,
+    step: usize,
+}
+
+/// Restores the terminal when the duel ends, even on error.
+struct Screen(Stdout);
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self(stdout))
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.0, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn run_engine(
+    engine: &str,
+    puzzle: &Puzzle,
+    nodes: &AtomicU64,
+    stop: &AtomicBool,
+) -> Result<Vec<Move>, &'static str> {
+    let callback = |progress: &Progress| {
+        nodes.store(progress.nodes, Ordering::Relaxed);
+        !stop.load(Ordering::Relaxed)
+    };
+
+    match engine {
+        "ida-star" => puzzle.solve_with_progress(callback),
+        "weighted-a-star" => puzzle.solve_weighted_with_progress(2.0, callback),
+        "beam" => puzzle.solve_beam_with_progress(1000, callback),
+        _ => Err("Engine cannot take part in a duel"),
+    }
+}
+
+/// Solves `puzzle` with two engines at once, showing both boards side by
+/// side with live node counts and animating each solution as soon as it is
+/// found. Press `q` or Esc to leave early.
+pub fn duel(puzzle: &Puzzle, left: &'static str, right: &'static str) -> io::Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let start = Instant::now();
+
+    let mut contestants: Vec<Contestant> = [left, right]
+        .into_iter()
+        .map(|engine| {
+            let contestant = Contestant {
+                engine,
+                nodes: Arc::new(AtomicU64::new(0)),
+                result: Arc::new(Mutex::new(None)),
+                board: puzzle.clone(),
+                step: 0,
+            };
+
+            let (nodes, result, stop) = (
+                contestant.nodes.clone(),
+                contestant.result.clone(),
+                stop.clone(),
+            );
+            let puzzle = puzzle.clone();
+            thread::spawn(move || {
+                let outcome = run_engine(engine, &puzzle, &nodes, &stop);
+                *result.lock().unwrap() = Some((outcome, start.elapsed()));
+            });
+
+            contestant
+        })
+        .collect();
+
+    let mut screen = Screen::enter()?;
+    let mut finished = false;
+
+    loop {
+        for contestant in &mut contestants {
+            let result = contestant.result.lock().unwrap();
+            if let Some((Ok(moves), _)) = result.as_ref() {
+                if let Some(&item) = moves.get(contestant.step) {
+                    contestant.board.apply_move(item);
+                    contestant.step += 1;
+                }
+            }
+        }
+
+        finished = finished || contestants.iter().all(Contestant::is_done);
+        draw(&mut screen.0, &contestants, start, finished)?;
+
+        if event::poll(FRAME)? {
+            if let Event::Key(key) = event::read()? {
+                if finished || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+impl Contestant {
+    fn is_done(&self) -> bool {
+        match self.result.lock().unwrap().as_ref() {
+            Some((Ok(moves), _)) => self.step == moves.len(),
+            Some((Err(_), _)) => true,
+            None => false,
+        }
+    }
+
+    fn status(&self, start: Instant) -> String {
+        match self.result.lock().unwrap().as_ref() {
+            Some((Ok(moves), elapsed)) => format!(
+                "Solved: {} moves in {:.2}s ({}/{})",
+                moves.len(),
+                elapsed.as_secs_f64(),
+                self.step,
+                moves.len()
+            ),
+            Some((Err(err), _)) => format!("Failed: {}", err),
+            None => format!("Solving... {:.1}s", start.elapsed().as_secs_f64()),
+        }
+    }
+}
+
+fn draw(
+    stdout: &mut Stdout,
+    contestants: &[Contestant],
+    start: Instant,
+    finished: bool,
+) -> io::Result<()> {
+    queue!(stdout, terminal::Clear(ClearType::All))?;
+
+    for (i, contestant) in contestants.iter().enumerate() {
+        let x = i as u16 * COLUMN_WIDTH;
+        let nodes = contestant.nodes.load(Ordering::Relaxed);
+        let board = contestant.board.to_string();
+
+        let mut lines = vec![
+            contestant.engine.to_string(),
+            format!("Nodes: {}", nodes),
+            contestant.status(start),
+            String::new(),
+        ];
+        lines.extend(board.lines().map(str::to_string));
+
+        for (y, line) in lines.iter().enumerate() {
+            queue!(stdout, cursor::MoveTo(x, y as u16), Print(line))?;
+        }
+    }
+
+    let footer = if finished {
+        "Press any key to exit"
+    } else {
+        "Press q to quit"
+    };
+    let y = 5 + contestants[0].board.rows() as u16;
+    queue!(stdout, cursor::MoveTo(0, y), Print(footer))?;
+
+    stdout.flush()
+}
@@ -0,0 +1,6 @@
+/* The LM3S6965 emulated by QEMU; adjust for your board */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 256K
+  RAM : ORIGIN = 0x20000000, LENGTH = 64K
+}
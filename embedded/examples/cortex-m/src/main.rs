@@ -0,0 +1,37 @@
+//! Solves one 3x3 board on a Cortex-M core and prints the moves through
+//! semihosting. Generates the table in RAM at startup; to keep it in flash
+//! instead, write it on the host with
+//! `slider-puzzle tablebase --export_embedded table.bin` and replace the
+//! `singleton!` with
+//! `Solver::from_table(include_bytes!("../table.bin"))`.
+//!
+//! Run in QEMU with `cargo run --release`.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::{debug, hprintln};
+use panic_halt as _;
+use slider_puzzle_embedded::{Move, Solver, MAX_SOLUTION, TABLE_BYTES};
+
+#[entry]
+fn main() -> ! {
+    let table = cortex_m::singleton!(: [u8; TABLE_BYTES] = [0; TABLE_BYTES]).unwrap();
+    let solver = Solver::generate(table);
+
+    let board = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+    let mut moves = [Move::Up; MAX_SOLUTION];
+    match solver.solve(&board, &mut moves) {
+        Some(length) => {
+            hprintln!("Solved in {} moves:", length);
+            for movement in &moves[..length] {
+                hprintln!("{:?}", movement);
+            }
+        }
+        None => hprintln!("The board cannot be solved"),
+    }
+
+    debug::exit(debug::EXIT_SUCCESS);
+    loop {}
+}
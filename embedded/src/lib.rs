@@ -0,0 +1,225 @@
+//! An optimal 3x3 solver for microcontrollers.
+//!
+//! Uses only `core`, never allocates, and keeps a 45,360-byte table of the
+//! distance of every solvable state to the goal, modulo 3 and packed two
+//! bits to a state. Every move changes the distance by exactly one, so the
+//! neighbour whose entry is one less, modulo 3, is a step along a shortest
+//! solution.
+//!
+//! The table can be generated on the device with `Solver::generate`, which
+//! needs it in RAM, or written by the host with
+//! `slider-puzzle tablebase --export_embedded FILE` and placed in flash with
+//! `include_bytes!` for `Solver::from_table`. Build with
+//! `cargo build --profile embedded` for the smallest code.
+
+#![no_std]
+
+/// Cells of the board, row by row.
+pub const CELLS: usize = 9;
+
+/// Solvable arrangements: half of the 9! arrangements of the cells.
+pub const STATES: usize = 181_440;
+
+/// Size of the table, at two bits per state.
+pub const TABLE_BYTES: usize = STATES / 4;
+
+/// Moves in the longest optimal 3x3 solution.
+pub const MAX_SOLUTION: usize = 31;
+
+/// Tile values row by row, with 0 for the empty space. The goal is 1 to 8
+/// in order with the empty space last.
+pub type Board = [u8; CELLS];
+
+const GOAL: Board = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+/// Table entry of a state not yet reached while generating.
+const UNSEEN: u8 = 3;
+
+/// Named, as in `slider_puzzle::Move`, after the direction a tile slides
+/// into the empty space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+impl Move {
+    pub const ALL: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+}
+
+pub struct Solver<'a> {
+    table: &'a [u8; TABLE_BYTES],
+}
+
+impl<'a> Solver<'a> {
+    /// Fills `table` by sweeping over all states once per move of the
+    /// longest solution, extending every state at the current distance.
+    /// Needs no memory beyond the table, at the cost of about 32 sweeps, so
+    /// expect it to take a while on a slow core.
+    pub fn generate(table: &'a mut [u8; TABLE_BYTES]) -> Self {
+        table.fill(0xff);
+        set(table, compact_rank(&GOAL), 0);
+
+        let mut depth = 0;
+        loop {
+            let mut grew = false;
+            for index in 0..STATES {
+                // Entries from three moves earlier match too, but all their
+                // neighbours have been reached already
+                if get(table, index) != depth % 3 {
+                    continue;
+                }
+                let board = unrank(index);
+                for movement in Move::ALL {
+                    if let Some(next) = moved(&board, movement) {
+                        let next = compact_rank(&next);
+                        if get(table, next) == UNSEEN {
+                            set(table, next, (depth + 1) % 3);
+                            grew = true;
+                        }
+                    }
+                }
+            }
+            if !grew {
+                break;
+            }
+            depth += 1;
+        }
+        Self { table }
+    }
+
+    /// Uses a table made earlier by `generate`, for example in flash.
+    pub fn from_table(table: &'a [u8; TABLE_BYTES]) -> Self {
+        Self { table }
+    }
+
+    pub fn table(&self) -> &[u8; TABLE_BYTES] {
+        self.table
+    }
+
+    /// Writes a shortest solution to the start of `moves` and returns its
+    /// length, or `None` if `board` is not an arrangement of the tiles 0 to
+    /// 8 that can be solved.
+    pub fn solve(&self, board: &Board, moves: &mut [Move; MAX_SOLUTION]) -> Option<usize> {
+        if !is_solvable(board) {
+            return None;
+        }
+        let mut board = *board;
+        let mut distance = get(self.table, compact_rank(&board));
+        let mut length = 0;
+        while board != GOAL {
+            let closer = (distance + 2) % 3;
+            let (movement, next) = Move::ALL.into_iter().find_map(|movement| {
+                let next = moved(&board, movement)?;
+                (get(self.table, compact_rank(&next)) == closer).then_some((movement, next))
+            })?;
+            *moves.get_mut(length)? = movement;
+            length += 1;
+            board = next;
+            distance = closer;
+        }
+        Some(length)
+    }
+}
+
+fn get(table: &[u8; TABLE_BYTES], index: usize) -> u8 {
+    table[index / 4] >> (index % 4 * 2) & 0b11
+}
+
+fn set(table: &mut [u8; TABLE_BYTES], index: usize, value: u8) {
+    let shift = index % 4 * 2;
+    table[index / 4] = table[index / 4] & !(0b11 << shift) | value << shift;
+}
+
+/// Holds each tile once and has an even number of inversions, which on a
+/// board of odd width is exactly the solvable arrangements.
+fn is_solvable(board: &Board) -> bool {
+    let mut seen = [false; CELLS];
+    for &value in board {
+        match seen.get_mut(value as usize) {
+            Some(slot) if !*slot => *slot = true,
+            _ => return false,
+        }
+    }
+    let mut inversions = 0;
+    for i in 0..CELLS {
+        for j in i + 1..CELLS {
+            if board[i] != 0 && board[j] != 0 && board[j] < board[i] {
+                inversions += 1;
+            }
+        }
+    }
+    inversions % 2 == 0
+}
+
+/// The board after `movement`, or `None` if no tile can slide that way.
+fn moved(board: &Board, movement: Move) -> Option<Board> {
+    let blank = board.iter().position(|&value| value == 0)?;
+    let (row, col) = (blank / 3, blank % 3);
+    let tile = match movement {
+        Move::Up => (row < 2).then(|| blank + 3),
+        Move::Left => (col < 2).then(|| blank + 1),
+        Move::Down => (row > 0).then(|| blank - 3),
+        Move::Right => (col > 0).then(|| blank - 1),
+    }?;
+    let mut next = *board;
+    next.swap(blank, tile);
+    Some(next)
+}
+
+/// The blank cell, then the Lehmer rank of the eight tiles halved, as in
+/// `slider_puzzle::tablebase`: `2k` and `2k + 1` differ only by swapping the
+/// last two tiles, so exactly one of them is solvable.
+fn compact_rank(board: &Board) -> usize {
+    let blank = board.iter().position(|&value| value == 0).unwrap_or(0);
+    let mut tiles = [0; CELLS - 1];
+    let mut next = 0;
+    for &value in board {
+        if value != 0 {
+            tiles[next] = value;
+            next += 1;
+        }
+    }
+
+    let mut index = 0;
+    for i in 0..tiles.len() {
+        let smaller = tiles[i + 1..]
+            .iter()
+            .filter(|&&value| value < tiles[i])
+            .count();
+        index = index * (tiles.len() - i) + smaller;
+    }
+    blank * (STATES / CELLS) + index / 2
+}
+
+/// The solvable board with the given `compact_rank`.
+fn unrank(index: usize) -> Board {
+    let blank = index / (STATES / CELLS);
+    let mut lehmer = index % (STATES / CELLS) * 2;
+
+    // Digits of the Lehmer code, most significant first
+    let mut digits = [0; CELLS - 1];
+    for (i, digit) in digits.iter_mut().enumerate().rev() {
+        let base = CELLS - 1 - i;
+        *digit = lehmer % base;
+        lehmer /= base;
+    }
+    // The digits sum to the number of inversions, which must be even
+    if digits.iter().sum::<usize>() % 2 == 1 {
+        digits[CELLS - 3] ^= 1;
+    }
+
+    let mut unused = [1, 2, 3, 4, 5, 6, 7, 8];
+    let mut left = unused.len();
+    let mut board = [0; CELLS];
+    let mut tiles = (0..CELLS).filter(|&cell| cell != blank);
+    for digit in digits {
+        let cell = tiles.next().unwrap_or(0);
+        board[cell] = unused[digit];
+        unused.copy_within(digit + 1..left, digit);
+        left -= 1;
+    }
+    board
+}
@@ -0,0 +1,64 @@
+//! Boards built from arbitrary dimensions and tile values, including ones
+//! near `u32::MAX`, then moved and analysed. Construction must reject
+//! invalid boards, and everything after it must cope with any valid one.
+//!
+//! Run with `cargo fuzz run board` from this directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use slider_puzzle::orientation::Orientation;
+use slider_puzzle::solution::Solution;
+use slider_puzzle::{dedup, explain, heuristic, strip, tablebase, Move, Puzzle};
+
+/// Larger boards make the exact searches below too slow to fuzz.
+const MAX_SOLVED_CELLS: usize = 9;
+
+fuzz_target!(|data: &[u8]| {
+    let [rows, cols, tile_count, rest @ ..] = data else {
+        return;
+    };
+    let (rows, cols) = (*rows as usize % 6, *cols as usize % 6);
+    let tile_bytes = (*tile_count as usize * 4).min(rest.len() & !3);
+    let tiles: Vec<u32> = rest[..tile_bytes]
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    let moves: Vec<Move> = rest[tile_bytes..]
+        .iter()
+        .map(|&byte| Move::ALL[byte as usize % 4])
+        .collect();
+
+    if rows == 2 {
+        let _ = strip::solve_strip(cols, &tiles);
+    }
+    let Ok(start) = Puzzle::from_tiles_rectangular(rows, cols, &tiles) else {
+        return;
+    };
+
+    let mut end = start.clone();
+    let _ = end.apply_moves(&moves);
+    let _ = end.random_walk(moves.len());
+    let solution = Solution::new(moves);
+    let _ = solution.tile_stats(&start);
+    let _ = solution.to_json(&start);
+
+    for name in heuristic::NAMES {
+        let _ = heuristic::by_name(name).map(|estimate| estimate.estimate(&start));
+    }
+    let _ = explain::explain(&start);
+    let _ = dedup::canonical_form(&start);
+    for quarter_turns in 0..4 {
+        let _ = Orientation::new(quarter_turns, false).apply(&start);
+        let _ = Orientation::new(quarter_turns, true).apply(&start);
+    }
+    let _ = tablebase::distance_between(&start, &end);
+
+    if let Ok(with_goal) = start.clone().with_goal(&end) {
+        let _ = with_goal.is_current_state_solvable();
+        let _ = with_goal.heuristic();
+    }
+    if rows >= 2 && cols >= 2 && rows * cols <= MAX_SOLVED_CELLS {
+        let _ = start.solve();
+    }
+});
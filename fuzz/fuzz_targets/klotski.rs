@@ -0,0 +1,43 @@
+//! Block boards with arbitrary piece sizes and positions, up to
+//! `usize::MAX`. Construction must reject pieces off the board without
+//! overflowing, and moves must never index outside it.
+//!
+//! Run with `cargo fuzz run klotski` from this directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use slider_puzzle::klotski::{BlockBoard, Piece, PieceMove};
+use slider_puzzle::Move;
+
+fuzz_target!(|data: &[u8]| {
+    let mut values = data
+        .chunks_exact(8)
+        .map(|bytes| usize::from_le_bytes(bytes.try_into().unwrap()));
+    let (Some(rows), Some(cols)) = (values.next(), values.next()) else {
+        return;
+    };
+    let numbers: Vec<usize> = values.collect();
+    let pieces = numbers
+        .chunks_exact(4)
+        .map(|piece| Piece::new(piece[0], piece[1], piece[2], piece[3]))
+        .collect();
+
+    let Ok(board) = BlockBoard::new(rows, cols, pieces) else {
+        return;
+    };
+    let mut moved = board.clone();
+    for (index, &number) in numbers.iter().enumerate() {
+        let movement = PieceMove {
+            piece: number,
+            direction: Move::ALL[index % 4],
+        };
+        let _ = moved.apply_move(movement);
+        let _ = moved.piece_at(number, index);
+    }
+    if let [piece, row, col, ..] = numbers[..] {
+        let _ = board.with_target(piece, row, col);
+    }
+    let _ = moved.legal_moves();
+    let _ = moved.to_string();
+});
@@ -0,0 +1,48 @@
+//! Every text format the crate reads, fed arbitrary text. Parsers must
+//! reject bad input with an error, never panic.
+//!
+//! Run with `cargo fuzz run parse` from this directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use slider_puzzle::config::Config;
+use slider_puzzle::json::Json;
+use slider_puzzle::klotski::BlockBoard;
+use slider_puzzle::mapping::TileMapping;
+use slider_puzzle::reverse::MovePattern;
+use slider_puzzle::{batch, dedup, pdb, schema, Move, Puzzle};
+
+fuzz_target!(|text: &str| {
+    if let Ok(puzzle) = text.parse::<Puzzle>() {
+        let _ = format!("{puzzle}{puzzle:#}");
+    }
+    let _ = text.parse::<Move>();
+    let _ = text.parse::<MovePattern>();
+    if let Ok(board) = text.parse::<BlockBoard>() {
+        let _ = board.to_string();
+    }
+    if let Ok(document) = Json::parse(text) {
+        let _ = schema::migrate(document);
+    }
+    let _ = Config::parse(text);
+    let _ = pdb::parse_partition(text);
+
+    for mapping in [
+        TileMapping::Standard,
+        TileMapping::zero_based("_"),
+        TileMapping::letters("."),
+        TileMapping::Hex,
+        TileMapping::Base36,
+        TileMapping::coordinates("."),
+    ] {
+        if let Ok(puzzle) = mapping.parse(text) {
+            let _ = mapping.format(&puzzle);
+        }
+    }
+
+    let entries = batch::read_scrambles(text);
+    let duplicates = dedup::find_duplicates(&entries);
+    let _ = dedup::dedupe(text, &duplicates);
+    let _ = batch::quarantine_csv(&batch::quarantine(text));
+});
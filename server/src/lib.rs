@@ -0,0 +1,164 @@
+//! Answers HTTP requests for the solver with JSON documents, for services
+//! that would rather call the solver over the network than link it.
+//!
+//! Every endpoint takes the board as the `board` query parameter, in any
+//! form `Puzzle` parses, and answers with a document from
+//! `slider_puzzle::schema`:
+//!
+//! - `GET /parse` gives the board's size, tiles and canonical form.
+//! - `GET /solve` gives a solution, found within `budget_ms` milliseconds.
+//! - `GET /hint` gives the best next move found within `budget_ms`.
+//!
+//! Failures are an `error` document with a message, and status 400 for a
+//! bad request or 404 for an unknown path.
+
+use slider_puzzle::hints::HintQuality;
+use slider_puzzle::json::Json;
+use slider_puzzle::puzzle::Puzzle;
+use slider_puzzle::schema;
+use slider_puzzle::solution::Solution;
+use std::time::Duration;
+
+/// Time spent on `/solve` and `/hint` when the request gives no budget.
+pub const DEFAULT_BUDGET_MS: u64 = 2000;
+
+/// Most time a request may ask for, so one client cannot hold a worker.
+pub const MAX_BUDGET_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Json,
+}
+
+impl Response {
+    fn ok(body: Json) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: schema::document("error", [("message", Json::from(message))]),
+        }
+    }
+
+    /// The reason phrase for the status line.
+    pub fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Error",
+        }
+    }
+}
+
+/// The response to a request, given its first line such as
+/// `GET /solve?board=... HTTP/1.1`.
+pub fn respond(request_line: &str) -> Response {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Response::error(400, "Malformed request line");
+    };
+    if method != "GET" {
+        return Response::error(405, "Only GET requests are answered");
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let route = match path {
+        "/parse" => parse,
+        "/solve" => solve,
+        "/hint" => hint,
+        _ => return Response::error(404, "Unknown path"),
+    };
+    match Request::from_query(query).and_then(route) {
+        Ok(body) => Response::ok(body),
+        Err(err) => Response::error(400, err),
+    }
+}
+
+struct Request {
+    puzzle: Puzzle,
+    budget: Duration,
+}
+
+impl Request {
+    fn from_query(query: &str) -> Result<Self, &'static str> {
+        let mut board = None;
+        let mut budget_ms = DEFAULT_BUDGET_MS;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            match key {
+                "board" => board = Some(value),
+                "budget_ms" => {
+                    budget_ms = value.parse().map_err(|_| "Invalid budget_ms")?;
+                }
+                _ => {}
+            }
+        }
+        let board = board.ok_or("Missing board")?;
+        Ok(Self {
+            puzzle: board.parse()?,
+            budget: Duration::from_millis(budget_ms.min(MAX_BUDGET_MS)),
+        })
+    }
+}
+
+fn parse(request: Request) -> Result<Json, &'static str> {
+    let puzzle = request.puzzle;
+    Ok(schema::document(
+        "board",
+        [
+            ("rows", Json::from(puzzle.rows())),
+            ("cols", Json::from(puzzle.cols())),
+            ("tiles", Json::from(puzzle.tiles())),
+            ("canonical", Json::from(format!("{:#}", puzzle))),
+        ],
+    ))
+}
+
+fn solve(request: Request) -> Result<Json, &'static str> {
+    let solution = request.puzzle.solve_adaptive(request.budget)?;
+    Ok(Solution::new(solution.moves).to_json(&request.puzzle))
+}
+
+fn hint(request: Request) -> Result<Json, &'static str> {
+    let hint = request
+        .puzzle
+        .hint_within(request.budget)
+        .ok_or("No move helps: the board is solved or cannot be solved")?;
+    let quality = match hint.quality {
+        HintQuality::Optimal => "optimal",
+        HintQuality::HeuristicBest => "heuristic_best",
+        HintQuality::Greedy => "greedy",
+    };
+    Ok(schema::document(
+        "hint",
+        [
+            ("move", Json::from(hint.movement.to_string())),
+            ("quality", Json::from(quality)),
+        ],
+    ))
+}
+
+/// Decodes `%XX` escapes and `+` for spaces, as browsers write query values.
+fn percent_decode(text: &str) -> Result<String, &'static str> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(byte) = rest.next() {
+        bytes.push(match byte {
+            b'+' => b' ',
+            b'%' => {
+                let mut digit = || rest.next().and_then(|byte| (byte as char).to_digit(16));
+                match (digit(), digit()) {
+                    (Some(high), Some(low)) => (high * 16 + low) as u8,
+                    _ => return Err("Invalid escape in query"),
+                }
+            }
+            byte => byte,
+        });
+    }
+    String::from_utf8(bytes).map_err(|_| "Query is not UTF-8")
+}
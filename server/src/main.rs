@@ -0,0 +1,67 @@
+//! Serves the solver over HTTP on the address given as the only argument,
+//! 127.0.0.1:8080 by default. See the library for the endpoints.
+
+use slider_server::respond;
+use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Longest request head read, so a client cannot make the server buffer
+/// without end.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let listener = TcpListener::bind(&address).unwrap_or_else(|err| {
+        eprintln!("Could not listen on {}: {}", address, err);
+        process::exit(1);
+    });
+    eprintln!("Listening on {}", address);
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        thread::spawn(move || {
+            if let Err(err) = handle(stream) {
+                eprintln!("Request failed: {}", err);
+            }
+        });
+    }
+}
+
+/// Answers one request and closes the connection.
+fn handle(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_HEAD_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but are read so the client sees its
+    // request accepted before the connection closes
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+
+    let response = respond(&request_line);
+    let body = response.body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.reason(),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
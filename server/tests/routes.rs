@@ -0,0 +1,51 @@
+//! Requests answered without a socket, through `respond`.
+
+use slider_puzzle::json::Json;
+use slider_server::respond;
+
+fn field<'a>(body: &'a Json, key: &str) -> Option<&'a str> {
+    body.get(key).and_then(Json::as_str)
+}
+
+#[test]
+fn solves_and_hints() {
+    let solved = respond("GET /solve?board=3%3A1,2,3,4,5,6,0,7,8 HTTP/1.1");
+    assert_eq!(solved.status, 200);
+    assert_eq!(field(&solved.body, "kind"), Some("solution"));
+    assert_eq!(solved.body.get("length").and_then(Json::as_f64), Some(2.0));
+
+    let hint = respond("GET /hint?board=1+2+3+4+5+6+0+7+8&budget_ms=100 HTTP/1.1");
+    assert_eq!(hint.status, 200);
+    assert_eq!(field(&hint.body, "move"), Some("Left"));
+    assert_eq!(field(&hint.body, "quality"), Some("optimal"));
+}
+
+#[test]
+fn parse_gives_the_canonical_form() {
+    let parsed = respond("GET /parse?board=1,2,3,4,5,6,7,8,0 HTTP/1.1");
+    assert_eq!(parsed.status, 200);
+    assert_eq!(
+        field(&parsed.body, "canonical"),
+        Some("3:1,2,3,4,5,6,7,8,0")
+    );
+}
+
+#[test]
+fn bad_requests_get_errors() {
+    for (request, status) in [
+        ("GET /solve?board=1,2,3,4,5,6,8,7,0 HTTP/1.1", 400),
+        ("GET /solve HTTP/1.1", 400),
+        ("GET /solve?board=%zz HTTP/1.1", 400),
+        (
+            "GET /solve?board=3:1,2,3,4,5,6,7,8,0&budget_ms=soon HTTP/1.1",
+            400,
+        ),
+        ("GET /elsewhere HTTP/1.1", 404),
+        ("POST /solve HTTP/1.1", 405),
+        ("", 400),
+    ] {
+        let response = respond(request);
+        assert_eq!(response.status, status, "{}", request);
+        assert_eq!(field(&response.body, "kind"), Some("error"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::clock::Instant;
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use std::time::Duration;
+
+const FALLBACK_WEIGHT: f64 = 2.0;
+const FALLBACK_BEAM_WIDTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    IdaStar,
+    WeightedAStar,
+    Beam,
+}
+
+/// When `solve_adaptive` gives up on the optimal search. The fallback
+/// engines are held to the same budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    /// Once the projected finishing time exceeds this. Which engine answers
+    /// depends on how fast the machine is. The fallbacks share what is left.
+    Time(Duration),
+    /// Once this many nodes have been expanded. Slower, since it cannot
+    /// abandon a search early, but the same board always gets the same
+    /// solution on every machine. Each fallback may expand as many.
+    Nodes(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveSolution {
+    pub moves: Vec<Move>,
+    /// The engine that produced `moves`.
+    pub engine: Engine,
+    /// Only solutions from IDA* are guaranteed to be optimal.
+    pub optimal: bool,
+    pub elapsed: Duration,
+}
+
+/// Outcome of an optimal search under a time limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedSolution {
+    /// The optimal solution if the search finished, otherwise the best
+    /// suboptimal one the fallback engines found in time, if any.
+    pub moves: Option<Vec<Move>>,
+    /// No solution is shorter than this: every IDA* bound below it was
+    /// searched exhaustively. This relies on the heuristic being admissible.
+    pub lower_bound: usize,
+    /// Whether `moves` is known to be optimal, either because IDA* finished
+    /// or because the fallback solution meets the lower bound.
+    pub optimal: bool,
+    pub elapsed: Duration,
+}
+
+impl Puzzle {
+    /// Starts an optimal IDA* search and watches its progress. If the
+    /// projected finishing time exceeds `budget`, the search is abandoned in
+    /// favour of weighted A*, and beam search as a last resort, in whatever
+    /// time is left. Fails if none of them finishes within `budget`.
+    pub fn solve_adaptive(&self, budget: Duration) -> Result<AdaptiveSolution, &'static str> {
+        self.solve_adaptive_within(Budget::Time(budget))
+    }
+
+    /// Like `solve_adaptive`, with the budget given as either a time or a
+    /// node count.
+    pub fn solve_adaptive_within(&self, budget: Budget) -> Result<AdaptiveSolution, &'static str> {
+        let start = Instant::now();
+
+        let optimal = self.solve_with_progress(|progress| match budget {
+            Budget::Time(limit) => {
+                let projected = progress.elapsed + progress.eta.unwrap_or_default();
+                projected <= limit
+            }
+            Budget::Nodes(limit) => progress.nodes <= limit,
+        });
+
+        let (moves, engine) = match optimal {
+            Ok(moves) => (moves, Engine::IdaStar),
+            Err("Search cancelled") => self
+                .solve_fallback(budget, start)
+                .ok_or("No solution found within the budget")?,
+            Err(err) => return Err(err),
+        };
+
+        Ok(AdaptiveSolution {
+            moves,
+            engine,
+            optimal: engine == Engine::IdaStar,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Runs IDA* for at most half of `limit`. If it times out, reports the
+    /// highest bound reached as a lower bound on the solution length,
+    /// alongside the best solution the fallback engines find in the rest of
+    /// `limit`.
+    pub fn solve_within(&self, limit: Duration) -> Result<BoundedSolution, &'static str> {
+        let start = Instant::now();
+        let mut lower_bound = self.heuristic();
+
+        // The optimal search gets half the budget so the fallback has time too
+        let optimal = self.solve_with_progress(|progress| {
+            lower_bound = lower_bound.max(progress.bound);
+            start.elapsed() <= limit / 2
+        });
+
+        match optimal {
+            Ok(moves) => Ok(BoundedSolution {
+                lower_bound: moves.len(),
+                moves: Some(moves),
+                optimal: true,
+                elapsed: start.elapsed(),
+            }),
+            Err("Search cancelled") => {
+                let moves = self
+                    .solve_fallback(Budget::Time(limit), start)
+                    .map(|(moves, _)| moves);
+                Ok(BoundedSolution {
+                    optimal: moves
+                        .as_ref()
+                        .is_some_and(|moves| moves.len() == lower_bound),
+                    moves,
+                    lower_bound,
+                    elapsed: start.elapsed(),
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Weighted A*, then beam search if that fails, within what is left of
+    /// `budget` since `start`. Weighted A* gets half the time left.
+    fn solve_fallback(&self, budget: Budget, start: Instant) -> Option<(Vec<Move>, Engine)> {
+        let deadline = match budget {
+            Budget::Time(limit) => start + limit,
+            Budget::Nodes(_) => start,
+        };
+        let halfway = Instant::now() + deadline.saturating_duration_since(Instant::now()) / 2;
+        let within = |deadline: Instant| {
+            move |progress: &Progress| match budget {
+                Budget::Time(_) => Instant::now() <= deadline,
+                Budget::Nodes(limit) => progress.nodes <= limit,
+            }
+        };
+
+        if let Ok(moves) = self.solve_weighted_with_progress(FALLBACK_WEIGHT, within(halfway)) {
+            return Some((moves, Engine::WeightedAStar));
+        }
+        self.solve_beam_with_progress(FALLBACK_BEAM_WIDTH, within(deadline))
+            .ok()
+            .map(|moves| (moves, Engine::Beam))
+    }
+}
@@ -0,0 +1,201 @@
+use crate::heuristic::{Heuristic, LinearConflict, Manhattan};
+use crate::progress::{Progress, ProgressTracker};
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, Search, SearchStatus, HEURISTIC_GOAL_MISMATCH};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Upper limit on stored states. Every state is kept until the search ends,
+/// so A* is only practical for 3x3 and easy 4x4 boards.
+const MAX_STATES: usize = 2_000_000;
+
+struct Node {
+    state: Puzzle,
+    parent: Option<usize>,
+    movement: Option<Move>,
+    g: usize,
+    /// Expanded already; in the closed set.
+    closed: bool,
+}
+
+impl Puzzle {
+    /// Classic A*: expands states in order of `g + h` and never expands a
+    /// state twice. Unlike IDA*, which re-expands states in every iteration
+    /// and along every path that reaches them, this remembers every state it
+    /// has seen, so it needs memory for all of them. Searches with the same
+    /// heuristic as `solve`.
+    pub fn solve_astar(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_astar_with_progress(|_| true)
+    }
+
+    /// Like `solve_astar`, reporting progress to `callback`; returning
+    /// `false` from the callback cancels the search. `bound` in the reports
+    /// is the f-value currently being expanded.
+    pub fn solve_astar_with_progress<F>(&self, callback: F) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(AStarSearch::new(self)?, callback)
+    }
+
+    /// Like `solve_astar`, but with the Manhattan distance alone; see
+    /// `solve_optimal`.
+    pub fn solve_astar_optimal_with_progress<F>(
+        &self,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(AStarSearch::with_heuristic(self, Manhattan)?, callback)
+    }
+}
+
+/// Resumable state of an A* search; see `Puzzle::solve_astar`.
+pub struct AStarSearch {
+    heuristic: Box<dyn Heuristic>,
+    nodes: Vec<Node>,
+    index: HashMap<Puzzle, usize>,
+    /// Lowest f first, then deepest g, so ties favour states nearer the goal.
+    /// Entries go stale when a shorter path to their state is found.
+    open: BinaryHeap<(Reverse<usize>, usize, usize)>,
+    /// Highest f-value expanded so far.
+    bound: usize,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus>,
+}
+
+impl AStarSearch {
+    pub fn new(start: &Puzzle) -> Result<Self, &'static str> {
+        Self::with_heuristic(start, LinearConflict)
+    }
+
+    /// Searches with `heuristic` in place of the default estimate. Solutions
+    /// are only guaranteed to be shortest if it never overestimates.
+    pub fn with_heuristic(
+        start: &Puzzle,
+        heuristic: impl Heuristic + 'static,
+    ) -> Result<Self, &'static str> {
+        if !start.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+
+        let h = heuristic.estimate(start);
+        let mut tracker = ProgressTracker::new();
+        tracker.start_iteration(h);
+        Ok(Self {
+            heuristic: Box::new(heuristic),
+            nodes: vec![Node {
+                state: start.clone(),
+                parent: None,
+                movement: None,
+                g: 0,
+                closed: false,
+            }],
+            index: HashMap::from([(start.clone(), 0)]),
+            open: BinaryHeap::from([(Reverse(h), 0, 0)]),
+            bound: h,
+            tracker,
+            outcome: None,
+        })
+    }
+
+    fn finish(&mut self, status: SearchStatus) -> SearchStatus {
+        self.outcome = Some(status.clone());
+        status
+    }
+
+    fn path(&self, mut index: usize) -> Vec<Move> {
+        let mut moves = Vec::new();
+        while let (Some(parent), Some(dir)) = (self.nodes[index].parent, self.nodes[index].movement)
+        {
+            moves.push(dir);
+            index = parent;
+        }
+        moves.reverse();
+        moves
+    }
+}
+
+impl Search for AStarSearch {
+    fn step(&mut self, nodes: u64) -> SearchStatus {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        while visited < nodes {
+            let Some((Reverse(f), g, index)) = self.open.pop() else {
+                return self.finish(SearchStatus::Failed("No solution found"));
+            };
+            let node = &mut self.nodes[index];
+            if node.closed || node.g != g {
+                continue;
+            }
+            node.closed = true;
+            let state = node.state.clone();
+
+            self.tracker.node();
+            visited += 1;
+            if f > self.bound {
+                self.bound = f;
+                self.tracker.finish_iteration();
+                self.tracker.start_iteration(f);
+            }
+            let h = f - g;
+            if state.is_solved() {
+                if h != 0 {
+                    return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+                }
+                let moves = self.path(index);
+                return self.finish(SearchStatus::Solved(moves));
+            }
+            if h == 0 {
+                return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+            }
+            if self.nodes.len() > MAX_STATES {
+                return self.finish(SearchStatus::Failed("Node limit exceeded"));
+            }
+
+            for dir in Move::ALL {
+                let mut next = state.clone();
+                if !next.apply_move(dir) {
+                    continue;
+                }
+                let next_index = match self.index.entry(next) {
+                    Entry::Occupied(entry) => {
+                        let existing = &mut self.nodes[*entry.get()];
+                        if existing.closed || existing.g <= g + 1 {
+                            continue;
+                        }
+                        existing.parent = Some(index);
+                        existing.movement = Some(dir);
+                        existing.g = g + 1;
+                        *entry.get()
+                    }
+                    Entry::Vacant(entry) => {
+                        self.nodes.push(Node {
+                            state: entry.key().clone(),
+                            parent: Some(index),
+                            movement: Some(dir),
+                            g: g + 1,
+                            closed: false,
+                        });
+                        *entry.insert(self.nodes.len() - 1)
+                    }
+                };
+                let next_h =
+                    self.heuristic
+                        .estimate_after(&state, h, dir, &self.nodes[next_index].state);
+                self.open.push((Reverse(g + 1 + next_h), g + 1, next_index));
+            }
+        }
+
+        SearchStatus::Running
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
@@ -0,0 +1,222 @@
+use crate::puzzle::{Move, Puzzle};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    /// Position of the board among the entries of the input file.
+    pub index: usize,
+    pub outcome: Result<Vec<Move>, String>,
+}
+
+/// Reads a scramble file: one board per line as tiles separated by commas or
+/// spaces. Blank lines and lines starting with `#` are skipped.
+pub fn read_scrambles(text: &str) -> Vec<Result<Puzzle, &'static str>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+/// An entry set aside because it cannot be solved, with enough context to
+/// find and fix it in the input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quarantined {
+    /// Position of the entry among the entries of the input file.
+    pub index: usize,
+    /// 1-based line number in the input file.
+    pub line: usize,
+    pub input: String,
+    pub reason: &'static str,
+    pub detail: Option<String>,
+}
+
+/// Finds the entries of a scramble file that fail to parse or are not
+/// solvable, so a batch can skip them instead of spending time on them.
+pub fn quarantine(text: &str) -> Vec<Quarantined> {
+    text.lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .enumerate()
+        .filter_map(|(index, (line, input))| {
+            let (reason, detail) = match input.parse::<Puzzle>() {
+                Err(err) => (err, None),
+                Ok(puzzle) if !puzzle.is_current_state_solvable() => {
+                    let (row, _) = puzzle.blank_position();
+                    let detail = format!(
+                        "{} inversions, empty space on row {} from the bottom",
+                        puzzle.inversions(),
+                        puzzle.rows() - row
+                    );
+                    ("Puzzle is not solvable", Some(detail))
+                }
+                Ok(_) => return None,
+            };
+            Some(Quarantined {
+                index,
+                line,
+                input: input.to_string(),
+                reason,
+                detail,
+            })
+        })
+        .collect()
+}
+
+/// `line,reason,input` for every quarantined entry, with any detail
+/// appended to the reason in parentheses.
+pub fn quarantine_csv(entries: &[Quarantined]) -> String {
+    let mut out = String::from("line,reason,input\n");
+    for entry in entries {
+        match &entry.detail {
+            Some(detail) => out.push_str(&format!(
+                "{},{} ({}),{}\n",
+                entry.line, entry.reason, detail, entry.input
+            )),
+            None => out.push_str(&format!(
+                "{},{},{}\n",
+                entry.line, entry.reason, entry.input
+            )),
+        }
+    }
+    out
+}
+
+/// Results of the entries completed so far, saved periodically so an
+/// interrupted batch can pick up where it left off.
+#[derive(Debug, Clone, Default)]
+pub struct Checkpoint {
+    completed: BTreeMap<usize, BatchResult>,
+}
+
+impl Checkpoint {
+    /// Loads a checkpoint, or starts an empty one if the file does not exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+
+        let completed = text
+            .lines()
+            .filter_map(BatchResult::from_line)
+            .map(|result| (result.index, result))
+            .collect();
+        Ok(Self { completed })
+    }
+
+    /// Writes to a temporary file first so a crash mid-write cannot corrupt
+    /// the previous checkpoint.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, self.to_csv())?;
+        fs::rename(temp, path)
+    }
+
+    pub fn is_done(&self, index: usize) -> bool {
+        self.completed.contains_key(&index)
+    }
+
+    pub fn record(&mut self, result: BatchResult) {
+        self.completed.insert(result.index, result);
+    }
+
+    pub fn len(&self) -> usize {
+        self.completed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.completed.is_empty()
+    }
+
+    pub fn results(&self) -> impl Iterator<Item = &BatchResult> {
+        self.completed.values()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("index,length,moves\n");
+        for result in self.completed.values() {
+            out.push_str(&result.to_line());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl BatchResult {
+    /// `index,length,moves` on success, or `index,error,message` on failure.
+    pub fn to_line(&self) -> String {
+        match &self.outcome {
+            Ok(moves) => {
+                let moves: Vec<String> = moves.iter().map(Move::to_string).collect();
+                format!("{},{},{}", self.index, moves.len(), moves.join(" "))
+            }
+            Err(err) => format!("{},error,{}", self.index, err),
+        }
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, ',');
+        let index = fields.next()?.parse().ok()?;
+        let length = fields.next()?;
+        let rest = fields.next().unwrap_or("");
+
+        let outcome = if length == "error" {
+            Err(rest.to_string())
+        } else {
+            rest.split_whitespace()
+                .map(|item| item.parse().ok())
+                .collect::<Option<Vec<Move>>>()
+                .map(Ok)?
+        };
+        Some(Self { index, outcome })
+    }
+}
+
+/// Solves every entry not already in `checkpoint`, saving the checkpoint to
+/// `checkpoint_path` after every `every` newly solved entries.
+pub fn run_batch<F>(
+    entries: &[Result<Puzzle, &'static str>],
+    solver: F,
+    checkpoint: &mut Checkpoint,
+    checkpoint_path: Option<&Path>,
+    every: usize,
+) -> io::Result<()>
+where
+    F: Fn(&Puzzle) -> Result<Vec<Move>, &'static str>,
+{
+    let mut since_save = 0;
+
+    for (index, entry) in entries.iter().enumerate() {
+        if checkpoint.is_done(index) {
+            continue;
+        }
+
+        let outcome = match entry {
+            Ok(puzzle) => solver(puzzle),
+            Err(err) => Err(*err),
+        };
+        checkpoint.record(BatchResult {
+            index,
+            outcome: outcome.map_err(str::to_string),
+        });
+
+        since_save += 1;
+        if let Some(path) = checkpoint_path {
+            if since_save >= every.max(1) {
+                checkpoint.save(path)?;
+                since_save = 0;
+            }
+        }
+    }
+
+    if let Some(path) = checkpoint_path {
+        checkpoint.save(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Repeatable solver timings, so downstream projects can check for
+//! performance regressions on their own hardware from their test suites.
+//!
+//! Boards are derived from a seed and node counts do not depend on the
+//! machine, so a change in `nodes` means the search itself changed, while a
+//! change in `median` may just be a busier machine.
+
+use crate::clock::Instant;
+use crate::config::Config;
+use crate::json::Json;
+use crate::puzzle::Puzzle;
+use crate::registry::{solve_interruptible, INTERRUPTIBLE_ENGINES};
+use crate::schema;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Names from `INTERRUPTIBLE_ENGINES`, which report node counts.
+    pub engines: Vec<&'static str>,
+    /// Engine parameters such as `weight`, read as `solve_interruptible` does.
+    pub engine_config: Config,
+    pub size: usize,
+    pub boards: usize,
+    /// Boards are the same for the same seed and size.
+    pub seed: String,
+    /// Times each board is solved; the median time is reported.
+    pub repeats: usize,
+    /// Longest a single solve may run before it counts as over budget.
+    pub timeout: Duration,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            engines: INTERRUPTIBLE_ENGINES.to_vec(),
+            engine_config: Config::default(),
+            size: 3,
+            boards: 5,
+            seed: "bench".to_string(),
+            repeats: 3,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchEntry {
+    pub engine: &'static str,
+    /// Position of the board among the generated ones.
+    pub board: usize,
+    /// Solution length, or why no solution was found.
+    pub outcome: Result<usize, &'static str>,
+    /// Nodes expanded by a single solve.
+    pub nodes: u64,
+    pub median: Duration,
+    pub fastest: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub size: usize,
+    pub entries: Vec<BenchEntry>,
+}
+
+/// The boards `run` solves for `config`.
+pub fn boards(config: &BenchConfig) -> Result<Vec<Puzzle>, &'static str> {
+    (0..config.boards)
+        .map(|i| Puzzle::daily_in(config.size, &format!("{}-{}", config.seed, i), "bench"))
+        .collect()
+}
+
+/// Solves every board with every engine `config.repeats` times.
+pub fn run(config: &BenchConfig) -> Result<BenchReport, &'static str> {
+    let boards = boards(config)?;
+    let mut entries = Vec::new();
+    for &engine in &config.engines {
+        if !INTERRUPTIBLE_ENGINES.contains(&engine) {
+            return Err("Engine cannot be benchmarked");
+        }
+        for (board, puzzle) in boards.iter().enumerate() {
+            entries.push(measure(engine, config, board, puzzle));
+        }
+    }
+    Ok(BenchReport {
+        size: config.size,
+        entries,
+    })
+}
+
+fn measure(
+    engine: &'static str,
+    config: &BenchConfig,
+    board: usize,
+    puzzle: &Puzzle,
+) -> BenchEntry {
+    let mut times = Vec::with_capacity(config.repeats.max(1));
+    let mut outcome = Err("Not run");
+    let mut nodes = 0;
+
+    for _ in 0..config.repeats.max(1) {
+        let start = Instant::now();
+        let result = solve_interruptible(engine, &config.engine_config, puzzle, |progress| {
+            nodes = progress.nodes;
+            start.elapsed() < config.timeout
+        });
+        times.push(start.elapsed());
+
+        outcome = match result {
+            Ok(moves) => Ok(moves.len()),
+            Err("Search cancelled") => Err("Over budget"),
+            Err(err) => Err(err),
+        };
+        if outcome.is_err() {
+            break;
+        }
+    }
+
+    times.sort();
+    BenchEntry {
+        engine,
+        board,
+        outcome,
+        nodes,
+        median: times[times.len() / 2],
+        fastest: times[0],
+    }
+}
+
+impl BenchReport {
+    /// Entries that failed, expanded more nodes, or took more than
+    /// `1 + tolerance` times as long as the matching entry in `baseline`.
+    /// Entries missing from `baseline` are not compared.
+    pub fn regressions(&self, baseline: &BenchReport, tolerance: f64) -> Vec<&BenchEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                baseline
+                    .entries
+                    .iter()
+                    .find(|base| base.engine == entry.engine && base.board == entry.board)
+                    .is_some_and(|base| {
+                        (base.outcome.is_ok() && entry.outcome.is_err())
+                            || entry.nodes > base.nodes
+                            || entry.median.as_secs_f64()
+                                > base.median.as_secs_f64() * (1.0 + tolerance)
+                    })
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Json {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut fields = vec![
+                    ("engine", Json::from(entry.engine)),
+                    ("board", Json::from(entry.board)),
+                ];
+                match entry.outcome {
+                    Ok(length) => fields.push(("length", Json::from(length))),
+                    Err(err) => fields.push(("error", Json::from(err))),
+                }
+                fields.extend([
+                    ("nodes", Json::from(entry.nodes)),
+                    ("median_ms", Json::from(entry.median.as_secs_f64() * 1000.0)),
+                    (
+                        "fastest_ms",
+                        Json::from(entry.fastest.as_secs_f64() * 1000.0),
+                    ),
+                ]);
+                Json::object(fields)
+            })
+            .collect();
+
+        schema::document(
+            "bench",
+            [
+                ("size", Json::from(self.size)),
+                ("entries", Json::Array(entries)),
+            ],
+        )
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let board = format!("#{}", entry.board);
+            match entry.outcome {
+                Ok(length) => writeln!(
+                    f,
+                    "{:<16} {:<4} {:>5} moves {:>12} nodes {:>10.2?}",
+                    entry.engine, board, length, entry.nodes, entry.median
+                )?,
+                Err(err) => writeln!(f, "{:<16} {:<4} {}", entry.engine, board, err)?,
+            }
+        }
+        Ok(())
+    }
+}
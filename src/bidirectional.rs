@@ -0,0 +1,187 @@
+use crate::progress::{Progress, ProgressTracker};
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, Search, SearchStatus};
+use std::collections::HashMap;
+use std::mem;
+
+/// Upper limit on stored states across both directions.
+const MAX_STATES: usize = 10_000_000;
+
+const FORWARD: usize = 0;
+const BACKWARD: usize = 1;
+
+impl Puzzle {
+    /// Breadth-first search from both the scrambled board and the goal at
+    /// once, meeting in the middle. Each side only searches about half the
+    /// solution depth, which makes optimal solutions cheap for 3x3 boards and
+    /// practical for moderate 4x4 ones. Needs no heuristic, and the solution
+    /// is always shortest.
+    pub fn solve_bidirectional(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_bidirectional_with_progress(|_| true)
+    }
+
+    /// Like `solve_bidirectional`, reporting progress to `callback`;
+    /// returning `false` from the callback cancels the search. `bound` in the
+    /// reports is the combined depth searched from both ends.
+    pub fn solve_bidirectional_with_progress<F>(
+        &self,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(BidirectionalSearch::new(self)?, callback)
+    }
+}
+
+/// Resumable state of a bidirectional search; see `Puzzle::solve_bidirectional`.
+pub struct BidirectionalSearch {
+    /// For each direction, every state reached and the move that reached it.
+    seen: [HashMap<Puzzle, Option<Move>>; 2],
+    /// For each direction, the states at the deepest level reached.
+    frontiers: [Vec<Puzzle>; 2],
+    depths: [usize; 2],
+    /// Direction whose frontier is being expanded.
+    side: usize,
+    /// How many states of that frontier have been expanded.
+    position: usize,
+    next: Vec<Puzzle>,
+    /// Shortest meeting point found while expanding the current frontier.
+    meeting: Option<(usize, Puzzle)>,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus>,
+}
+
+impl BidirectionalSearch {
+    pub fn new(start: &Puzzle) -> Result<Self, &'static str> {
+        if !start.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+
+        let goal = start.goal();
+        let outcome = (*start == goal).then(|| SearchStatus::Solved(Vec::new()));
+        Ok(Self {
+            seen: [
+                HashMap::from([(start.clone(), None)]),
+                HashMap::from([(goal.clone(), None)]),
+            ],
+            frontiers: [vec![start.clone()], vec![goal]],
+            depths: [0, 0],
+            side: FORWARD,
+            position: 0,
+            next: Vec::new(),
+            meeting: None,
+            tracker: ProgressTracker::new(),
+            outcome,
+        })
+    }
+
+    fn finish(&mut self, status: SearchStatus) -> SearchStatus {
+        self.outcome = Some(status.clone());
+        status
+    }
+
+    /// Moves from `state` back to the root of `side`, in the order they are
+    /// undone.
+    fn path_to_root(&self, side: usize, mut state: Puzzle) -> Vec<Move> {
+        let mut moves = Vec::new();
+        while let Some(&Some(movement)) = self.seen[side].get(&state) {
+            moves.push(movement);
+            state.apply_move(movement.opposite());
+        }
+        moves
+    }
+
+    /// Depth of `state` on `side`, found by walking back to the root.
+    fn depth_of(&self, side: usize, state: &Puzzle) -> usize {
+        self.path_to_root(side, state.clone()).len()
+    }
+
+    fn solution(&self, meeting: Puzzle) -> Vec<Move> {
+        let mut moves = self.path_to_root(FORWARD, meeting.clone());
+        moves.reverse();
+        moves.extend(
+            self.path_to_root(BACKWARD, meeting)
+                .into_iter()
+                .map(|movement| movement.opposite()),
+        );
+        moves
+    }
+
+    /// Called once the current frontier has been fully expanded.
+    fn next_level(&mut self) -> SearchStatus {
+        self.tracker.finish_iteration();
+        if let Some((_, meeting)) = self.meeting.take() {
+            let moves = self.solution(meeting);
+            return self.finish(SearchStatus::Solved(moves));
+        }
+        if self.next.is_empty() {
+            return self.finish(SearchStatus::Failed("No solution found"));
+        }
+
+        self.frontiers[self.side] = mem::take(&mut self.next);
+        self.depths[self.side] += 1;
+        // Grow whichever side is cheaper to expand
+        self.side = if self.frontiers[FORWARD].len() <= self.frontiers[BACKWARD].len() {
+            FORWARD
+        } else {
+            BACKWARD
+        };
+        self.position = 0;
+        self.tracker
+            .start_iteration(self.depths[FORWARD] + self.depths[BACKWARD] + 1);
+        SearchStatus::Running
+    }
+}
+
+impl Search for BidirectionalSearch {
+    fn step(&mut self, nodes: u64) -> SearchStatus {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        while visited < nodes {
+            let side = self.side;
+            let Some(state) = self.frontiers[side].get(self.position).cloned() else {
+                match self.next_level() {
+                    SearchStatus::Running => continue,
+                    status => return status,
+                }
+            };
+            self.position += 1;
+            self.tracker.node();
+            visited += 1;
+            if self.seen[FORWARD].len() + self.seen[BACKWARD].len() > MAX_STATES {
+                return self.finish(SearchStatus::Failed("Node limit exceeded"));
+            }
+
+            let last = self.seen[side][&state];
+            for movement in Move::ALL {
+                if last.is_some_and(|last| movement == last.opposite()) {
+                    continue;
+                }
+                let mut next = state.clone();
+                if !next.apply_move(movement) || self.seen[side].contains_key(&next) {
+                    continue;
+                }
+                if self.seen[1 - side].contains_key(&next) {
+                    // Any shorter path would have met in an earlier frontier, so
+                    // the shortest meeting in this one is optimal
+                    let length = self.depths[side] + 1 + self.depth_of(1 - side, &next);
+                    if self.meeting.as_ref().is_none_or(|(best, _)| length < *best) {
+                        self.meeting = Some((length, next.clone()));
+                    }
+                }
+                self.seen[side].insert(next.clone(), Some(movement));
+                self.next.push(next);
+            }
+        }
+
+        SearchStatus::Running
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
@@ -0,0 +1,126 @@
+//! How a solution's length compares with what can be proven about its
+//! board: the best admissible estimate below it and the most moves any
+//! board of the size needs above it.
+//!
+//! A solution from an engine that does not promise shortest solutions is
+//! still provably shortest when its length equals a lower bound, and one
+//! longer than the size's ceiling is certainly not.
+
+use crate::catalog::{self, PdbStatus};
+use crate::heuristic;
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundsReport {
+    pub length: usize,
+    /// The largest admissible estimate for the board, or its exact distance
+    /// where the tablebase covers it.
+    pub lower_bound: usize,
+    /// Whether `lower_bound` is the exact optimal length.
+    pub exact: bool,
+    /// The admissible heuristic that gave `lower_bound`, or "tablebase".
+    pub bound_source: &'static str,
+    /// The most moves any board of this size needs, where known.
+    pub ceiling: Option<usize>,
+}
+
+impl BoundsReport {
+    /// Whether the solution is proven shortest, because no solution can be
+    /// shorter than the lower bound it reaches.
+    pub fn provably_optimal(&self) -> bool {
+        self.length == self.lower_bound
+    }
+
+    /// Moves above the lower bound, an upper limit on how far the solution
+    /// is from shortest.
+    pub fn gap(&self) -> usize {
+        self.length - self.lower_bound
+    }
+
+    /// Whether the solution is longer than any board of the size needs, so
+    /// certainly not shortest.
+    pub fn exceeds_ceiling(&self) -> bool {
+        self.ceiling.is_some_and(|ceiling| self.length > ceiling)
+    }
+}
+
+/// God's number for a board: the most moves an optimal solution of any
+/// solvable board of the size takes, for the sizes where it is known.
+/// Boards a single cell across can only ever need one move per tile.
+pub fn gods_number(rows: usize, cols: usize) -> Option<usize> {
+    let (short, long) = (rows.min(cols), rows.max(cols));
+    match (short, long) {
+        (1, _) => Some(long - 1),
+        (2, 2) => Some(6),
+        (2, 3) => Some(21),
+        (2, 4) => Some(36),
+        (3, 3) => Some(31),
+        (3, 4) => Some(53),
+        (4, 4) => Some(80),
+        _ => None,
+    }
+}
+
+/// Compares `moves`, which must solve `puzzle`, with the bounds on its
+/// optimal length. Only boards with the standard goal have known ceilings.
+pub fn compare(puzzle: &Puzzle, moves: &[Move]) -> Result<BoundsReport, &'static str> {
+    let mut end = puzzle.clone();
+    if end.apply_moves(moves).is_err() || !end.is_solved() {
+        return Err("Moves do not solve the board");
+    }
+    let ceiling = if puzzle.has_standard_goal() {
+        gods_number(puzzle.rows(), puzzle.cols())
+    } else {
+        None
+    };
+
+    if let Some(distance) = tablebase::distance_to_goal(puzzle) {
+        return Ok(BoundsReport {
+            length: moves.len(),
+            lower_bound: distance,
+            exact: true,
+            bound_source: "tablebase",
+            ceiling,
+        });
+    }
+    let (lower_bound, bound_source) = catalog::heuristics()
+        .into_iter()
+        .filter(|info| {
+            info.admissible && info.sizes.contains(&puzzle.size()) && info.pdb != PdbStatus::Missing
+        })
+        .filter_map(|info| Some((heuristic::by_name(info.name)?.estimate(puzzle), info.name)))
+        .max_by_key(|&(estimate, _)| estimate)
+        .unwrap_or((0, "none"));
+    Ok(BoundsReport {
+        length: moves.len(),
+        lower_bound,
+        exact: false,
+        bound_source,
+        ceiling,
+    })
+}
+
+impl fmt::Display for BoundsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.exact { "exact" } else { "lower bound" };
+        write!(
+            f,
+            "{} moves; {} {} ({})",
+            self.length, kind, self.lower_bound, self.bound_source
+        )?;
+        if let Some(ceiling) = self.ceiling {
+            write!(f, "; ceiling {}", ceiling)?;
+        }
+        if self.provably_optimal() {
+            write!(f, "; provably optimal")
+        } else if self.exceeds_ceiling() {
+            write!(f, "; longer than any board of this size needs")
+        } else if self.exact {
+            write!(f, "; {} moves over optimal", self.gap())
+        } else {
+            write!(f, "; at most {} moves over optimal", self.gap())
+        }
+    }
+}
@@ -0,0 +1,533 @@
+//! Descriptions of the built-in engines and heuristics, so front ends can
+//! offer only the options that make sense for a given board.
+
+use crate::heuristic;
+use crate::pdb;
+use crate::puzzle::MAX_SIZE;
+use crate::registry::SolverRegistry;
+use crate::walking;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// How an engine's or heuristic's memory use grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryUse {
+    /// Nothing beyond the board itself.
+    Constant,
+    /// Proportional to the solution length.
+    Depth,
+    /// Proportional to the number of states explored.
+    Explored,
+    /// Capped by an engine parameter such as the beam width.
+    Bounded,
+    /// A fixed-size table, built on first use.
+    Table { bytes: usize },
+}
+
+/// Whether a pattern database file is required, and if so whether it was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdbStatus {
+    NotNeeded,
+    Found,
+    Missing,
+}
+
+/// How practical an engine is for a board, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Recommended,
+    /// Works, but only on some boards or with long waits.
+    Slow,
+    /// Will not finish in any reasonable time or memory.
+    Impractical,
+    /// The engine cannot take the board at all.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suitability {
+    pub verdict: Verdict,
+    /// Why, in a sentence suitable for showing to users.
+    pub reason: &'static str,
+}
+
+/// The verdict for square boards up to a size; see `EngineInfo::limits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit {
+    pub up_to: usize,
+    pub verdict: Verdict,
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineInfo {
+    pub name: &'static str,
+    /// Whether solutions are shortest, given an admissible heuristic.
+    pub optimal: bool,
+    /// Whether the engine reports progress and can be cancelled.
+    pub interruptible: bool,
+    pub sizes: RangeInclusive<usize>,
+    pub memory: MemoryUse,
+    pub pdb: PdbStatus,
+    /// Config keys the engine reads.
+    pub parameters: &'static [&'static str],
+    /// Practical limits by board size, smallest first. The first entry
+    /// covering a size applies.
+    pub limits: &'static [Limit],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeuristicInfo {
+    pub name: &'static str,
+    /// Whether the estimate never exceeds the true distance.
+    pub admissible: bool,
+    /// Whether this is the estimate the built-in engines search with.
+    pub default: bool,
+    pub sizes: RangeInclusive<usize>,
+    pub memory: MemoryUse,
+    pub pdb: PdbStatus,
+}
+
+/// The engines registered by `SolverRegistry::with_builtins`.
+pub fn engines() -> Vec<EngineInfo> {
+    let engines = vec![
+        EngineInfo {
+            name: "a-star",
+            optimal: true,
+            interruptible: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Explored,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &["require_optimal", "heuristic"],
+            limits: &[
+                Limit {
+                    up_to: 3,
+                    verdict: Verdict::Recommended,
+                    reason: "Solves any 3x3 board optimally in milliseconds",
+                },
+                Limit {
+                    up_to: 4,
+                    verdict: Verdict::Slow,
+                    reason: "Keeps every state it sees, so only easy 4x4 scrambles fit in its state limit",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "Reaches its state limit long before finding a solution",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "adaptive",
+            optimal: false,
+            interruptible: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Explored,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &["budget_secs", "deterministic", "budget_nodes"],
+            limits: &[
+                Limit {
+                    up_to: 4,
+                    verdict: Verdict::Recommended,
+                    reason: "Optimal when IDA* finishes within the budget, otherwise falls back to weighted A*",
+                },
+                Limit {
+                    up_to: 5,
+                    verdict: Verdict::Recommended,
+                    reason: "IDA* will not finish, so the solution comes from the weighted A* fallback",
+                },
+                Limit {
+                    up_to: 7,
+                    verdict: Verdict::Slow,
+                    reason: "Only the beam search fallback finishes, after the IDA* budget is spent",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "None of the fallback engines finish on boards this large",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "beam",
+            optimal: false,
+            interruptible: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Bounded,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &["beam_width"],
+            limits: &[
+                Limit {
+                    up_to: 5,
+                    verdict: Verdict::Recommended,
+                    reason: "Fast, with solutions somewhat longer than optimal",
+                },
+                Limit {
+                    up_to: 7,
+                    verdict: Verdict::Slow,
+                    reason: "Takes several seconds and gives long solutions",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "Boards this large need a row-by-row method, which the crate does not provide",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "bidirectional",
+            optimal: true,
+            interruptible: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Explored,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &[],
+            limits: &[
+                Limit {
+                    up_to: 3,
+                    verdict: Verdict::Recommended,
+                    reason: "Solves any 3x3 board optimally in milliseconds",
+                },
+                Limit {
+                    up_to: 4,
+                    verdict: Verdict::Slow,
+                    reason: "Only moderate 4x4 scrambles fit in its state limit",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "Reaches its state limit long before the two searches meet",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "external-a-star",
+            optimal: true,
+            interruptible: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Bounded,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &["external_dir", "external_buffer"],
+            limits: &[
+                Limit {
+                    up_to: 3,
+                    verdict: Verdict::Recommended,
+                    reason: "Solves any 3x3 board optimally, though slower than the in-memory engines",
+                },
+                Limit {
+                    up_to: 4,
+                    verdict: Verdict::Slow,
+                    reason: "Hard 4x4 boards need hours and many gigabytes of fast disk",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "The layers on disk outgrow any drive long before a solution",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "ida-star",
+            optimal: true,
+            interruptible: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Depth,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &[
+                "require_optimal",
+                "heuristic",
+                "table_bytes",
+                "pdb_file",
+                "pdb_partition",
+            ],
+            limits: &[
+                Limit {
+                    up_to: 3,
+                    verdict: Verdict::Recommended,
+                    reason: "Solves any 3x3 board optimally in milliseconds",
+                },
+                Limit {
+                    up_to: 4,
+                    verdict: Verdict::Recommended,
+                    reason: "Manhattan distance and linear conflicts handle most 4x4 boards; set pdb_file for the hardest",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "Optimal solving beyond 4x4 needs pattern databases for the larger board, which the crate does not build",
+                },
+            ],
+        },
+        EngineInfo {
+            name: "weighted-a-star",
+            optimal: false,
+            interruptible: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Explored,
+            pdb: PdbStatus::NotNeeded,
+            parameters: &["weight"],
+            limits: &[
+                Limit {
+                    up_to: 5,
+                    verdict: Verdict::Recommended,
+                    reason: "Fast, with solutions somewhat longer than optimal",
+                },
+                Limit {
+                    up_to: MAX_SIZE,
+                    verdict: Verdict::Impractical,
+                    reason: "Reaches its state limit; beam search still works up to 7x7",
+                },
+            ],
+        },
+    ];
+    debug_assert!(engines
+        .iter()
+        .map(|engine| engine.name)
+        .eq(SolverRegistry::with_builtins().names()));
+    engines
+}
+
+/// How practical `engine` is for a `rows` x `cols` board, or `None` if no
+/// built-in engine has that name.
+pub fn recommended_for(engine: &str, rows: usize, cols: usize) -> Option<Suitability> {
+    engines()
+        .into_iter()
+        .find(|info| info.name == engine)
+        .map(|info| info.recommended_for(rows, cols))
+}
+
+impl EngineInfo {
+    /// How practical the engine is for a `rows` x `cols` board and why, so
+    /// front ends can warn before starting a search that cannot finish.
+    pub fn recommended_for(&self, rows: usize, cols: usize) -> Suitability {
+        if rows != cols {
+            return Suitability {
+                verdict: Verdict::Unsupported,
+                reason: if rows == 2 || cols == 2 {
+                    "Only square boards are supported; strip::solve_strip solves two-row boards"
+                } else {
+                    "Only square boards are supported"
+                },
+            };
+        }
+        if !self.sizes.contains(&rows) {
+            return Suitability {
+                verdict: Verdict::Unsupported,
+                reason: "The engine does not support this board size",
+            };
+        }
+        if rows <= 2 {
+            return Suitability {
+                verdict: Verdict::Recommended,
+                reason: "Boards this small are solved instantly",
+            };
+        }
+        self.limits.iter().find(|limit| rows <= limit.up_to).map_or(
+            Suitability {
+                verdict: Verdict::Impractical,
+                reason: "No known limits for this board size",
+            },
+            |limit| Suitability {
+                verdict: limit.verdict,
+                reason: limit.reason,
+            },
+        )
+    }
+}
+
+/// The heuristics implemented on `Puzzle`, as named by `heuristic::by_name`.
+pub fn heuristics() -> Vec<HeuristicInfo> {
+    let heuristics = vec![
+        HeuristicInfo {
+            name: "manhattan",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            name: "linear-conflict",
+            admissible: true,
+            default: true,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            name: "tablebase",
+            admissible: true,
+            default: false,
+            sizes: 3..=3,
+            memory: MemoryUse::Table { bytes: 362_880 },
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            name: "pattern-database",
+            admissible: true,
+            default: false,
+            sizes: 4..=4,
+            memory: MemoryUse::Table {
+                bytes: pdb::table_bytes(),
+            },
+            pdb: if pdb::installed().is_some() || Path::new(pdb::DEFAULT_FILE).exists() {
+                PdbStatus::Found
+            } else {
+                PdbStatus::Missing
+            },
+        },
+        HeuristicInfo {
+            name: "walking-distance",
+            admissible: true,
+            default: false,
+            sizes: 1..=walking::MAX_SIZE,
+            memory: MemoryUse::Table {
+                bytes: walking::table_bytes(),
+            },
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            name: "inversion-distance",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            // The larger of the Manhattan and inversion distances
+            name: "manhattan-inversion",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            name: "misplaced-tiles",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            // Swaps of the empty space with any tile needed to sort the board
+            name: "gaschnig",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            // Linear conflicts plus detours around missing corner tiles
+            name: "corner-tiles",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+        HeuristicInfo {
+            // Corner tiles plus a detour for the tile making the final move
+            name: "last-moves",
+            admissible: true,
+            default: false,
+            sizes: 1..=MAX_SIZE,
+            memory: MemoryUse::Constant,
+            pdb: PdbStatus::NotNeeded,
+        },
+    ];
+    debug_assert!(heuristics.iter().map(|info| info.name).eq(heuristic::NAMES));
+    heuristics
+}
+
+impl fmt::Display for MemoryUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryUse::Constant => write!(f, "constant"),
+            MemoryUse::Depth => write!(f, "grows with solution length"),
+            MemoryUse::Explored => write!(f, "grows with states explored"),
+            MemoryUse::Bounded => write!(f, "bounded by parameters"),
+            MemoryUse::Table { bytes } => write!(f, "{} KiB per table", bytes.div_ceil(1024)),
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Recommended => write!(f, "recommended"),
+            Verdict::Slow => write!(f, "slow"),
+            Verdict::Impractical => write!(f, "impractical"),
+            Verdict::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+impl fmt::Display for Suitability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.verdict, self.reason)
+    }
+}
+
+impl fmt::Display for PdbStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdbStatus::NotNeeded => write!(f, "no PDB"),
+            PdbStatus::Found => write!(f, "PDB found"),
+            PdbStatus::Missing => write!(f, "PDB missing"),
+        }
+    }
+}
+
+fn sizes(range: &RangeInclusive<usize>) -> String {
+    if range.start() == range.end() {
+        format!("{0}x{0}", range.start())
+    } else {
+        format!("{0}x{0} to {1}x{1}", range.start(), range.end())
+    }
+}
+
+impl fmt::Display for EngineInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}, {}, memory {}, {}",
+            self.name,
+            if self.optimal {
+                "optimal"
+            } else {
+                "suboptimal"
+            },
+            sizes(&self.sizes),
+            self.memory,
+            self.pdb
+        )?;
+        if !self.parameters.is_empty() {
+            write!(f, ", reads {}", self.parameters.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HeuristicInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}, {}, memory {}, {}{}",
+            self.name,
+            if self.admissible {
+                "admissible"
+            } else {
+                "inadmissible"
+            },
+            sizes(&self.sizes),
+            self.memory,
+            self.pdb,
+            if self.default { " (default)" } else { "" }
+        )
+    }
+}
@@ -0,0 +1,161 @@
+//! Messages for chat bots: boards in monospaced code blocks and solutions
+//! summarised to fit a platform's message limit.
+//!
+//! Limits are counted in characters, as Discord and Slack count them. Moves
+//! are written one letter each, so even long solutions fit; what does not
+//! fit is cut off with a count of the moves left out.
+
+use crate::glyphs::GlyphSet;
+use crate::puzzle::{Move, Puzzle};
+use std::time::Duration;
+
+/// Most characters in a Discord message.
+pub const DISCORD_LIMIT: usize = 2000;
+
+/// Most characters Slack shows in a message before truncating it.
+pub const SLACK_LIMIT: usize = 4000;
+
+/// Characters kept free under a board for the line that follows it.
+const LINE_ROOM: usize = 40;
+
+/// Time `solve_and_format` spends on a board by default. Discord drops a
+/// command that is not answered within three seconds.
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatFormat {
+    /// Most characters in one message.
+    limit: usize,
+    /// Draws boards with these instead of tile numbers.
+    glyphs: Option<GlyphSet>,
+    /// Most time `solve_and_format` spends solving.
+    budget: Duration,
+}
+
+impl ChatFormat {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            glyphs: None,
+            budget: DEFAULT_BUDGET,
+        }
+    }
+
+    pub fn discord() -> Self {
+        Self::new(DISCORD_LIMIT)
+    }
+
+    pub fn slack() -> Self {
+        Self::new(SLACK_LIMIT)
+    }
+
+    /// Draws boards as picture puzzles with `glyphs`.
+    pub fn with_glyphs(mut self, glyphs: GlyphSet) -> Self {
+        self.glyphs = Some(glyphs);
+        self
+    }
+
+    /// Gives `solve_and_format` at most `budget` to solve a board.
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The board in a code block, so it keeps its alignment.
+    pub fn board(&self, puzzle: &Puzzle) -> String {
+        let drawn = match &self.glyphs {
+            Some(glyphs) => glyphs.render(puzzle),
+            None => puzzle.to_string(),
+        };
+        format!("```\n{}```", drawn)
+    }
+
+    /// One line giving the length of a solution and its moves, one letter
+    /// each. If the line would be longer than `max_chars` the moves are cut
+    /// short with a count of the rest, or left out when even that is too
+    /// long.
+    pub fn summary(&self, moves: &[Move], max_chars: usize) -> String {
+        let head = match moves.len() {
+            0 => return "Already solved".to_string(),
+            1 => "Solved in 1 move: ".to_string(),
+            len => format!("Solved in {} moves: ", len),
+        };
+        let letters: String = moves.iter().map(|&movement| letter(movement)).collect();
+        let full = format!("{}`{}`", head, letters);
+        if full.chars().count() <= max_chars {
+            return full;
+        }
+
+        // The count of the rest is never longer than the count of all the
+        // moves, so reserving room for that always leaves enough
+        let reserved =
+            head.chars().count() + format!("`…` (+{} more)", moves.len()).chars().count();
+        let shown = max_chars.saturating_sub(reserved).min(moves.len() - 1);
+        if shown == 0 {
+            return head.trim_end_matches([':', ' ']).to_string();
+        }
+        format!(
+            "{}`{}…` (+{} more)",
+            head,
+            &letters[..shown],
+            moves.len() - shown
+        )
+    }
+
+    /// The board followed by a summary of `moves`, together no longer than
+    /// the message limit. A board too big to fit is described instead of
+    /// drawn.
+    pub fn message(&self, puzzle: &Puzzle, moves: &[Move]) -> String {
+        let board = self.board_within(puzzle);
+        let room = self.limit.saturating_sub(board.chars().count() + 1);
+        self.clamp(format!("{}\n{}", board, self.summary(moves, room)))
+    }
+
+    /// `text` cut short to the message limit. Only needed when the limit is
+    /// too small for even the shortest summary.
+    fn clamp(&self, mut text: String) -> String {
+        if let Some((end, _)) = text.char_indices().nth(self.limit) {
+            text.truncate(end);
+        }
+        text
+    }
+
+    /// The board in a code block if that leaves room for a line of text
+    /// under it, or else just its size.
+    fn board_within(&self, puzzle: &Puzzle) -> String {
+        let board = self.board(puzzle);
+        if board.chars().count() + LINE_ROOM <= self.limit {
+            board
+        } else {
+            format!("{}x{} board", puzzle.rows(), puzzle.cols())
+        }
+    }
+}
+
+/// Solves `puzzle` within the format's time budget and formats the board
+/// and solution as one chat message, or says why it could not. Boards too
+/// hard to solve optimally in time get the best solution found instead.
+pub fn solve_and_format(puzzle: &Puzzle, format: &ChatFormat) -> String {
+    match puzzle.solve_adaptive(format.budget) {
+        Ok(solution) => format.message(puzzle, &solution.moves),
+        Err(err) => format.clamp(format!(
+            "{}\nCould not solve: {}",
+            format.board_within(puzzle),
+            err
+        )),
+    }
+}
+
+/// The first letter of the move's name.
+fn letter(movement: Move) -> char {
+    match movement {
+        Move::Up => 'U',
+        Move::Down => 'D',
+        Move::Left => 'L',
+        Move::Right => 'R',
+    }
+}
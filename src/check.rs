@@ -0,0 +1,133 @@
+use crate::heuristic::Heuristic;
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::fmt;
+
+/// States sampled when a custom heuristic is installed in a debug build.
+#[cfg(debug_assertions)]
+const INSTALL_SAMPLES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The heuristic overestimated the true distance to the goal.
+    Inadmissible {
+        state: Puzzle,
+        estimate: usize,
+        distance: usize,
+    },
+    /// The estimate changed by more than the cost of a single move.
+    Inconsistent {
+        state: Puzzle,
+        successor: Puzzle,
+        estimate: usize,
+        successor_estimate: usize,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HeuristicReport {
+    pub states_checked: usize,
+    pub successors_checked: usize,
+    /// Whether admissibility was checked against exact distances.
+    pub admissibility_checked: bool,
+    pub violations: Vec<Violation>,
+}
+
+impl HeuristicReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Samples `samples` random states solved towards `goal` and checks
+/// `heuristic` for consistency on every successor pair. On 3x3 boards each
+/// sample is also compared against its exact distance to the goal.
+pub fn check_heuristic(
+    heuristic: &dyn Heuristic,
+    goal: &Puzzle,
+    samples: usize,
+) -> HeuristicReport {
+    let mut report = HeuristicReport {
+        admissibility_checked: tablebase::distance_to_goal(goal).is_some(),
+        ..Default::default()
+    };
+
+    for _ in 0..samples {
+        let mut state = goal.clone();
+        state.shuffle();
+        let estimate = heuristic.estimate(&state);
+        report.states_checked += 1;
+
+        if let Some(distance) = tablebase::distance_to_goal(&state) {
+            if estimate > distance {
+                report.violations.push(Violation::Inadmissible {
+                    state: state.clone(),
+                    estimate,
+                    distance,
+                });
+            }
+        }
+
+        for item in Move::ALL {
+            let mut successor = state.clone();
+            if !successor.apply_move(item) {
+                continue;
+            }
+            let successor_estimate = heuristic.estimate(&successor);
+            report.successors_checked += 1;
+
+            if estimate.abs_diff(successor_estimate) > 1 {
+                report.violations.push(Violation::Inconsistent {
+                    state: state.clone(),
+                    successor,
+                    estimate,
+                    successor_estimate,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Checks a custom heuristic as a search installs it, in debug builds only,
+/// and reports the first violation found on stderr. The search goes ahead
+/// either way, since an estimate that overshoots only costs optimality.
+#[cfg(debug_assertions)]
+pub(crate) fn report_on_install(heuristic: &dyn Heuristic, goal: &Puzzle) {
+    let report = check_heuristic(heuristic, goal, INSTALL_SAMPLES);
+    if let Some(violation) = report.violations.first() {
+        eprintln!(
+            "Heuristic check found {} violations in {} states, the first: {}",
+            report.violations.len(),
+            report.states_checked,
+            violation
+        );
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Inadmissible {
+                state,
+                estimate,
+                distance,
+            } => write!(
+                f,
+                "Inadmissible: estimate {} exceeds distance {}\n{}",
+                estimate, distance, state
+            ),
+            Violation::Inconsistent {
+                state,
+                successor,
+                estimate,
+                successor_estimate,
+            } => write!(
+                f,
+                "Inconsistent: estimate {} next to {}\n{}\n{}",
+                estimate, successor_estimate, state, successor
+            ),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! The clock behind every time limit and reported duration. Under
+//! `wasm32-unknown-unknown` the standard library has no clock, so browsers
+//! use theirs through `web-time` instead.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
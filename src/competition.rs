@@ -0,0 +1,74 @@
+//! Scrambles for competitions, where every competitor must face a board that
+//! is hard enough and no board may be more likely than another.
+//!
+//! Boards are drawn uniformly from the solvable states and rejected unless
+//! their optimal distance is proven to reach the minimum, so the accepted
+//! boards are uniform over the qualifying states.
+
+use crate::puzzle::Puzzle;
+use crate::rescramble;
+use crate::tablebase;
+use rand::Rng;
+
+/// Draws before giving up, which only happens when the minimum distance is
+/// close to or beyond the hardest states of the board size.
+const MAX_ATTEMPTS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scramble {
+    pub puzzle: Puzzle,
+    /// Proven lower bound on the optimal solution length.
+    pub lower_bound: usize,
+    /// Whether `lower_bound` is the exact optimal solution length.
+    pub exact: bool,
+}
+
+/// A minimum distance in line with common practice, for the sizes where
+/// one is established.
+pub fn default_min_distance(size: usize) -> Option<usize> {
+    match size {
+        2 => Some(4),
+        3 => Some(20),
+        4 => Some(25),
+        _ => None,
+    }
+}
+
+/// Draws a scramble of the given size whose optimal solution takes at least
+/// `min_distance` moves.
+pub fn competition_scramble<R: Rng + ?Sized>(
+    size: usize,
+    min_distance: usize,
+    rng: &mut R,
+) -> Result<Scramble, &'static str> {
+    let goal = Puzzle::new(size)?;
+    for _ in 0..MAX_ATTEMPTS {
+        let mut puzzle = goal.clone();
+        puzzle.shuffle_with(rng);
+        if let Some((lower_bound, exact)) = proven_distance(&puzzle, &goal, min_distance) {
+            return Ok(Scramble {
+                puzzle,
+                lower_bound,
+                exact,
+            });
+        }
+    }
+    Err("No scramble reached the minimum distance")
+}
+
+/// A lower bound of at least `min_distance` on the optimal distance, and
+/// whether it is exact, or `None` if the puzzle can be solved in fewer moves.
+fn proven_distance(puzzle: &Puzzle, goal: &Puzzle, min_distance: usize) -> Option<(usize, bool)> {
+    if let Some(distance) = tablebase::distance_to_goal(puzzle) {
+        return (distance >= min_distance).then_some((distance, true));
+    }
+    let bound = puzzle.manhattan_distance();
+    if bound >= min_distance {
+        return Some((bound, false));
+    }
+
+    // The estimate falls short, so search for a solution below the minimum
+    let limit = min_distance.saturating_sub(1);
+    let distance = rescramble::distance_between(puzzle, goal, limit);
+    (distance > limit).then_some((distance, false))
+}
@@ -0,0 +1,125 @@
+use crate::json::Json;
+use crate::puzzle::Puzzle;
+use crate::schema;
+use crate::tablebase;
+use std::collections::HashSet;
+
+/// Random walks tried per requested state before a bucket is left short.
+const ATTEMPTS_PER_STATE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurriculumBucket {
+    /// Solution length shared by every state in the bucket.
+    pub distance: usize,
+    pub states: Vec<Puzzle>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curriculum {
+    pub size: usize,
+    pub buckets: Vec<CurriculumBucket>,
+    /// Whether distances are optimal solution lengths. Boards larger than 4x4
+    /// are measured with weighted A*, which only gives an upper bound.
+    pub exact: bool,
+}
+
+/// Samples up to `per_bucket` distinct states at each of the given distances
+/// from the goal, e.g. `&[5, 10, 15]` for a beginner-to-intermediate course.
+pub fn generate_curriculum(
+    size: usize,
+    distances: &[usize],
+    per_bucket: usize,
+) -> Result<Curriculum, &'static str> {
+    let goal = Puzzle::new(size)?;
+    let mut buckets = Vec::new();
+
+    for &distance in distances {
+        let mut seen = HashSet::new();
+        let mut states = Vec::new();
+
+        for _ in 0..per_bucket * ATTEMPTS_PER_STATE {
+            if states.len() == per_bucket {
+                break;
+            }
+
+            let mut state = goal.clone();
+            state.random_walk(distance);
+            if measure(&state) == Some(distance) && seen.insert(state.clone()) {
+                states.push(state);
+            }
+        }
+
+        buckets.push(CurriculumBucket { distance, states });
+    }
+
+    Ok(Curriculum {
+        size,
+        buckets,
+        exact: size <= 4,
+    })
+}
+
+fn measure(state: &Puzzle) -> Option<usize> {
+    if let Some(distance) = tablebase::distance_to_goal(state) {
+        return Some(distance);
+    }
+    match state.size() {
+        0..=4 => state.solve().ok().map(|moves| moves.len()),
+        _ => state.solve_weighted(2.0).ok().map(|moves| moves.len()),
+    }
+}
+
+impl Curriculum {
+    pub fn to_json(&self) -> Json {
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|bucket| {
+                let states: Vec<Json> = bucket
+                    .states
+                    .iter()
+                    .map(|state| Json::from(state.tiles()))
+                    .collect();
+                Json::object([
+                    ("distance", Json::from(bucket.distance)),
+                    ("states", Json::Array(states)),
+                ])
+            })
+            .collect();
+
+        schema::document(
+            "dataset",
+            [
+                ("size", Json::from(self.size)),
+                ("exact", Json::from(self.exact)),
+                ("buckets", Json::Array(buckets)),
+            ],
+        )
+    }
+
+    /// Writes the curriculum as a scramble file: one board per line, with a
+    /// comment line introducing each bucket.
+    pub fn to_scramble_file(&self) -> String {
+        let mut out = String::new();
+
+        for bucket in &self.buckets {
+            out.push_str(&format!("# distance {}\n", bucket.distance));
+            for state in &bucket.states {
+                out.push_str(&scramble_line(state));
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+}
+
+/// A board as comma-separated tiles in reading order, which `Puzzle::from_str` accepts.
+pub fn scramble_line(puzzle: &Puzzle) -> String {
+    puzzle
+        .tiles()
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
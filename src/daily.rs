@@ -0,0 +1,41 @@
+use crate::puzzle::Puzzle;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// FNV-1a, chosen because its output is fixed by definition rather than by
+/// the standard library's unspecified `Hasher` implementations.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// A random generator seeded from `seed`, producing the same sequence for
+/// the same text on every platform.
+pub fn seeded_rng(seed: &str) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(fnv1a(seed.bytes()))
+}
+
+impl Puzzle {
+    /// The puzzle of the day for `date`, e.g. `"2024-05-01"`. Every caller
+    /// passing the same size and date gets the same board.
+    pub fn daily(size: usize, date: &str) -> Result<Self, &'static str> {
+        Self::daily_in(size, date, "")
+    }
+
+    /// Like `daily`, but `namespace` selects an independent sequence of
+    /// boards so different apps can each have their own puzzle of the day.
+    pub fn daily_in(size: usize, date: &str, namespace: &str) -> Result<Self, &'static str> {
+        let seed = fnv1a(
+            namespace
+                .bytes()
+                .chain(std::iter::once(0))
+                .chain(date.bytes())
+                .chain((size as u64).to_le_bytes()),
+        );
+
+        let mut puzzle = Self::new(size)?;
+        puzzle.shuffle_with(&mut ChaCha8Rng::seed_from_u64(seed));
+        Ok(puzzle)
+    }
+}
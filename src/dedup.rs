@@ -0,0 +1,110 @@
+use crate::puzzle::Puzzle;
+use std::collections::HashMap;
+
+/// An entry of a scramble file that repeats an earlier one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    /// Position of the repeated entry among the entries of the file.
+    pub index: usize,
+    /// Position of the first entry it repeats.
+    pub original: usize,
+    /// Whether the two boards only match after reflecting one of them.
+    pub symmetric: bool,
+    /// The representative shared by both boards.
+    pub canonical: Puzzle,
+}
+
+/// Reflects the board across the diagonal through the blank's goal cell and
+/// relabels the tiles to match, giving a board with the same solution length.
+/// A board of `rows` by `cols` becomes one of `cols` by `rows`. A custom
+/// goal is reflected and relabelled along with the board.
+pub fn transposed(puzzle: &Puzzle) -> Puzzle {
+    let (rows, cols) = (puzzle.rows(), puzzle.cols());
+    let relabel = |value: u32| {
+        if value == 0 {
+            return 0;
+        }
+        let index = value as usize - 1;
+        ((index % cols) * rows + index / cols + 1) as u32
+    };
+
+    let tiles: Vec<u32> = (0..rows * cols)
+        .map(|i| relabel(puzzle.tile_at(i % rows, i / rows)))
+        .collect();
+    let mirror = Puzzle::from_tiles_rectangular(cols, rows, &tiles)
+        .expect("transposing a valid board keeps it valid");
+    if puzzle.has_standard_goal() {
+        return mirror;
+    }
+    let goal = Puzzle::from_tiles_rectangular(rows, cols, &puzzle.goal().tiles())
+        .expect("the goal is a valid board");
+    mirror
+        .with_goal(&transposed(&goal))
+        .expect("the goal has the same shape as the board")
+}
+
+/// The smaller of a board and its transposition, comparing the tiles and
+/// then the goal, so that symmetric boards share one representative. Boards
+/// that are not square are their own representative, since transposing
+/// changes their shape.
+pub fn canonical_form(puzzle: &Puzzle) -> Puzzle {
+    if !puzzle.is_square() {
+        return puzzle.clone();
+    }
+    let mirror = transposed(puzzle);
+    let key = |board: &Puzzle| (board.tiles(), board.goal().tiles());
+    if key(&mirror) < key(puzzle) {
+        mirror
+    } else {
+        puzzle.clone()
+    }
+}
+
+/// Finds entries that repeat an earlier entry exactly or up to reflection.
+/// Entries that failed to parse are never reported.
+pub fn find_duplicates(entries: &[Result<Puzzle, &'static str>]) -> Vec<Duplicate> {
+    let mut first_seen: HashMap<Puzzle, usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let Ok(puzzle) = entry else {
+            continue;
+        };
+        let canonical = canonical_form(puzzle);
+        match first_seen.get(&canonical) {
+            Some(&original) => duplicates.push(Duplicate {
+                index,
+                original,
+                symmetric: entries[original].as_ref() != Ok(puzzle),
+                canonical,
+            }),
+            None => {
+                first_seen.insert(canonical, index);
+            }
+        }
+    }
+
+    duplicates
+}
+
+/// Rewrites a scramble file without the entries reported by `find_duplicates`.
+/// Comments, blank lines and unparseable entries are kept as they are.
+pub fn dedupe(text: &str, duplicates: &[Duplicate]) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            let repeated = duplicates.iter().any(|duplicate| duplicate.index == index);
+            index += 1;
+            if repeated {
+                continue;
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out
+}
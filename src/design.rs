@@ -0,0 +1,117 @@
+//! Help for puzzle designers: the hardest board within a few moves of a
+//! chosen one.
+//!
+//! Designers often start from a layout that looks right, such as a picture
+//! almost assembled, and want the position near it that takes longest to
+//! solve. Every board within the given number of moves is scored, either by
+//! its exact optimal solution length or, much faster, by a heuristic.
+
+use crate::heuristic::{self, Heuristic};
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::collections::HashMap;
+
+/// Boards `hardest_within` examines before giving up.
+const MAX_BOARDS: usize = 1_000_000;
+
+/// How `Puzzle::hardest_within` scores boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hardness<'a> {
+    /// The optimal solution length, from the tablebase on 3x3 boards and by
+    /// `solve_walking_distance` otherwise, which is slow beyond 3x3.
+    OptimalLength,
+    /// The estimate of the heuristic of this name from `heuristic::NAMES`.
+    Heuristic(&'a str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nearby {
+    pub puzzle: Puzzle,
+    /// Moves from the starting board to `puzzle`, a shortest way there.
+    pub path: Vec<Move>,
+    /// The board's score under the chosen `Hardness`.
+    pub score: usize,
+    /// Boards scored, including the starting one.
+    pub examined: usize,
+}
+
+impl Puzzle {
+    /// The board within `k` moves of this one that scores highest under
+    /// `hardness`. Among equally hard boards the one closest to this board
+    /// wins, then the first in `Move::ALL` order, so results are
+    /// reproducible. Scoring by optimal length stops early once a board is
+    /// `k` moves harder than this one, since none can be harder.
+    pub fn hardest_within(&self, k: usize, hardness: Hardness) -> Result<Nearby, &'static str> {
+        if !self.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        let estimate = match hardness {
+            Hardness::OptimalLength => None,
+            Hardness::Heuristic(name) => Some(heuristic::by_name(name).ok_or("Unknown heuristic")?),
+        };
+        let score = |puzzle: &Puzzle| -> Result<usize, &'static str> {
+            match &estimate {
+                Some(estimate) => Ok(estimate.estimate(puzzle)),
+                None => match tablebase::distance_to_goal(puzzle) {
+                    Some(distance) => Ok(distance),
+                    None => Ok(puzzle.solve_walking_distance()?.len()),
+                },
+            }
+        };
+
+        // Breadth first, so each board is reached by a shortest path
+        let mut boards = vec![(self.clone(), None::<(usize, Move)>)];
+        let mut seen = HashMap::from([(self.clone(), 0)]);
+        let mut frontier = vec![0];
+        for _ in 0..k {
+            let mut next = Vec::new();
+            for &index in &frontier {
+                for movement in boards[index].0.legal_moves() {
+                    let mut board = boards[index].0.clone();
+                    board.apply_move(movement);
+                    if seen.contains_key(&board) {
+                        continue;
+                    }
+                    if boards.len() == MAX_BOARDS {
+                        return Err("Too many boards within that many moves");
+                    }
+                    seen.insert(board.clone(), boards.len());
+                    next.push(boards.len());
+                    boards.push((board, Some((index, movement))));
+                }
+            }
+            frontier = next;
+        }
+
+        let ceiling = match hardness {
+            Hardness::OptimalLength => Some(score(self)? + k),
+            Hardness::Heuristic(_) => None,
+        };
+        let mut best = (0, score(self)?);
+        let mut examined = 1;
+        for (index, (board, _)) in boards.iter().enumerate().skip(1) {
+            if ceiling == Some(best.1) {
+                break;
+            }
+            examined += 1;
+            let value = score(board)?;
+            if value > best.1 {
+                best = (index, value);
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut at = best.0;
+        while let Some((parent, movement)) = boards[at].1 {
+            path.push(movement);
+            at = parent;
+        }
+        path.reverse();
+        Ok(Nearby {
+            puzzle: boards[best.0].0.clone(),
+            path,
+            score: best.1,
+            examined,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Which results are the same on every platform, so replay files and share
+//! links resolve to the same boards and solutions on WASM, Linux and Windows.
+//!
+//! Given the same inputs, these never depend on the platform:
+//!
+//! - Seeded boards: `Puzzle::daily`, `competition::competition_scramble`,
+//!   and `shuffle_with` or `random_walk_with` fed by `daily::seeded_rng`.
+//! - Solutions from every engine, and from `adaptive` when the config sets
+//!   `deterministic`. The engines only use hash maps for lookups, never
+//!   iterate them, and break ties in fixed orders such as `Move::ALL`.
+//! - Anything derived from a solution, such as `Puzzle::ranked_moves`.
+//!
+//! These are not:
+//!
+//! - `Puzzle::shuffle` and `random_walk`, which draw from the thread's
+//!   random generator.
+//! - Anything limited by time: `adaptive` with a time budget, timeouts in
+//!   `scheduler`, `ladder` and `tune`, and all reported durations.
+//! - `Puzzle::mixedness`, whose logarithms come from the platform's maths
+//!   library and may differ in the last bits.
+//!
+//! `fingerprint` hashes a sample of the guaranteed results, so a build for a
+//! new target can be checked against `REFERENCE_FINGERPRINT`; running the
+//! tests on that target does so.
+//!
+//! Results also must not depend on how many boards are solved at once.
+//! `compare_worker_counts` solves the same boards through `Scheduler` with
+//! different worker counts and short time slices, so solves are paused and
+//! resumed on different threads, and reports any that came out differently.
+
+use crate::competition;
+use crate::config::Config;
+use crate::daily::{fnv1a, seeded_rng};
+use crate::puzzle::{Move, Puzzle};
+use crate::registry::SolverRegistry;
+use crate::scheduler::Scheduler;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// `fingerprint` as computed on x86_64 Linux.
+pub const REFERENCE_FINGERPRINT: u64 = 0xe519_7ca8_1b1d_2ea4;
+
+/// Hash of seeded boards and of every built-in engine's solution to them.
+/// Differs from `REFERENCE_FINGERPRINT` if any of them would resolve
+/// differently on this platform.
+pub fn fingerprint() -> u64 {
+    let mut record = String::new();
+    for size in 2..=5 {
+        let daily = Puzzle::daily(size, "2024-01-01").expect("Valid size");
+        writeln!(record, "{:#}", daily).unwrap();
+    }
+
+    let mut rng = seeded_rng("fingerprint");
+    let mut walked = Puzzle::new(4).expect("Valid size");
+    let walk = walked.random_walk_with(40, &mut rng);
+    writeln!(record, "{:#} {:?}", walked, walk).unwrap();
+    match competition::competition_scramble(3, 20, &mut rng) {
+        Ok(scramble) => writeln!(record, "{:#} {}", scramble.puzzle, scramble.lower_bound),
+        Err(err) => writeln!(record, "{}", err),
+    }
+    .unwrap();
+
+    let board = Puzzle::daily(3, "2024-01-01").expect("Valid size");
+    let registry = SolverRegistry::with_builtins();
+    let mut config = Config::default();
+    config.set("deterministic", "true");
+    for name in registry.names() {
+        let solver = registry.create(name, &config).expect("Registered engine");
+        writeln!(record, "{} {:?}", name, solver(&board)).unwrap();
+    }
+
+    fnv1a(record.bytes())
+}
+
+/// A board whose result changed with the number of workers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerMismatch {
+    /// Position of the board in the input.
+    pub board: usize,
+    pub workers: usize,
+    /// The result with the first worker count.
+    pub expected: Result<Vec<Move>, &'static str>,
+    pub found: Result<Vec<Move>, &'static str>,
+}
+
+/// Solves `boards` with `engine`, one of the interruptible engines, once for
+/// each of `worker_counts`, and returns the results that differ from those
+/// of the first count. Solution lengths must always agree, and the moves
+/// themselves too when the config sets `deterministic`. No timeout applies,
+/// so boards must be ones the engine can finish.
+pub fn compare_worker_counts(
+    engine: &str,
+    config: &Config,
+    boards: &[Puzzle],
+    worker_counts: &[usize],
+) -> Vec<WorkerMismatch> {
+    let strict = config.parse_or("deterministic", false);
+    let solve = |workers| {
+        let scheduler = Scheduler {
+            workers,
+            slice: Duration::from_millis(1),
+            timeout: Duration::MAX,
+        };
+        scheduler.solve_all(boards, engine, config)
+    };
+    let Some((&first, rest)) = worker_counts.split_first() else {
+        return Vec::new();
+    };
+    let expected = solve(first);
+
+    let mut mismatches = Vec::new();
+    for &workers in rest {
+        for (expected, found) in expected.iter().zip(solve(workers)) {
+            let agrees = match (&expected.outcome, &found.outcome) {
+                (Ok(a), Ok(b)) if strict => a == b,
+                (Ok(a), Ok(b)) => a.len() == b.len(),
+                (a, b) => a == b,
+            };
+            if !agrees {
+                mismatches.push(WorkerMismatch {
+                    board: found.index,
+                    workers,
+                    expected: expected.outcome.clone(),
+                    found: found.outcome,
+                });
+            }
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,65 @@
+use crate::puzzle::Puzzle;
+use std::collections::BTreeMap;
+
+/// How scrambled a board looks, independent of how far it is from solved.
+/// All scores are in `0.0..=1.0`, where 0 looks ordered and 1 looks random.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mixedness {
+    /// How close the inversion count is to that of a random permutation. Both
+    /// the solved board and the fully reversed one score 0.
+    pub inversion_score: f64,
+    /// Shannon entropy of the tiles' displacement vectors from their goal
+    /// cells, relative to the maximum possible. Sliding every tile by the same
+    /// offset scores 0.
+    pub displacement_entropy: f64,
+    /// Mean of the two scores above.
+    pub score: f64,
+}
+
+impl Puzzle {
+    pub fn mixedness(&self) -> Mixedness {
+        let tiles = self.rows() * self.cols() - 1;
+
+        let max_inversions = (tiles * tiles.saturating_sub(1) / 2).max(1);
+        let normalized = self.inversions() as f64 / max_inversions as f64;
+        let inversion_score = 1.0 - (2.0 * normalized - 1.0).abs();
+
+        let goals = self.goals();
+        // Ordered so the entropy terms are always summed in the same order;
+        // floating-point addition is not associative
+        let mut displacements: BTreeMap<(isize, isize), usize> = BTreeMap::new();
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                let value = self.tile_at(row, col) as usize;
+                if value != 0 {
+                    let (goal_row, goal_col) = goals[value];
+                    let offset = (
+                        row as isize - goal_row as isize,
+                        col as isize - goal_col as isize,
+                    );
+                    *displacements.entry(offset).or_default() += 1;
+                }
+            }
+        }
+
+        let entropy: f64 = displacements
+            .values()
+            .map(|&count| {
+                let p = count as f64 / tiles as f64;
+                -p * p.log2()
+            })
+            .sum();
+        let max_entropy = (tiles as f64).log2();
+        let displacement_entropy = if max_entropy > 0.0 {
+            entropy / max_entropy
+        } else {
+            0.0
+        };
+
+        Mixedness {
+            inversion_score,
+            displacement_entropy,
+            score: (inversion_score + displacement_entropy) / 2.0,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::entropy::Mixedness;
+use crate::puzzle::{Move, Puzzle};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    VeryHard,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub puzzle: Puzzle,
+    pub manhattan: usize,
+    pub linear_conflicts: usize,
+    pub heuristic: usize,
+    pub inversions: usize,
+    pub solvable: bool,
+    pub difficulty: Difficulty,
+    /// How scrambled the board looks, regardless of its distance from solved.
+    pub mixedness: Mixedness,
+    /// The legal move that lowers the heuristic the most, with the resulting
+    /// change in heuristic value.
+    pub suggested_move: Option<(Move, isize)>,
+    /// The closest well-known arrangement and how many tiles differ from it.
+    pub nearest_pattern: (&'static str, usize),
+}
+
+impl Difficulty {
+    fn from_heuristic(heuristic: usize) -> Self {
+        match heuristic {
+            0 => Difficulty::Trivial,
+            1..=10 => Difficulty::Easy,
+            11..=30 => Difficulty::Medium,
+            31..=50 => Difficulty::Hard,
+            _ => Difficulty::VeryHard,
+        }
+    }
+}
+
+/// Builds a report describing `puzzle` for learners: where the heuristic
+/// estimate comes from, whether the position can be solved, and a first move.
+pub fn explain(puzzle: &Puzzle) -> Explanation {
+    let heuristic = puzzle.heuristic();
+
+    let suggested_move = Move::ALL
+        .iter()
+        .filter_map(|&item| {
+            let mut next = puzzle.clone();
+            next.apply_move(item)
+                .then(|| (item, next.heuristic() as isize - heuristic as isize))
+        })
+        .min_by_key(|&(_, delta)| delta);
+
+    Explanation {
+        puzzle: puzzle.clone(),
+        manhattan: puzzle.manhattan_distance(),
+        linear_conflicts: puzzle.linear_conflicts(),
+        heuristic,
+        inversions: puzzle.inversions(),
+        solvable: puzzle.is_current_state_solvable(),
+        difficulty: Difficulty::from_heuristic(heuristic),
+        mixedness: puzzle.mixedness(),
+        suggested_move,
+        nearest_pattern: nearest_pattern(puzzle),
+    }
+}
+
+const PATTERNS: [&str; 4] = ["Solved", "Transposed", "Rotated", "Mirrored"];
+
+/// The tile `pattern` puts at a cell, given the goal board as `goal`.
+fn pattern_tile(pattern: &str, goal: &[Vec<u32>], row: usize, col: usize) -> u32 {
+    let (rows, cols) = (goal.len(), goal[0].len());
+    let (row, col) = match pattern {
+        "Transposed" => (col, row),
+        "Rotated" => (rows - 1 - row, cols - 1 - col),
+        "Mirrored" => (row, cols - 1 - col),
+        _ => (row, col),
+    };
+    goal[row][col]
+}
+
+/// The closest of the goal and its transposed, rotated and mirrored forms,
+/// with how many cells differ. Custom goals are compared as they are.
+fn nearest_pattern(puzzle: &Puzzle) -> (&'static str, usize) {
+    let (rows, cols) = (puzzle.rows(), puzzle.cols());
+    let mut goal = vec![vec![0; cols]; rows];
+    for (value, &(row, col)) in puzzle.goals().iter().enumerate() {
+        goal[row][col] = value as u32;
+    }
+
+    PATTERNS
+        .iter()
+        // Only a square board can be transposed in place
+        .filter(|&&pattern| pattern != "Transposed" || puzzle.is_square())
+        .map(|&pattern| {
+            let differences = (0..rows * cols)
+                .map(|i| (i / cols, i % cols))
+                .filter(|&(row, col)| {
+                    puzzle.tile_at(row, col) != pattern_tile(pattern, &goal, row, col)
+                })
+                .count();
+            (pattern, differences)
+        })
+        .min_by_key(|&(_, differences)| differences)
+        .unwrap()
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.puzzle)?;
+        writeln!(
+            f,
+            "Heuristic: {} = Manhattan distance {} + 2 x linear conflicts {}",
+            self.heuristic, self.manhattan, self.linear_conflicts
+        )?;
+
+        let (row, _) = self.puzzle.blank_position();
+        if !self.puzzle.has_standard_goal() {
+            writeln!(
+                f,
+                "Solvable: {} (the tiles' arrangement and the blank's distance from its goal cell need matching parity for the custom goal)",
+                self.solvable
+            )?;
+        } else if self.puzzle.cols() % 2 == 1 {
+            writeln!(
+                f,
+                "Solvable: {} ({} inversions, odd-width boards need an even count)",
+                self.solvable, self.inversions
+            )?;
+        } else {
+            let parity = if self.puzzle.rows() % 2 == 1 {
+                "an even"
+            } else {
+                "an odd"
+            };
+            writeln!(
+                f,
+                "Solvable: {} ({} inversions + blank row {}, even-width boards with {} rows need {} sum)",
+                self.solvable,
+                self.inversions,
+                row,
+                self.puzzle.rows(),
+                parity
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Difficulty: {:?} (at least {} moves)",
+            self.difficulty, self.heuristic
+        )?;
+
+        writeln!(
+            f,
+            "Mixedness: {:.2} (inversions {:.2}, displacement entropy {:.2})",
+            self.mixedness.score,
+            self.mixedness.inversion_score,
+            self.mixedness.displacement_entropy
+        )?;
+
+        match self.suggested_move {
+            Some((item, delta)) => writeln!(
+                f,
+                "Suggested move: {} (heuristic changes by {:+})",
+                item, delta
+            )?,
+            None => writeln!(f, "Suggested move: none")?,
+        }
+
+        let (name, differences) = self.nearest_pattern;
+        write!(
+            f,
+            "Nearest pattern: {} ({} cells differ)",
+            name, differences
+        )
+    }
+}
@@ -0,0 +1,404 @@
+//! Optimal search for boards whose explored states do not fit in memory,
+//! keeping them on disk with delayed duplicate detection.
+//!
+//! The search runs breadth-first within a bound on the estimated solution
+//! length, raised between iterations as IDA* raises it. Each layer of
+//! states at one depth goes to disk as sorted runs while it is generated,
+//! and runs are merged once there are too many. Duplicates are not looked
+//! up as states are generated; they are dropped when the layer's runs are
+//! merged, along with any state already in the layer before the one that
+//! was expanded. Every move changes the colour of the empty space's cell,
+//! so no state can reappear in the expanded layer itself.
+//!
+//! Memory holds one buffer of states and one key per run being merged.
+//! Solutions are traced back through the layers on disk, so they need no
+//! parent pointers either.
+
+use crate::puzzle::{Move, Puzzle};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+#[derive(Debug, Clone)]
+pub struct ExternalOptions {
+    /// Directory for layer files. Each search works in a directory of its
+    /// own inside it, created when the search starts and removed when it
+    /// ends.
+    pub dir: PathBuf,
+    /// States kept in memory before a run is written.
+    pub buffer_limit: usize,
+    /// Number of runs in a layer that triggers merging them into one.
+    pub max_runs: usize,
+}
+
+impl Default for ExternalOptions {
+    fn default() -> Self {
+        Self {
+            dir: std::env::temp_dir().join("slider-puzzle-external"),
+            buffer_limit: 1_000_000,
+            max_runs: 16,
+        }
+    }
+}
+
+/// Numbers the working directories of searches in this process.
+static NEXT_WORKSPACE: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory holding one search's files, removed with them on drop.
+struct Workspace {
+    dir: PathBuf,
+    next_file: usize,
+}
+
+impl Workspace {
+    /// Creates a directory inside `parent` that no other search, in this
+    /// process or another, is using.
+    fn new(parent: &Path) -> io::Result<Self> {
+        fs::create_dir_all(parent)?;
+        loop {
+            let number = NEXT_WORKSPACE.fetch_add(1, AtomicOrdering::Relaxed);
+            let dir = parent.join(format!("search-{}-{}", process::id(), number));
+            match fs::create_dir(&dir) {
+                Ok(()) => return Ok(Self { dir, next_file: 0 }),
+                // Left behind by an earlier process with the same id
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn file(&mut self) -> PathBuf {
+        self.next_file += 1;
+        self.dir.join(format!("{}.bin", self.next_file))
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A file of distinct fixed-length keys in ascending order, deleted on drop.
+struct Layer {
+    path: PathBuf,
+    key_len: usize,
+    len: u64,
+}
+
+impl Layer {
+    /// Writes `keys`, which must be ascending and distinct, to `path`.
+    fn write<'a>(
+        path: PathBuf,
+        key_len: usize,
+        keys: impl IntoIterator<Item = &'a [u8]>,
+    ) -> io::Result<Self> {
+        let mut layer = Self {
+            path,
+            key_len,
+            len: 0,
+        };
+        let mut writer = BufWriter::new(File::create(&layer.path)?);
+        for key in keys {
+            writer.write_all(key)?;
+            layer.len += 1;
+        }
+        writer.flush()?;
+        Ok(layer)
+    }
+
+    fn reader(&self) -> io::Result<KeyReader> {
+        Ok(KeyReader {
+            reader: BufReader::new(File::open(&self.path)?),
+            key_len: self.key_len,
+        })
+    }
+
+    /// Binary search of the file, one seek per step.
+    fn contains(&self, key: &[u8]) -> io::Result<bool> {
+        let mut file = File::open(&self.path)?;
+        let mut record = vec![0; self.key_len];
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = (low + high) / 2;
+            file.seek(SeekFrom::Start(mid * self.key_len as u64))?;
+            file.read_exact(&mut record)?;
+            match record.as_slice().cmp(key) {
+                Ordering::Equal => return Ok(true),
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Drop for Layer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+struct KeyReader {
+    reader: BufReader<File>,
+    key_len: usize,
+}
+
+impl KeyReader {
+    fn next_key(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut key = vec![0; self.key_len];
+        match self.reader.read_exact(&mut key) {
+            Ok(()) => Ok(Some(key)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Merges `runs` into one layer at `path`, keeping one copy of each key
+/// and none of those in `skip`.
+fn merge(runs: &[Layer], skip: Option<&Layer>, path: PathBuf, key_len: usize) -> io::Result<Layer> {
+    let mut readers = runs
+        .iter()
+        .map(Layer::reader)
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::new();
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some(key) = reader.next_key()? {
+            heap.push(Reverse((key, i)));
+        }
+    }
+    let mut skip = skip.map(Layer::reader).transpose()?;
+    let mut skip_key = match &mut skip {
+        Some(reader) => reader.next_key()?,
+        None => None,
+    };
+
+    let mut layer = Layer {
+        path,
+        key_len,
+        len: 0,
+    };
+    let mut writer = BufWriter::new(File::create(&layer.path)?);
+    let mut last: Option<Vec<u8>> = None;
+    while let Some(Reverse((key, i))) = heap.pop() {
+        if let Some(next) = readers[i].next_key()? {
+            heap.push(Reverse((next, i)));
+        }
+        if last.as_ref() == Some(&key) {
+            continue;
+        }
+        while skip_key.as_ref().is_some_and(|skipped| *skipped < key) {
+            skip_key = skip.as_mut().map_or(Ok(None), KeyReader::next_key)?;
+        }
+        if skip_key.as_ref() != Some(&key) {
+            writer.write_all(&key)?;
+            layer.len += 1;
+        }
+        last = Some(key);
+    }
+    writer.flush()?;
+    Ok(layer)
+}
+
+/// Collects the states of one layer as they are generated, writing a
+/// sorted run whenever the buffer fills.
+struct LayerWriter {
+    key_len: usize,
+    buffer: Vec<u8>,
+    buffer_limit: usize,
+    runs: Vec<Layer>,
+    max_runs: usize,
+}
+
+impl LayerWriter {
+    fn new(key_len: usize, options: &ExternalOptions) -> Self {
+        Self {
+            key_len,
+            buffer: Vec::new(),
+            buffer_limit: options.buffer_limit.max(1),
+            runs: Vec::new(),
+            max_runs: options.max_runs.max(2),
+        }
+    }
+
+    fn push(&mut self, key: &[u8], workspace: &mut Workspace) -> io::Result<()> {
+        debug_assert_eq!(key.len(), self.key_len);
+        self.buffer.extend_from_slice(key);
+        if self.buffer.len() >= self.buffer_limit * self.key_len {
+            self.flush(workspace)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, workspace: &mut Workspace) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let mut keys: Vec<&[u8]> = self.buffer.chunks(self.key_len).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let run = Layer::write(workspace.file(), self.key_len, keys)?;
+        self.buffer.clear();
+        self.runs.push(run);
+
+        if self.runs.len() > self.max_runs {
+            let merged = merge(&self.runs, None, workspace.file(), self.key_len)?;
+            self.runs = vec![merged];
+        }
+        Ok(())
+    }
+
+    /// The finished layer, without duplicates or any state in `skip`.
+    fn finish(mut self, skip: Option<&Layer>, workspace: &mut Workspace) -> io::Result<Layer> {
+        self.flush(workspace)?;
+        merge(&self.runs, skip, workspace.file(), self.key_len)
+    }
+}
+
+/// Packs a board into one byte per tile (two for boards with more than 256 cells).
+fn pack(puzzle: &Puzzle) -> Vec<u8> {
+    let tiles = puzzle.tiles();
+    if tiles.len() <= 256 {
+        tiles.iter().map(|&value| value as u8).collect()
+    } else {
+        tiles
+            .iter()
+            .flat_map(|&value| (value as u16).to_be_bytes())
+            .collect()
+    }
+}
+
+/// The board packed in `key`, solved towards `goal`.
+fn unpack(goal: &Puzzle, key: &[u8]) -> Puzzle {
+    let tiles: Vec<u32> = if goal.rows() * goal.cols() <= 256 {
+        key.iter().map(|&value| value as u32).collect()
+    } else {
+        key.chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as u32)
+            .collect()
+    };
+    let puzzle = Puzzle::from_tiles_rectangular(goal.rows(), goal.cols(), &tiles)
+        .expect("Keys are packed from valid boards");
+    if goal.has_standard_goal() {
+        puzzle
+    } else {
+        puzzle.with_goal(goal).expect("Same dimensions as the goal")
+    }
+}
+
+impl Puzzle {
+    /// A shortest solution by a breadth-first search whose layers live on
+    /// disk, for boards whose explored states do not fit in memory; see the
+    /// module documentation. Uses the same admissible estimate as
+    /// `solve_astar`, and needs fast disks on anything harder than 3x3.
+    pub fn solve_astar_external(
+        &self,
+        options: &ExternalOptions,
+    ) -> Result<Vec<Move>, &'static str> {
+        if !self.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        if self.is_solved() {
+            return Ok(Vec::new());
+        }
+        let mut workspace =
+            Workspace::new(&options.dir).map_err(|_| "Could not create search directory")?;
+        let mut bound = self.heuristic();
+        loop {
+            match self
+                .external_iteration(bound, options, &mut workspace)
+                .map_err(|_| "External search I/O error")?
+            {
+                Iteration::Solved(moves) => return Ok(moves),
+                Iteration::Exceeded(next) => bound = next,
+                Iteration::Exhausted => return Err("No solution found"),
+            }
+        }
+    }
+
+    /// Expands layer after layer, keeping states whose estimated solution
+    /// length is within `bound`.
+    fn external_iteration(
+        &self,
+        bound: usize,
+        options: &ExternalOptions,
+        workspace: &mut Workspace,
+    ) -> io::Result<Iteration> {
+        let goal = self.goal();
+        let start = pack(self);
+        let key_len = start.len();
+        let mut layers = vec![Layer::write(workspace.file(), key_len, [&start[..]])?];
+        let mut next_bound = usize::MAX;
+
+        while layers.last().is_some_and(|layer| layer.len > 0) {
+            let depth = layers.len() - 1;
+            let mut writer = LayerWriter::new(key_len, options);
+            let mut reader = layers[depth].reader()?;
+            while let Some(key) = reader.next_key()? {
+                let state = unpack(&goal, &key);
+                for movement in state.legal_moves() {
+                    let mut next = state.clone();
+                    next.apply_move(movement);
+                    let estimate = depth + 1 + next.heuristic();
+                    if estimate > bound {
+                        next_bound = next_bound.min(estimate);
+                        continue;
+                    }
+                    if next.is_solved() {
+                        let mut moves = trace_back(&layers, state)?;
+                        moves.push(movement);
+                        return Ok(Iteration::Solved(moves));
+                    }
+                    writer.push(&pack(&next), workspace)?;
+                }
+            }
+            let skip = depth.checked_sub(1).map(|below| &layers[below]);
+            let layer = writer.finish(skip, workspace)?;
+            layers.push(layer);
+        }
+
+        Ok(match next_bound {
+            usize::MAX => Iteration::Exhausted,
+            next => Iteration::Exceeded(next),
+        })
+    }
+}
+
+enum Iteration {
+    Solved(Vec<Move>),
+    /// No solution within the bound; the smallest estimate that exceeded it.
+    Exceeded(usize),
+    Exhausted,
+}
+
+/// The moves from the start to `state`, which is in the last of `layers`,
+/// found by looking up one of its neighbours in each layer below.
+fn trace_back(layers: &[Layer], mut state: Puzzle) -> io::Result<Vec<Move>> {
+    let mut moves = Vec::new();
+    for layer in layers[..layers.len() - 1].iter().rev() {
+        let mut found = false;
+        for movement in state.legal_moves() {
+            let mut previous = state.clone();
+            previous.apply_move(movement);
+            if layer.contains(&pack(&previous))? {
+                moves.push(movement.opposite());
+                state = previous;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "State has no neighbour in the layer below",
+            ));
+        }
+    }
+    moves.reverse();
+    Ok(moves)
+}
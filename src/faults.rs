@@ -0,0 +1,123 @@
+//! Carrying out a solution on unreliable hardware.
+//!
+//! A robot arm sometimes fails to slide a tile, or knocks one it should
+//! not have. `execute` plays a plan under a `FaultModel` that injects both
+//! kinds of mistake at random. After every step it compares the board with
+//! what the plan expected and, when they differ, asks for a new plan from
+//! the board as it really is. A controller for real hardware can use the
+//! same loop with observed boards in place of the simulated ones.
+
+use crate::puzzle::{Move, Puzzle};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Replans allowed per execution before giving up, so a fault rate near
+/// certainty fails rather than running forever.
+pub const DEFAULT_MAX_REPLANS: usize = 100;
+
+/// How often each kind of fault strikes, as probabilities per planned move.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FaultModel {
+    /// The planned move does not happen.
+    pub skip_probability: f64,
+    /// A random legal move happens after the planned one.
+    pub extra_probability: f64,
+}
+
+impl FaultModel {
+    pub fn new(skip_probability: f64, extra_probability: f64) -> Result<Self, &'static str> {
+        let valid = 0.0..=1.0;
+        if !valid.contains(&skip_probability) || !valid.contains(&extra_probability) {
+            return Err("Fault probabilities must be between 0 and 1");
+        }
+        Ok(Self {
+            skip_probability,
+            extra_probability,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// `movement`, the planned move at position `step` of the execution,
+    /// did not happen.
+    Skipped { step: usize, movement: Move },
+    /// `movement` happened unplanned after position `step`.
+    Extra { step: usize, movement: Move },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    /// Every move that really happened, planned or not.
+    pub performed: Vec<Move>,
+    pub faults: Vec<Fault>,
+    /// Times the remaining plan was thrown away and solved again.
+    pub replans: usize,
+    /// The board when the last plan ran out, solved if every plan was a
+    /// solution.
+    pub final_state: Puzzle,
+}
+
+/// Plays `plan` from `start`, injecting faults drawn from `rng`, and calls
+/// `replan` with the observed board whenever it differs from the expected
+/// one. Fails if `replan` does, or after `max_replans` replans.
+pub fn execute<F, R>(
+    start: &Puzzle,
+    plan: Vec<Move>,
+    model: FaultModel,
+    mut replan: F,
+    max_replans: usize,
+    rng: &mut R,
+) -> Result<Execution, &'static str>
+where
+    F: FnMut(&Puzzle) -> Result<Vec<Move>, &'static str>,
+    R: Rng + ?Sized,
+{
+    let mut observed = start.clone();
+    let mut plan = plan.into_iter();
+    let mut performed = Vec::new();
+    let mut faults = Vec::new();
+    let mut replans = 0;
+
+    let mut step = 0;
+    while let Some(movement) = plan.next() {
+        let mut expected = observed.clone();
+        if !expected.apply_move(movement) {
+            return Err("Plan contains an illegal move");
+        }
+
+        if rng.gen_bool(model.skip_probability) {
+            faults.push(Fault::Skipped { step, movement });
+        } else {
+            observed.apply_move(movement);
+            performed.push(movement);
+        }
+        if rng.gen_bool(model.extra_probability) {
+            let legal: Vec<Move> = observed.legal_moves().collect();
+            if let Some(&extra) = legal.choose(rng) {
+                observed.apply_move(extra);
+                performed.push(extra);
+                faults.push(Fault::Extra {
+                    step,
+                    movement: extra,
+                });
+            }
+        }
+        step += 1;
+
+        if observed != expected {
+            if replans == max_replans {
+                return Err("Too many faults to recover from");
+            }
+            replans += 1;
+            plan = replan(&observed)?.into_iter();
+        }
+    }
+
+    Ok(Execution {
+        performed,
+        faults,
+        replans,
+        final_state: observed,
+    })
+}
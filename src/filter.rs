@@ -0,0 +1,80 @@
+//! Filter mode: boards in, advice out, one line each, so programs in any
+//! language can drive the solver through a pipe.
+//!
+//! Each input line is a board in any form `Puzzle` parses. The reply is a
+//! line of its own, written and flushed before the next board is read:
+//! the next move or a whole solution, `solved` for a solved board, or
+//! `error: ` and the reason. Both kinds of reply stop searching once the
+//! time budget runs out, so replies come within about the budget and a
+//! caller can wait for each in turn.
+
+use crate::hints::CachedHint;
+use crate::puzzle::Puzzle;
+use crate::solution::Solution;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// What `Filter` replies with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reply {
+    /// The next move, as `CachedHint::hint_within` gives it. Boards that
+    /// follow its advice are answered from the cached solution at once.
+    NextMove,
+    /// A whole solution, moves separated by spaces, from
+    /// `Puzzle::solve_within`. Boards it cannot solve in time get an error.
+    Solution,
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    reply: Reply,
+    budget: Duration,
+    cache: CachedHint,
+}
+
+impl Filter {
+    pub fn new(reply: Reply, budget: Duration) -> Self {
+        Self {
+            reply,
+            budget,
+            cache: CachedHint::default(),
+        }
+    }
+
+    /// The reply to one line of input, without a line ending.
+    pub fn respond(&mut self, line: &str) -> String {
+        let puzzle: Puzzle = match line.trim().parse() {
+            Ok(puzzle) => puzzle,
+            Err(err) => return format!("error: {}", err),
+        };
+        if puzzle.is_solved() {
+            return "solved".to_string();
+        }
+        if !puzzle.is_current_state_solvable() {
+            return "error: Puzzle is not solvable".to_string();
+        }
+        match self.reply {
+            Reply::NextMove => match self.cache.hint_within(&puzzle, self.budget) {
+                Some(hint) => hint.movement.to_string(),
+                None => "error: No move found".to_string(),
+            },
+            Reply::Solution => match puzzle.solve_within(self.budget) {
+                Ok(bounded) => match bounded.moves {
+                    Some(moves) => Solution::new(moves).to_string(),
+                    None => "error: No solution found in time".to_string(),
+                },
+                Err(err) => format!("error: {}", err),
+            },
+        }
+    }
+
+    /// Replies to every line of `input` until it ends, flushing `output`
+    /// after each reply.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            writeln!(output, "{}", self.respond(&line?))?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
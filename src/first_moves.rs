@@ -0,0 +1,136 @@
+//! Which first moves start shortest solutions, and how far a heuristic
+//! underestimates, grouped by where the empty space starts.
+//!
+//! Strategy guides want to know whether, say, a blank in the corner should
+//! usually head along the top row first; search authors want to know
+//! whether trying moves in a fixed order wastes work, and on which boards a
+//! heuristic is weakest. Both come from solving many seeded scrambles
+//! exactly, so the same seed always gives the same report.
+
+use crate::daily::seeded_rng;
+use crate::heuristic::{self, Heuristic};
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlankStart {
+    /// Row and column of the empty space.
+    pub position: (usize, usize),
+    /// Scrambles with the empty space here.
+    pub boards: usize,
+    /// How many of those boards each move starts a shortest solution of,
+    /// in `Move::ALL` order. A board with several such moves counts for
+    /// each of them.
+    pub optimal_first_moves: [usize; 4],
+    /// Total of the optimal length less the heuristic's estimate.
+    pub total_error: usize,
+}
+
+impl BlankStart {
+    /// Average moves by which the heuristic underestimated, 0 without
+    /// boards.
+    pub fn average_error(&self) -> f64 {
+        if self.boards == 0 {
+            return 0.0;
+        }
+        self.total_error as f64 / self.boards as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstMoveReport {
+    pub size: usize,
+    pub heuristic: String,
+    /// One entry per cell, row by row.
+    pub by_blank: Vec<BlankStart>,
+}
+
+/// Solves `count` scrambles of the given size drawn from `seed` and tallies
+/// their optimal first moves and the error of the heuristic named
+/// `heuristic` by the blank's starting cell. Distances come from the
+/// tablebase on 3x3 boards and from optimal IDA* otherwise, which takes
+/// seconds to minutes per 4x4 board and is impractical on larger ones.
+pub fn analyze(
+    size: usize,
+    count: usize,
+    seed: &str,
+    heuristic: &str,
+) -> Result<FirstMoveReport, &'static str> {
+    let goal = Puzzle::new(size)?;
+    let estimate = heuristic::by_name(heuristic).ok_or("Unknown heuristic")?;
+    let mut by_blank: Vec<BlankStart> = (0..size * size)
+        .map(|cell| BlankStart {
+            position: (cell / size, cell % size),
+            ..Default::default()
+        })
+        .collect();
+
+    let mut rng = seeded_rng(seed);
+    for _ in 0..count {
+        let mut puzzle = goal.clone();
+        puzzle.shuffle_with(&mut rng);
+        let distance = optimal_distance(&puzzle)?;
+
+        let (row, col) = puzzle.blank_position();
+        let entry = &mut by_blank[row * size + col];
+        entry.boards += 1;
+        entry.total_error += distance.saturating_sub(estimate.estimate(&puzzle));
+        for (index, movement) in Move::ALL.into_iter().enumerate() {
+            let mut next = puzzle.clone();
+            if next.apply_move(movement) && optimal_distance(&next)? + 1 == distance {
+                entry.optimal_first_moves[index] += 1;
+            }
+        }
+    }
+
+    Ok(FirstMoveReport {
+        size,
+        heuristic: heuristic.to_string(),
+        by_blank,
+    })
+}
+
+fn optimal_distance(puzzle: &Puzzle) -> Result<usize, &'static str> {
+    match tablebase::distance_to_goal(puzzle) {
+        Some(distance) => Ok(distance),
+        None => Ok(puzzle.solve_walking_distance()?.len()),
+    }
+}
+
+impl fmt::Display for FirstMoveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>10}",
+            "Blank", "Boards", "Up", "Down", "Left", "Right", "Avg error"
+        )?;
+        for entry in &self.by_blank {
+            let share = |count: usize| {
+                if entry.boards == 0 {
+                    "-".to_string()
+                } else {
+                    format!("{:.0}%", 100.0 * count as f64 / entry.boards as f64)
+                }
+            };
+            let [up, down, left, right] = entry.optimal_first_moves.map(share);
+            writeln!(
+                f,
+                "{:>6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>10.2}",
+                format!("{},{}", entry.position.0, entry.position.1),
+                entry.boards,
+                up,
+                down,
+                left,
+                right,
+                entry.average_error()
+            )?;
+        }
+        write!(
+            f,
+            "Percentages are of boards where the move starts a shortest solution; \
+             errors are of {}.",
+            self.heuristic
+        )
+    }
+}
@@ -0,0 +1,757 @@
+//! Sliding puzzles on any arrangement of cells, such as hexagons or
+//! triangles, described by a `Geometry`: how many cells there are, which
+//! are next to which, and where each tile belongs.
+//!
+//! `GeometryBoard` plays and solves the puzzle on any geometry, so a new
+//! shape needs only its adjacency. `SquareGrid` is the usual rectangle,
+//! the same board as `Puzzle`; `HexGrid` and `TriangleGrid` are the others
+//! built in.
+//!
+//! Solvability follows Wilson's theorem on sliding puzzles over graphs, as
+//! for `walls::WalledBoard`. Where the cells have no choke point and are not
+//! a single loop, every arrangement can be solved if some loop has an odd
+//! number of cells, as on hexagons, and otherwise the parity rule of the
+//! square board decides. Other shapes fall between and only a search can
+//! tell.
+
+use crate::progress::Progress;
+use crate::puzzle::{Puzzle, MAX_SIZE};
+use crate::search::{self, SearchState, StateSearch};
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Most cells on a board, so its table of distances stays small.
+const MAX_CELLS: usize = 1024;
+
+/// The shape of a board.
+pub trait Geometry {
+    /// Cells are numbered from 0 up to this.
+    fn cells(&self) -> usize;
+
+    /// Cells sharing an edge with `cell`, in a fixed order. A tile can
+    /// slide between two cells if and only if each lists the other.
+    fn neighbours(&self, cell: usize) -> Vec<usize>;
+
+    /// The cell tile `value` belongs in, with 0 for the empty space. By
+    /// default the tiles fill the cells in order and the empty space takes
+    /// the last.
+    fn goal_cell(&self, value: u32) -> usize {
+        match value {
+            0 => self.cells() - 1,
+            value => value as usize - 1,
+        }
+    }
+
+    /// Lays out a label for each cell, in cell order, as text. By default
+    /// they are written on one line.
+    fn draw(&self, labels: &[String]) -> String {
+        labels.join(" ") + "\n"
+    }
+}
+
+/// The rectangular board of `Puzzle`, cells numbered row by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SquareGrid {
+    rows: usize,
+    cols: usize,
+}
+
+impl SquareGrid {
+    pub fn new(rows: usize, cols: usize) -> Result<Self, &'static str> {
+        if rows == 0 || cols == 0 {
+            return Err("Board size must be positive");
+        }
+        if rows > MAX_SIZE || cols > MAX_SIZE {
+            return Err("Board size is too large");
+        }
+        Ok(Self { rows, cols })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+}
+
+impl Geometry for SquareGrid {
+    fn cells(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        let mut cells = Vec::new();
+        if row > 0 {
+            cells.push(cell - self.cols);
+        }
+        if row + 1 < self.rows {
+            cells.push(cell + self.cols);
+        }
+        if col > 0 {
+            cells.push(cell - 1);
+        }
+        if col + 1 < self.cols {
+            cells.push(cell + 1);
+        }
+        cells
+    }
+
+    fn draw(&self, labels: &[String]) -> String {
+        draw_rows(labels, (0..self.rows).map(|_| (0, self.cols)))
+    }
+}
+
+/// A hexagon of hexagonal cells, `radius` cells from the centre to each
+/// corner, numbered row by row from the top. Every cell inside has six
+/// neighbours.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexGrid {
+    radius: usize,
+    /// Axial coordinates of each cell: column then row, both from 0.
+    coords: Vec<(usize, usize)>,
+}
+
+impl HexGrid {
+    pub fn new(radius: usize) -> Result<Self, &'static str> {
+        if radius == 0 {
+            return Err("Board size must be positive");
+        }
+        let side = 2 * radius + 1;
+        if 3 * radius * (radius + 1) + 1 > MAX_CELLS {
+            return Err("Board size is too large");
+        }
+        // Cells with both coordinates within the side and their sum within
+        // the middle band, which cuts two corners off the rhombus
+        let coords = (0..side)
+            .flat_map(|row| (0..side).map(move |col| (col, row)))
+            .filter(|&(col, row)| (radius..=3 * radius).contains(&(col + row)))
+            .collect();
+        Ok(Self { radius, coords })
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    fn cell_at(&self, col: usize, row: usize) -> Option<usize> {
+        self.coords.iter().position(|&coords| coords == (col, row))
+    }
+}
+
+impl Geometry for HexGrid {
+    fn cells(&self) -> usize {
+        self.coords.len()
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        let (col, row) = self.coords[cell];
+        [(0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)]
+            .into_iter()
+            .filter_map(|(dc, dr)| {
+                self.cell_at(col.checked_add_signed(dc)?, row.checked_add_signed(dr)?)
+            })
+            .collect()
+    }
+
+    fn draw(&self, labels: &[String]) -> String {
+        // Rows shrink by one cell away from the middle; half a cell of
+        // indent per missing cell keeps neighbours diagonal to each other
+        let side = 2 * self.radius + 1;
+        draw_rows(
+            labels,
+            (0..side).map(|row| (row.abs_diff(self.radius), side - row.abs_diff(self.radius))),
+        )
+    }
+}
+
+/// A strip of triangles, `rows` by `cols`, alternately pointing up and down
+/// along each row, numbered row by row. A triangle meets its neighbours on
+/// either side in its row, and the row above or below through its flat
+/// edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TriangleGrid {
+    rows: usize,
+    cols: usize,
+}
+
+impl TriangleGrid {
+    pub fn new(rows: usize, cols: usize) -> Result<Self, &'static str> {
+        if rows == 0 || cols == 0 {
+            return Err("Board size must be positive");
+        }
+        if rows * cols > MAX_CELLS {
+            return Err("Board size is too large");
+        }
+        Ok(Self { rows, cols })
+    }
+
+    /// Whether a cell's triangle points up, with its flat edge below.
+    pub fn points_up(&self, cell: usize) -> bool {
+        (cell / self.cols + cell % self.cols).is_multiple_of(2)
+    }
+}
+
+impl Geometry for TriangleGrid {
+    fn cells(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        let mut cells = Vec::new();
+        if self.points_up(cell) {
+            if row + 1 < self.rows {
+                cells.push(cell + self.cols);
+            }
+        } else if row > 0 {
+            cells.push(cell - self.cols);
+        }
+        if col > 0 {
+            cells.push(cell - 1);
+        }
+        if col + 1 < self.cols {
+            cells.push(cell + 1);
+        }
+        cells
+    }
+
+    fn draw(&self, labels: &[String]) -> String {
+        draw_rows(labels, (0..self.rows).map(|_| (0, self.cols)))
+    }
+}
+
+/// Writes `labels` as rows of the given indent, in half cells, and length,
+/// each label right-aligned in a cell wide enough for the longest.
+fn draw_rows(labels: &[String], rows: impl Iterator<Item = (usize, usize)>) -> String {
+    let width = labels.iter().map(String::len).max().unwrap_or(0).max(2);
+    let mut out = String::new();
+    let mut labels = labels.iter();
+    for (indent, len) in rows {
+        out.push_str(&" ".repeat(indent * (width + 1) / 2));
+        for label in labels.by_ref().take(len) {
+            out.push_str(&format!("{:>width$} ", label));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// A sliding puzzle on the cells of a geometry. A move names the cell of
+/// the tile that slides into the empty space.
+#[derive(Debug, Clone)]
+pub struct GeometryBoard<G> {
+    geometry: Arc<G>,
+    /// The tile in each cell, with 0 for the empty space.
+    cells: Vec<u32>,
+    blank: usize,
+    /// Fixed by the geometry, so shared between copies.
+    layout: Arc<Layout>,
+}
+
+#[derive(Debug)]
+struct Layout {
+    neighbours: Vec<Vec<usize>>,
+    /// The cell each tile belongs in, indexed by tile value. Entry 0 is the
+    /// empty space's goal.
+    goals: Vec<usize>,
+    /// Moves between every two cells, `u16::MAX` where they are not
+    /// connected, indexed by goal cell and then cell.
+    distances: Vec<u16>,
+}
+
+impl<G: Geometry> GeometryBoard<G> {
+    /// The solved board.
+    pub fn new(geometry: G) -> Result<Self, &'static str> {
+        let layout = Layout::new(&geometry)?;
+        let mut cells = vec![0; layout.goals.len()];
+        for (value, &cell) in layout.goals.iter().enumerate() {
+            cells[cell] = value as u32;
+        }
+        Ok(Self {
+            blank: layout.goals[0],
+            geometry: Arc::new(geometry),
+            cells,
+            layout: Arc::new(layout),
+        })
+    }
+
+    /// Builds a board from the tile in each cell, with 0 for the empty
+    /// space and each tile from 1 up once.
+    pub fn from_tiles(geometry: G, tiles: &[u32]) -> Result<Self, &'static str> {
+        let mut board = Self::new(geometry)?;
+        if tiles.len() != board.cells.len() {
+            return Err("Wrong number of tiles for board size");
+        }
+        let mut seen = vec![false; tiles.len()];
+        for &value in tiles {
+            match seen.get_mut(value as usize) {
+                Some(slot) if !*slot => *slot = true,
+                Some(_) => return Err("Duplicate tile"),
+                None => return Err("Tile value out of range"),
+            }
+        }
+        board.cells = tiles.to_vec();
+        board.blank = tiles.iter().position(|&value| value == 0).unwrap_or(0);
+        Ok(board)
+    }
+
+    pub fn geometry(&self) -> &G {
+        &self.geometry
+    }
+
+    /// The tile in each cell, with 0 for the empty space.
+    pub fn tiles(&self) -> &[u32] {
+        &self.cells
+    }
+
+    pub fn blank_cell(&self) -> usize {
+        self.blank
+    }
+
+    /// Cells whose tile can slide into the empty space, in the geometry's
+    /// order.
+    pub fn moves(&self) -> &[usize] {
+        &self.layout.neighbours[self.blank]
+    }
+
+    /// Slides the tile at `cell` into the empty space, returning whether
+    /// the move was legal.
+    pub fn apply_move(&mut self, cell: usize) -> bool {
+        if !self.moves().contains(&cell) {
+            return false;
+        }
+        self.cells.swap(self.blank, cell);
+        self.blank = cell;
+        true
+    }
+
+    /// Applies all of `moves` or none of them. On failure, returns the
+    /// position of the first illegal move.
+    pub fn apply_moves(&mut self, moves: &[usize]) -> Result<(), usize> {
+        let start = self.blank;
+        for (index, &cell) in moves.iter().enumerate() {
+            if !self.apply_move(cell) {
+                // Each move's empty cell is the cell the one before moved
+                let previous = std::iter::once(start).chain(moves[..index].iter().copied());
+                for cell in previous.collect::<Vec<_>>().into_iter().rev() {
+                    self.apply_move(cell);
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.layout
+            .goals
+            .iter()
+            .enumerate()
+            .all(|(value, &cell)| self.cells[cell] == value as u32)
+    }
+
+    /// Whether the goal can be reached, or `None` where only a search can
+    /// tell; see the module notes.
+    pub fn is_solvable(&self) -> Option<bool> {
+        let neighbours = |cell: usize| self.layout.neighbours[cell].iter().copied();
+        let open = reachable_from(self.cells.len(), self.blank, neighbours);
+        // Tiles the empty space can never reach must already be home
+        let stuck_home = (0..self.cells.len())
+            .filter(|&cell| !open[cell])
+            .all(|cell| self.layout.goals[self.cells[cell] as usize] == cell);
+        if !stuck_home {
+            return Some(false);
+        }
+
+        let component: Vec<usize> = (0..self.cells.len()).filter(|&cell| open[cell]).collect();
+        let goal = self.goal_values();
+        if component
+            .iter()
+            .all(|&cell| self.layout.neighbours[cell].len() <= 2)
+        {
+            return Some(keeps_order(&component, neighbours, &self.cells, &goal));
+        }
+
+        if let Some(colours) = two_colouring(self.cells.len(), &component, neighbours) {
+            // Each move is one swap with the empty space and moves it to a
+            // cell of the other colour, so the two parities change together.
+            // The goal's own order along the cells counts as well
+            let ranks = |values: &[u32]| -> Vec<u32> {
+                component
+                    .iter()
+                    .map(|&cell| match values[cell] {
+                        0 => u32::MAX,
+                        value => value,
+                    })
+                    .collect()
+            };
+            let swaps = Puzzle::count_inversions(&ranks(&self.cells))
+                + Puzzle::count_inversions(&ranks(&goal));
+            let moved = colours[self.blank] != colours[self.layout.goals[0]];
+            if swaps.is_multiple_of(2) == moved {
+                return Some(false);
+            }
+        }
+        // Wilson's exception: a loop of six with one extra cell joining two
+        // opposite cells, which is neither bipartite nor fully solvable
+        let branches = component
+            .iter()
+            .filter(|&&cell| self.layout.neighbours[cell].len() > 2)
+            .count();
+        if has_choke_point(self.cells.len(), &component, neighbours)
+            || (component.len() == 7 && branches == 2)
+        {
+            None
+        } else {
+            Some(true)
+        }
+    }
+
+    /// Sum of the moves each tile needs to reach its goal cell. Never
+    /// overestimates, since each move shifts one tile to a neighbouring
+    /// cell.
+    pub fn distance_estimate(&self) -> usize {
+        let cells = self.cells.len();
+        (0..cells)
+            .filter(|&cell| self.cells[cell] != 0)
+            .map(|cell| {
+                let goal = self.layout.goals[self.cells[cell] as usize];
+                self.layout.distances[goal * cells + cell] as usize
+            })
+            .sum()
+    }
+
+    /// A shortest solution, as the cells moved, by IDA* guided by
+    /// `distance_estimate`. Fails if the board cannot be solved, or if the
+    /// search grows past a fixed node limit, which is how unsolvable shapes
+    /// with choke points end.
+    pub fn solve(&self) -> Result<Vec<usize>, &'static str> {
+        self.solve_with_progress(|_| true)
+    }
+
+    /// Like `solve`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<usize>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if self.is_solvable() == Some(false) {
+            return Err("Puzzle is not solvable");
+        }
+        let board = GeometryBoard {
+            geometry: Arc::clone(&self.geometry),
+            cells: self.cells.clone(),
+            blank: self.blank,
+            layout: Arc::clone(&self.layout),
+        };
+        search::run(StateSearch::new(board), callback)
+    }
+
+    /// The tile that belongs in each cell, with 0 for the empty space.
+    fn goal_values(&self) -> Vec<u32> {
+        let mut values = vec![0; self.cells.len()];
+        for (value, &cell) in self.layout.goals.iter().enumerate() {
+            values[cell] = value as u32;
+        }
+        values
+    }
+}
+
+impl GeometryBoard<SquareGrid> {
+    /// The same arrangement as `puzzle`, which must have the standard goal.
+    pub fn from_puzzle(puzzle: &Puzzle) -> Result<Self, &'static str> {
+        if !puzzle.has_standard_goal() {
+            return Err("Geometry boards only support the standard goal");
+        }
+        Self::from_tiles(
+            SquareGrid::new(puzzle.rows(), puzzle.cols())?,
+            &puzzle.tiles(),
+        )
+    }
+
+    /// The same arrangement as a `Puzzle`.
+    pub fn to_puzzle(&self) -> Puzzle {
+        let grid = self.geometry();
+        Puzzle::from_tiles_rectangular(grid.rows, grid.cols, &self.cells)
+            .expect("A square grid board holds a valid arrangement")
+    }
+}
+
+impl Layout {
+    fn new<G: Geometry>(geometry: &G) -> Result<Self, &'static str> {
+        let cells = geometry.cells();
+        if cells == 0 {
+            return Err("Board needs a cell");
+        }
+        if cells > MAX_CELLS {
+            return Err("Board size is too large");
+        }
+        let neighbours: Vec<Vec<usize>> =
+            (0..cells).map(|cell| geometry.neighbours(cell)).collect();
+        let symmetric = (0..cells).all(|cell| {
+            neighbours[cell]
+                .iter()
+                .all(|&next| next < cells && next != cell && neighbours[next].contains(&cell))
+        });
+        if !symmetric {
+            return Err("Geometry neighbours must be mutual");
+        }
+
+        let goals: Vec<usize> = (0..cells as u32)
+            .map(|value| geometry.goal_cell(value))
+            .collect();
+        let mut taken = vec![false; cells];
+        for &cell in &goals {
+            match taken.get_mut(cell) {
+                Some(slot) if !*slot => *slot = true,
+                _ => return Err("Geometry goal must put one tile in each cell"),
+            }
+        }
+
+        let mut distances = vec![u16::MAX; cells * cells];
+        for goal in 0..cells {
+            let row = &mut distances[goal * cells..(goal + 1) * cells];
+            row[goal] = 0;
+            let mut queue = VecDeque::from([goal]);
+            while let Some(cell) = queue.pop_front() {
+                for &next in &neighbours[cell] {
+                    if row[next] == u16::MAX {
+                        row[next] = row[cell] + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            neighbours,
+            goals,
+            distances,
+        })
+    }
+}
+
+/// Boards are equal when their geometries and tiles are.
+impl<G: PartialEq> PartialEq for GeometryBoard<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells && self.geometry == other.geometry
+    }
+}
+
+impl<G: Eq> Eq for GeometryBoard<G> {}
+
+impl<G: Hash> Hash for GeometryBoard<G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.geometry.hash(state);
+        self.cells.hash(state);
+    }
+}
+
+/// Cells connected to `start`.
+pub(crate) fn reachable_from<I>(
+    cells: usize,
+    start: usize,
+    neighbours: impl Fn(usize) -> I,
+) -> Vec<bool>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut seen = vec![false; cells];
+    seen[start] = true;
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        for next in neighbours(cell) {
+            if !seen[next] {
+                seen[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+/// A colour for each cell of `component` such that neighbours differ, or
+/// `None` if it has a loop of odd length.
+fn two_colouring<I>(
+    cells: usize,
+    component: &[usize],
+    neighbours: impl Fn(usize) -> I,
+) -> Option<Vec<bool>>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut colours = vec![None; cells];
+    colours[component[0]] = Some(false);
+    let mut queue = VecDeque::from([component[0]]);
+    while let Some(cell) = queue.pop_front() {
+        let colour = colours[cell]?;
+        for next in neighbours(cell) {
+            match colours[next] {
+                None => {
+                    colours[next] = Some(!colour);
+                    queue.push_back(next);
+                }
+                Some(other) if other == colour => return None,
+                Some(_) => {}
+            }
+        }
+    }
+    Some(
+        colours
+            .into_iter()
+            .map(|colour| colour.unwrap_or(false))
+            .collect(),
+    )
+}
+
+/// On a corridor or loop of cells, whether the tiles read along it, `tiles`
+/// giving the one in each cell, are in the order of `goal`, allowing for
+/// rotation around a loop. Empty cells are 0 in both.
+pub(crate) fn keeps_order<I>(
+    component: &[usize],
+    neighbours: impl Fn(usize) -> I,
+    tiles: &[u32],
+    goal: &[u32],
+) -> bool
+where
+    I: Iterator<Item = usize>,
+{
+    let start = component
+        .iter()
+        .copied()
+        .find(|&cell| neighbours(cell).count() <= 1)
+        .unwrap_or(component[0]);
+    let mut along = vec![start];
+    let mut previous = None;
+    let mut current = start;
+    while let Some(next) = neighbours(current).find(|&next| Some(next) != previous && next != start)
+    {
+        along.push(next);
+        previous = Some(current);
+        current = next;
+    }
+
+    let read = |values: &[u32]| -> Vec<u32> {
+        along
+            .iter()
+            .map(|&cell| values[cell])
+            .filter(|&value| value != 0)
+            .collect()
+    };
+    let current = read(tiles);
+    let goal_values = read(goal);
+    let is_loop = along.len() > 2 && component.len() == along.len() && {
+        let last = *along.last().expect("The walk starts at a cell");
+        neighbours(last).any(|cell| cell == start)
+    };
+    if !is_loop {
+        return current == goal_values;
+    }
+    let Some(first) = current.first() else {
+        return true;
+    };
+    let offset = goal_values.iter().position(|value| value == first);
+    offset.is_some_and(|offset| {
+        goal_values[offset..]
+            .iter()
+            .chain(&goal_values[..offset])
+            .eq(&current)
+    })
+}
+
+/// Whether removing one cell would split `component`, found by Tarjan's
+/// depth-first search without recursion.
+pub(crate) fn has_choke_point<I>(
+    cells: usize,
+    component: &[usize],
+    neighbours: impl Fn(usize) -> I,
+) -> bool
+where
+    I: Iterator<Item = usize>,
+{
+    const UNSEEN: usize = usize::MAX;
+    let root = component[0];
+    let mut order = vec![UNSEEN; cells];
+    let mut low = vec![0; cells];
+    let mut time = 0;
+    let mut root_children = 0;
+    // Each frame is a cell, its parent and how many neighbours are done
+    let mut stack = vec![(root, UNSEEN, 0)];
+    order[root] = 0;
+    low[root] = 0;
+    while let Some(&mut (cell, parent, ref mut done)) = stack.last_mut() {
+        let next = neighbours(cell).nth(*done);
+        *done += 1;
+        match next {
+            Some(next) if order[next] == UNSEEN => {
+                time += 1;
+                order[next] = time;
+                low[next] = time;
+                if cell == root {
+                    root_children += 1;
+                }
+                stack.push((next, cell, 0));
+            }
+            Some(next) if next != parent => low[cell] = low[cell].min(order[next]),
+            Some(_) => {}
+            None => {
+                stack.pop();
+                if parent != UNSEEN {
+                    low[parent] = low[parent].min(low[cell]);
+                    if parent != root && low[cell] >= order[parent] {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    root_children > 1
+}
+
+impl<G: Geometry> SearchState for GeometryBoard<G> {
+    /// The cell whose tile slides into the empty space.
+    type Move = usize;
+
+    fn moves(&self) -> Vec<usize> {
+        GeometryBoard::moves(self).to_vec()
+    }
+
+    fn apply(&mut self, cell: usize) -> usize {
+        let blank = self.blank;
+        self.apply_move(cell);
+        blank
+    }
+
+    fn is_solved(&self) -> bool {
+        GeometryBoard::is_solved(self)
+    }
+
+    fn estimate(&self) -> usize {
+        self.distance_estimate()
+    }
+}
+
+impl<G: Geometry> fmt::Display for GeometryBoard<G> {
+    /// Draws the board as its geometry lays it out, with `.` for the empty
+    /// space. The alternate flag (`{:#}`) instead writes the tiles in cell
+    /// order on one line, as `from_tiles` takes them.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let tiles: Vec<String> = self.cells.iter().map(u32::to_string).collect();
+            return write!(f, "{}", tiles.join(","));
+        }
+        let labels: Vec<String> = self
+            .cells
+            .iter()
+            .map(|&value| match value {
+                0 => ".".to_string(),
+                value => value.to_string(),
+            })
+            .collect();
+        write!(f, "{}", self.geometry.draw(&labels))
+    }
+}
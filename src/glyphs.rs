@@ -0,0 +1,112 @@
+//! Boards drawn with pictures or letters in place of tile numbers, for
+//! picture puzzles in terminals and chat.
+//!
+//! Emoji and East Asian characters take two columns in a terminal, so cells
+//! are padded by display width rather than by character count to keep the
+//! grid aligned. Tiles beyond the end of a glyph set are drawn as numbers.
+
+use crate::puzzle::Puzzle;
+use unicode_width::UnicodeWidthStr;
+
+/// Names of the built-in glyph sets, in the order `GlyphSet::by_name`
+/// documents them.
+pub const NAMES: [&str; 4] = ["letters", "fullwidth", "blocks", "fruit"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphSet {
+    /// The glyph for tile 1 first.
+    glyphs: Vec<String>,
+    /// Drawn for the empty space, which is left blank if this is empty.
+    blank: String,
+}
+
+impl GlyphSet {
+    /// A set drawing tile `n` as the `n`th of `glyphs`, and the empty space
+    /// as blank cells.
+    pub fn new<I, S>(glyphs: I) -> Result<Self, &'static str>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let glyphs: Vec<String> = glyphs.into_iter().map(Into::into).collect();
+        if glyphs.is_empty() {
+            return Err("Glyph set is empty");
+        }
+        if glyphs.iter().any(|glyph| glyph.width() == 0) {
+            return Err("Glyphs must take up space");
+        }
+        Ok(Self {
+            glyphs,
+            blank: String::new(),
+        })
+    }
+
+    /// Draws the empty space as `blank` instead of leaving it empty.
+    pub fn with_blank(mut self, blank: &str) -> Self {
+        self.blank = blank.to_string();
+        self
+    }
+
+    /// One of the built-in sets listed in `NAMES`: `letters` (A to Z, then a
+    /// to z), `fullwidth` (double-width letters for CJK terminals),
+    /// `blocks` (coloured squares, with a white one for the empty space)
+    /// and `fruit` (sixteen fruit, enough for a 4x4 board).
+    pub fn by_name(name: &str) -> Option<Self> {
+        let set = match name {
+            "letters" => Self::new(('A'..='Z').chain('a'..='z').map(String::from)),
+            "fullwidth" => Self::new(('Ａ'..='Ｚ').map(String::from)),
+            "blocks" => Self::new(["🟥", "🟧", "🟨", "🟩", "🟦", "🟪", "🟫", "⬛"])
+                .map(|set| set.with_blank("⬜")),
+            "fruit" => Self::new([
+                "🍎", "🍐", "🍊", "🍋", "🍌", "🍉", "🍇", "🍓", "🫐", "🍈", "🍒", "🍑", "🥭", "🍍",
+                "🥥", "🥝",
+            ]),
+            _ => return None,
+        };
+        set.ok()
+    }
+
+    /// A built-in set by name, or else a set of its own listed in `text`,
+    /// glyphs separated by whitespace.
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        match Self::by_name(text) {
+            Some(set) => Ok(set),
+            None => Self::new(text.split_whitespace()),
+        }
+    }
+
+    /// The glyph drawn for `value`, with 0 for the empty space.
+    pub fn glyph(&self, value: u32) -> String {
+        match value {
+            0 => self.blank.clone(),
+            value => match self.glyphs.get(value as usize - 1) {
+                Some(glyph) => glyph.clone(),
+                None => value.to_string(),
+            },
+        }
+    }
+
+    /// Draws a board as a grid like `Puzzle`'s `Display`, with every cell
+    /// padded to the display width of the widest glyph on it.
+    pub fn render(&self, puzzle: &Puzzle) -> String {
+        self.render_tiles(puzzle.cols(), &puzzle.tiles())
+    }
+
+    /// Draws tiles listed row by row, `cols` to a row, with 0 for empty
+    /// cells, so boards other than `Puzzle` can be drawn too.
+    pub fn render_tiles(&self, cols: usize, tiles: &[u32]) -> String {
+        let cells: Vec<String> = tiles.iter().map(|&value| self.glyph(value)).collect();
+        let width = cells.iter().map(|cell| cell.width()).max().unwrap_or(0);
+        let mut out = String::new();
+        for row in cells.chunks(cols.max(1)) {
+            for cell in row {
+                // Format padding counts characters, not columns
+                out.push_str(&" ".repeat(width - cell.width()));
+                out.push_str(cell);
+                out.push(' ');
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
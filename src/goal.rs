@@ -0,0 +1,68 @@
+use crate::puzzle::Puzzle;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Boards up to this many rows and columns get a cached table; larger ones
+/// build theirs on demand.
+const MAX_CACHED_SIZE: usize = 16;
+
+/// Custom goals cached at once; the cache is emptied when it fills up.
+const MAX_CACHED_GOALS: usize = 64;
+
+/// Cached tables for boards with some number of rows, indexed by columns.
+type TablesByCols = [OnceLock<Vec<(usize, usize)>>; MAX_CACHED_SIZE + 1];
+
+/// Indexed by rows, then columns.
+static TABLES: [TablesByCols; MAX_CACHED_SIZE + 1] =
+    [const { [const { OnceLock::new() }; MAX_CACHED_SIZE + 1] }; MAX_CACHED_SIZE + 1];
+
+type GoalCache = HashMap<Puzzle, Arc<[(usize, usize)]>>;
+
+static CUSTOM: OnceLock<Mutex<GoalCache>> = OnceLock::new();
+
+/// Goal row and column of every tile value on a `rows` x `cols` board,
+/// indexed by value. The empty space (0) belongs in the bottom-right corner.
+/// Tables are cached by shape.
+pub(crate) fn goal_positions(rows: usize, cols: usize) -> Cow<'static, [(usize, usize)]> {
+    match TABLES.get(rows).and_then(|by_cols| by_cols.get(cols)) {
+        Some(table) => Cow::Borrowed(table.get_or_init(|| build(rows, cols))),
+        None => Cow::Owned(build(rows, cols)),
+    }
+}
+
+fn build(rows: usize, cols: usize) -> Vec<(usize, usize)> {
+    let cells = rows * cols;
+    (0..cells)
+        .map(|value| {
+            let index = if value == 0 { cells - 1 } else { value - 1 };
+            (index / cols, index % cols)
+        })
+        .collect()
+}
+
+/// Like `goal_positions`, but for an arbitrary goal arrangement. Meant to be
+/// called once per solve rather than per node; tables are cached by goal.
+pub(crate) fn goal_positions_of(goal: &Puzzle) -> Arc<[(usize, usize)]> {
+    let mut cache = CUSTOM
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(table) = cache.get(goal) {
+        return Arc::clone(table);
+    }
+
+    let mut table = vec![(0, 0); goal.rows() * goal.cols()];
+    for row in 0..goal.rows() {
+        for col in 0..goal.cols() {
+            table[goal.tile_at(row, col) as usize] = (row, col);
+        }
+    }
+    let table: Arc<[(usize, usize)]> = table.into();
+
+    if cache.len() >= MAX_CACHED_GOALS {
+        cache.clear();
+    }
+    cache.insert(goal.clone(), Arc::clone(&table));
+    table
+}
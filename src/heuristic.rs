@@ -0,0 +1,222 @@
+//! The heuristics listed by `catalog::heuristics`, looked up by name so that
+//! engines taking an estimate can be run with any of them and their node
+//! counts compared on the same board, and the `Heuristic` trait for plugging
+//! in estimates of one's own.
+
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, IdaStarSearch};
+use crate::tablebase;
+
+/// An estimate of the moves left to the puzzle's goal. It must be 0 on the
+/// goal and only there: searches stop with `search::HEURISTIC_GOAL_MISMATCH`
+/// otherwise. Searches only guarantee shortest solutions with estimates
+/// that never exceed the true distance.
+pub trait Heuristic {
+    fn estimate(&self, puzzle: &Puzzle) -> usize;
+
+    /// The estimate for `child`, reached from `parent` by `movement`, given
+    /// `parent`'s estimate. Searches call this for every node they generate,
+    /// so estimates that can be updated from the moved tile alone should
+    /// override it; by default it starts afresh.
+    fn estimate_after(
+        &self,
+        parent: &Puzzle,
+        estimate: usize,
+        movement: Move,
+        child: &Puzzle,
+    ) -> usize {
+        let _ = (parent, estimate, movement);
+        self.estimate(child)
+    }
+}
+
+/// Plain functions and closures, such as `Puzzle::manhattan_distance`, can
+/// be used wherever a heuristic is expected.
+impl<F> Heuristic for F
+where
+    F: Fn(&Puzzle) -> usize,
+{
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        self(puzzle)
+    }
+}
+
+impl Heuristic for Box<dyn Heuristic> {
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        (**self).estimate(puzzle)
+    }
+
+    fn estimate_after(
+        &self,
+        parent: &Puzzle,
+        estimate: usize,
+        movement: Move,
+        child: &Puzzle,
+    ) -> usize {
+        (**self).estimate_after(parent, estimate, movement, child)
+    }
+}
+
+/// `Puzzle::manhattan_distance`, updated from the moved tile alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        puzzle.manhattan_distance()
+    }
+
+    fn estimate_after(
+        &self,
+        parent: &Puzzle,
+        estimate: usize,
+        movement: Move,
+        child: &Puzzle,
+    ) -> usize {
+        let goals = parent.goals();
+        let (from, to) = moved_tile(parent, movement);
+        let value = child.tile_at(to.0, to.1);
+        let goal = goals[value as usize];
+        estimate + distance(to, goal) - distance(from, goal)
+    }
+}
+
+/// `Puzzle::heuristic`, the Manhattan distance plus two moves per linear
+/// conflict, updated from the moved tile and the two lines it moves
+/// between. A tile moving along a row keeps its place in the order of that
+/// row, so only the conflicts of the columns it leaves and enters can
+/// change, and likewise for a tile moving along a column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinearConflict;
+
+impl Heuristic for LinearConflict {
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        puzzle.heuristic()
+    }
+
+    fn estimate_after(
+        &self,
+        parent: &Puzzle,
+        estimate: usize,
+        movement: Move,
+        child: &Puzzle,
+    ) -> usize {
+        let goals = parent.goals();
+        let (from, to) = moved_tile(parent, movement);
+        let value = child.tile_at(to.0, to.1);
+        let goal = goals[value as usize];
+
+        let conflicts = |puzzle: &Puzzle| {
+            if from.0 == to.0 {
+                puzzle.column_conflicts(from.1, &goals) + puzzle.column_conflicts(to.1, &goals)
+            } else {
+                puzzle.row_conflicts(from.0, &goals) + puzzle.row_conflicts(to.0, &goals)
+            }
+        };
+        estimate + distance(to, goal) + 2 * conflicts(child)
+            - distance(from, goal)
+            - 2 * conflicts(parent)
+    }
+}
+
+/// Cells the tile moved by `movement` on `parent` leaves and enters.
+fn moved_tile(parent: &Puzzle, movement: Move) -> ((usize, usize), (usize, usize)) {
+    let from = parent
+        .moved_tile_position(movement)
+        .expect("The move was made on the parent");
+    (from, parent.blank_position())
+}
+
+fn distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// The largest of several estimates. Never overestimates if none of them
+/// does, and is at least as strong as each, at the cost of computing all of
+/// them.
+pub struct MaxOf(Vec<Box<dyn Heuristic>>);
+
+impl MaxOf {
+    /// Fails if `heuristics` is empty, as there would be nothing to tell
+    /// the goal from any other board.
+    pub fn new(heuristics: Vec<Box<dyn Heuristic>>) -> Result<Self, &'static str> {
+        if heuristics.is_empty() {
+            return Err("MaxOf needs at least one heuristic");
+        }
+        Ok(Self(heuristics))
+    }
+}
+
+impl Heuristic for MaxOf {
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        self.0
+            .iter()
+            .map(|heuristic| heuristic.estimate(puzzle))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Puzzle {
+    /// Optimal IDA* guided by `heuristic`. The solution is only guaranteed
+    /// to be shortest if the estimate never overestimates. Debug builds
+    /// first sample the estimate with `check::check_heuristic` and report
+    /// any violation on stderr.
+    pub fn solve_with_heuristic(
+        &self,
+        heuristic: impl Heuristic + 'static,
+    ) -> Result<Vec<Move>, &'static str> {
+        self.solve_with_heuristic_and_progress(heuristic, |_| true)
+    }
+
+    pub fn solve_with_heuristic_and_progress<F>(
+        &self,
+        heuristic: impl Heuristic + 'static,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        #[cfg(debug_assertions)]
+        crate::check::report_on_install(&heuristic, &self.goal());
+        search::run(IdaStarSearch::with_heuristic(self, heuristic)?, callback)
+    }
+}
+
+/// Names accepted by `by_name`, in catalog order.
+pub const NAMES: [&str; 11] = [
+    "manhattan",
+    "linear-conflict",
+    "tablebase",
+    "pattern-database",
+    "walking-distance",
+    "inversion-distance",
+    "manhattan-inversion",
+    "misplaced-tiles",
+    "gaschnig",
+    "corner-tiles",
+    "last-moves",
+];
+
+/// The heuristic named `name` in the catalog. Those limited to some board
+/// sizes fall back to the Manhattan distance on others.
+pub fn by_name(name: &str) -> Option<Box<dyn Heuristic>> {
+    let estimate: fn(&Puzzle) -> usize = match name {
+        "manhattan" => return Some(Box::new(Manhattan)),
+        "linear-conflict" => return Some(Box::new(LinearConflict)),
+        "tablebase" => |puzzle| {
+            tablebase::distance_to_goal(puzzle).unwrap_or_else(|| puzzle.manhattan_distance())
+        },
+        "pattern-database" => Puzzle::pattern_database_distance,
+        "walking-distance" => Puzzle::walking_distance,
+        "inversion-distance" => Puzzle::inversion_distance,
+        "manhattan-inversion" => Puzzle::manhattan_inversion_distance,
+        "misplaced-tiles" => Puzzle::misplaced_tiles,
+        "gaschnig" => Puzzle::gaschnig_distance,
+        "corner-tiles" => Puzzle::corner_tiles_distance,
+        "last-moves" => Puzzle::last_moves_distance,
+        _ => return None,
+    };
+    Some(Box::new(estimate))
+}
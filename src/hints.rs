@@ -0,0 +1,227 @@
+use crate::clock::Instant;
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::time::Duration;
+
+/// Boards up to this size are small enough to rank moves by solving.
+const MAX_SOLVED_SIZE: usize = 2;
+
+/// Weight of the weighted A* search behind `HintQuality::HeuristicBest`.
+const FALLBACK_WEIGHT: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RankedMove {
+    pub movement: Move,
+    /// Moves left after making this one.
+    pub distance: usize,
+    /// Whether `distance` is the optimal number of moves rather than a
+    /// heuristic estimate.
+    pub exact: bool,
+    /// 1 for the best moves. Tied moves share a rank and the next rank is
+    /// skipped, so two best moves are followed by a move ranked 3.
+    pub rank: usize,
+    /// Whether another move leaves the same distance.
+    pub tied: bool,
+}
+
+/// How much a hint from `hint_within` can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintQuality {
+    /// The first move of a shortest solution.
+    Optimal,
+    /// The first move of a solution found by weighted A*, which may be
+    /// longer than needed.
+    HeuristicBest,
+    /// The move leaving the lowest heuristic estimate, as neither search
+    /// finished in time. It may lead away from the goal.
+    Greedy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedHint {
+    pub movement: Move,
+    pub quality: HintQuality,
+}
+
+impl Puzzle {
+    /// The best move that can be found within roughly `budget`, and how
+    /// good it is known to be, so interactive apps can trade advice quality
+    /// for responsiveness. An optimal search runs for half the budget, then
+    /// weighted A* for the rest, then a one-move lookahead. `None` if the
+    /// board is solved or cannot be solved.
+    pub fn hint_within(&self, budget: Duration) -> Option<TimedHint> {
+        budgeted_solution(self, budget).map(|(moves, quality)| TimedHint {
+            movement: moves[0],
+            quality,
+        })
+    }
+
+    /// Every legal move, best first, ranked by the distance left after it.
+    /// Distances are exact for 3x3 and smaller boards and heuristic
+    /// estimates otherwise. Moves with equal distances are marked as tied,
+    /// so a trainer can point out that several moves are equally good.
+    pub fn ranked_moves(&self) -> Vec<RankedMove> {
+        let mut moves: Vec<RankedMove> = Move::ALL
+            .into_iter()
+            .filter_map(|movement| {
+                let mut next = self.clone();
+                if !next.apply_move(movement) {
+                    return None;
+                }
+                let (distance, exact) = distance(&next);
+                Some(RankedMove {
+                    movement,
+                    distance,
+                    exact,
+                    rank: 0,
+                    tied: false,
+                })
+            })
+            .collect();
+        moves.sort_by_key(|ranked| ranked.distance);
+
+        let distances: Vec<usize> = moves.iter().map(|ranked| ranked.distance).collect();
+        for ranked in &mut moves {
+            ranked.rank = 1 + distances.iter().filter(|&&d| d < ranked.distance).count();
+            ranked.tied = distances.iter().filter(|&&d| d == ranked.distance).count() > 1;
+        }
+        moves
+    }
+}
+
+/// A solution kept alongside the board it solves, so hints can be given
+/// every frame without searching or allocating. Only `new` and `refresh`
+/// search and allocate; `hint` follows the player along the solution.
+#[derive(Debug, Clone)]
+pub struct CachedHint {
+    /// The board after the first `next` moves of the solution.
+    expected: Puzzle,
+    moves: Vec<Move>,
+    next: usize,
+}
+
+/// A cache with no solution yet, which `hint_within` fills on the first
+/// board instead of searching without a time limit as `new` does.
+impl Default for CachedHint {
+    fn default() -> Self {
+        Self {
+            expected: Puzzle::new(1).expect("A 1x1 board is valid"),
+            moves: Vec::new(),
+            next: 0,
+        }
+    }
+}
+
+impl CachedHint {
+    pub fn new(puzzle: &Puzzle) -> Result<Self, &'static str> {
+        Ok(Self {
+            moves: puzzle.solve()?,
+            expected: puzzle.clone(),
+            next: 0,
+        })
+    }
+
+    /// Replaces the cached solution with one for `puzzle`.
+    pub fn refresh(&mut self, puzzle: &Puzzle) -> Result<(), &'static str> {
+        self.moves = puzzle.solve()?;
+        self.expected = puzzle.clone();
+        self.next = 0;
+        Ok(())
+    }
+
+    /// The next move of the cached solution for `puzzle`, which may be one
+    /// move ahead of or behind the board of the last call, as when the
+    /// player follows the hint or undoes a move. `None` if the board is
+    /// solved or has strayed from the solution, in which case `refresh`
+    /// finds a new one. Never allocates.
+    pub fn hint(&mut self, puzzle: &Puzzle) -> Option<Move> {
+        if *puzzle != self.expected && !self.follow(puzzle) {
+            return None;
+        }
+        self.moves.get(self.next).copied()
+    }
+
+    /// Like `hint`, but searches for up to `budget` when the board has left
+    /// the cached solution, as `Puzzle::hint_within` does. A shortest
+    /// solution found that way replaces the cached one.
+    pub fn hint_within(&mut self, puzzle: &Puzzle, budget: Duration) -> Option<TimedHint> {
+        if let Some(movement) = self.hint(puzzle) {
+            return Some(TimedHint {
+                movement,
+                quality: HintQuality::Optimal,
+            });
+        }
+        let (moves, quality) = budgeted_solution(puzzle, budget)?;
+        let movement = moves[0];
+        if quality == HintQuality::Optimal {
+            self.moves = moves;
+            self.expected = puzzle.clone();
+            self.next = 0;
+        }
+        Some(TimedHint { movement, quality })
+    }
+
+    /// Steps `expected` one move along the solution in either direction to
+    /// match `puzzle`, leaving it unchanged if neither step does.
+    fn follow(&mut self, puzzle: &Puzzle) -> bool {
+        if let Some(&movement) = self.moves.get(self.next) {
+            self.expected.apply_move(movement);
+            if *puzzle == self.expected {
+                self.next += 1;
+                return true;
+            }
+            self.expected.apply_move(movement.opposite());
+        }
+        if let Some(&movement) = self
+            .next
+            .checked_sub(1)
+            .and_then(|last| self.moves.get(last))
+        {
+            self.expected.apply_move(movement.opposite());
+            if *puzzle == self.expected {
+                self.next -= 1;
+                return true;
+            }
+            self.expected.apply_move(movement);
+        }
+        false
+    }
+}
+
+fn distance(puzzle: &Puzzle) -> (usize, bool) {
+    if let Some(distance) = tablebase::distance_to_goal(puzzle) {
+        return (distance, true);
+    }
+    if puzzle.size() <= MAX_SOLVED_SIZE {
+        if let Ok(moves) = puzzle.solve_optimal() {
+            return (moves.len(), true);
+        }
+    }
+    (puzzle.heuristic(), false)
+}
+
+/// A non-empty solution, or for `HintQuality::Greedy` just its first move,
+/// found within about `budget`.
+fn budgeted_solution(puzzle: &Puzzle, budget: Duration) -> Option<(Vec<Move>, HintQuality)> {
+    if puzzle.is_solved() || !puzzle.is_current_state_solvable() {
+        return None;
+    }
+    // The optimal search gets half the budget so the fallback has time too
+    let start = Instant::now();
+    let in_time = |limit: Duration| move |_: &_| start.elapsed() <= limit;
+
+    match puzzle.solve_with_progress(in_time(budget / 2)) {
+        Ok(moves) => return Some((moves, HintQuality::Optimal)),
+        Err("Search cancelled") => {}
+        Err(_) => return None,
+    }
+    if let Ok(moves) = puzzle.solve_weighted_with_progress(FALLBACK_WEIGHT, in_time(budget)) {
+        return Some((moves, HintQuality::HeuristicBest));
+    }
+    let greedy = puzzle.legal_moves().min_by_key(|&movement| {
+        let mut next = puzzle.clone();
+        next.apply_move(movement);
+        next.heuristic()
+    })?;
+    Some((vec![greedy], HintQuality::Greedy))
+}
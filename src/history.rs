@@ -0,0 +1,195 @@
+//! Recently generated scrambles, so practice apps producing hundreds of
+//! boards a session do not hand out the same board, or one a few moves from
+//! it, twice in a row.
+//!
+//! Only a hash of each board is kept, eight bytes per scramble in the saved
+//! file. Hashes are taken of the board's `dedup::canonical_form`, so a board
+//! and its reflection count as the same scramble, and are the same on every
+//! platform, so a history file can move between devices.
+
+use crate::daily::fnv1a;
+use crate::dedup::canonical_form;
+use crate::puzzle::{Move, Puzzle};
+use rand::Rng;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Scrambles remembered unless another window is chosen.
+pub const DEFAULT_WINDOW: usize = 500;
+
+/// Moves within which a board counts as a near-duplicate of a recent one,
+/// unless another radius is chosen.
+pub const DEFAULT_RADIUS: usize = 2;
+
+/// Boards drawn by `fresh` before giving up, which only happens when
+/// the window covers most of the board size's states.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Identifies a saved history; the hashes follow, oldest first, as
+/// little-endian `u64`s.
+const MAGIC: &[u8; 5] = b"SPSH1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrambleHistory {
+    /// Hashes of recorded scrambles, oldest first.
+    hashes: VecDeque<u64>,
+    window: usize,
+    radius: usize,
+}
+
+impl ScrambleHistory {
+    /// An empty history remembering the last `window` scrambles.
+    pub fn new(window: usize) -> Self {
+        Self {
+            hashes: VecDeque::new(),
+            window,
+            radius: DEFAULT_RADIUS,
+        }
+    }
+
+    /// Treats boards within `radius` moves of a recent scramble as repeats.
+    /// A radius of 0 only rejects exact repeats, up to reflection. Each extra
+    /// move roughly triples the cost of `is_recent`.
+    pub fn with_radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Loads a history saved by `save`, keeping the newest `window` entries.
+    pub fn load(path: &Path, window: usize) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let hashes = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| invalid("Not a scramble history file"))?;
+        if hashes.len() % 8 != 0 {
+            return Err(invalid("Scramble history file has the wrong length"));
+        }
+
+        let mut history = Self::new(window);
+        for hash in hashes.chunks_exact(8) {
+            history.push(u64::from_le_bytes(hash.try_into().unwrap()));
+        }
+        Ok(history)
+    }
+
+    /// Like `load`, but starts an empty history if the file does not exist
+    /// yet.
+    pub fn load_or_new(path: &Path, window: usize) -> io::Result<Self> {
+        match Self::load(path, window) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::new(window)),
+            result => result,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 8 * self.hashes.len());
+        bytes.extend_from_slice(MAGIC);
+        for hash in &self.hashes {
+            bytes.extend_from_slice(&hash.to_le_bytes());
+        }
+        fs::write(path, bytes)
+    }
+
+    /// Scrambles currently remembered.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Whether `puzzle`, or any board within the radius of it, is among the
+    /// remembered scrambles.
+    pub fn is_recent(&self, puzzle: &Puzzle) -> bool {
+        !self.hashes.is_empty() && self.near(&mut puzzle.clone(), None, self.radius)
+    }
+
+    /// Remembers `puzzle`, forgetting the oldest scramble if the window is
+    /// full.
+    pub fn record(&mut self, puzzle: &Puzzle) {
+        self.push(hash(puzzle));
+    }
+
+    /// Shuffles a board of the given size that is not a near-duplicate of a
+    /// recent scramble, and records it.
+    pub fn shuffle_fresh<R: Rng + ?Sized>(
+        &mut self,
+        size: usize,
+        rng: &mut R,
+    ) -> Result<Puzzle, &'static str> {
+        let goal = Puzzle::new(size)?;
+        self.fresh(
+            || {
+                let mut puzzle = goal.clone();
+                puzzle.shuffle_with(rng);
+                Ok(puzzle)
+            },
+            |puzzle| puzzle,
+        )
+    }
+
+    /// Calls `generate` until it produces a scramble whose board, as picked
+    /// out by `board`, is not a near-duplicate of a recent one, then records
+    /// and returns it. Suits generators such as
+    /// `competition::competition_scramble` that return more than the board.
+    pub fn fresh<T, G, B>(&mut self, mut generate: G, board: B) -> Result<T, &'static str>
+    where
+        G: FnMut() -> Result<T, &'static str>,
+        B: Fn(&T) -> &Puzzle,
+    {
+        for _ in 0..MAX_ATTEMPTS {
+            let scramble = generate()?;
+            if !self.is_recent(board(&scramble)) {
+                self.record(board(&scramble));
+                return Ok(scramble);
+            }
+        }
+        Err("No scramble was far enough from the recent ones")
+    }
+
+    fn push(&mut self, hash: u64) {
+        if self.window == 0 {
+            return;
+        }
+        while self.hashes.len() >= self.window {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(hash);
+    }
+
+    /// Searches the boards within `moves` of `state`, never undoing the
+    /// previous move, for a remembered one.
+    fn near(&self, state: &mut Puzzle, last_move: Option<Move>, moves: usize) -> bool {
+        if self.hashes.contains(&hash(state)) {
+            return true;
+        }
+        if moves == 0 {
+            return false;
+        }
+        for movement in Move::ALL {
+            if last_move == Some(movement.opposite()) || !state.apply_move(movement) {
+                continue;
+            }
+            let found = self.near(state, Some(movement), moves - 1);
+            state.apply_move(movement.opposite());
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for ScrambleHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+fn hash(puzzle: &Puzzle) -> u64 {
+    fnv1a(format!("{:#}", canonical_form(puzzle)).into_bytes())
+}
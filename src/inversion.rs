@@ -0,0 +1,81 @@
+//! The inversion distance heuristic.
+//!
+//! Read row by row, the tiles of a solved board are in order. A move along a
+//! row leaves that reading unchanged, while a move along a column carries a
+//! tile past the `cols - 1` tiles between its old and new cell, fixing at
+//! most `cols - 1` inversions. So the inversions of the row-by-row reading
+//! bound the vertical moves needed, and the same count for the column-by-
+//! column reading, with tiles ranked by goal column first, bounds the
+//! horizontal moves. As each move is one or the other, the two add up.
+//!
+//! The estimate is weak on its own but catches boards whose tiles are near
+//! their goal cells yet badly out of order, where the Manhattan distance is
+//! weakest, so the larger of the two is a stronger bound than either.
+
+use crate::puzzle::Puzzle;
+
+impl Puzzle {
+    /// Lower bound on the vertical moves needed plus one on the horizontal
+    /// moves needed, from the inversions of the board read by rows and by
+    /// columns. Never overestimates.
+    pub fn inversion_distance(&self) -> usize {
+        let (rows, cols) = (self.rows(), self.cols());
+        let goals = self.goals();
+
+        // Tiles ranked by goal row, then goal column, read row by row
+        let mut by_row = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let value = self.tile_at(row, col);
+                by_row.push(if value == 0 {
+                    0
+                } else {
+                    let (goal_row, goal_col) = goals[value as usize];
+                    (goal_row * cols + goal_col + 1) as u32
+                });
+            }
+        }
+
+        // Tiles ranked by goal column, then goal row, read column by column
+        let mut by_column = Vec::with_capacity(rows * cols);
+        for col in 0..cols {
+            for row in 0..rows {
+                let value = self.tile_at(row, col);
+                by_column.push(if value == 0 {
+                    0
+                } else {
+                    let (goal_row, goal_col) = goals[value as usize];
+                    (goal_col * rows + goal_row + 1) as u32
+                });
+            }
+        }
+
+        let vertical = moves_to_fix(Puzzle::count_inversions(&by_row), cols - 1);
+        let horizontal = moves_to_fix(Puzzle::count_inversions(&by_column), rows - 1);
+        vertical + horizontal
+    }
+
+    /// The larger of `manhattan_distance` and `inversion_distance`. Never
+    /// overestimates.
+    pub fn manhattan_inversion_distance(&self) -> usize {
+        self.manhattan_distance().max(self.inversion_distance())
+    }
+}
+
+/// Fewest moves that fix `inversions` when each fixes at most `passed`.
+///
+/// Passing `passed` tiles changes the count by `passed` minus twice the
+/// tiles it moves ahead of, so every step has the parity of `passed`. When
+/// that is odd, an even remainder cannot be made up in one step, which gives
+/// the familiar `inversions / 3 + inversions % 3` on 4x4 boards.
+fn moves_to_fix(inversions: usize, passed: usize) -> usize {
+    if passed == 0 {
+        return 0;
+    }
+    let (steps, remainder) = (inversions / passed, inversions % passed);
+    match remainder {
+        0 => steps,
+        _ if passed.is_multiple_of(2) || !remainder.is_multiple_of(2) => steps + 1,
+        _ => steps + 2,
+    }
+}
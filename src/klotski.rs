@@ -0,0 +1,450 @@
+//! Sliding-block boards whose pieces may be larger than one cell, as in
+//! Klotski.
+//!
+//! Every piece is a rectangle that slides one cell at a time into empty
+//! cells. A goal pins some pieces to target positions and leaves the others
+//! free, so the classic Klotski goal is a single entry for the large block.
+
+use crate::puzzle::{Move, MAX_SIZE};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Pieces per board, so each one can be drawn with its own letter.
+pub const MAX_PIECES: usize = 26;
+
+/// Distinct positions `BlockBoard::solve` stores before giving up.
+const MAX_STATES: usize = 2_000_000;
+
+/// A rectangular piece, placed by its top-left cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub height: usize,
+    pub width: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Piece {
+    pub fn new(height: usize, width: usize, row: usize, col: usize) -> Self {
+        Self {
+            height,
+            width,
+            row,
+            col,
+        }
+    }
+
+    fn covers(&self, row: usize, col: usize) -> bool {
+        (self.row..self.row + self.height).contains(&row)
+            && (self.col..self.col + self.width).contains(&col)
+    }
+}
+
+/// One piece sliding one cell. Unlike a `Puzzle` move, which is named after
+/// the direction a tile slides into the empty space, `direction` is simply
+/// the way the piece travels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PieceMove {
+    /// Index into `BlockBoard::pieces`.
+    pub piece: usize,
+    pub direction: Move,
+}
+
+impl fmt::Display for PieceMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", piece_letter(self.piece), self.direction)
+    }
+}
+
+/// Where a piece must end up for the board to count as solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub piece: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockBoard {
+    rows: usize,
+    cols: usize,
+    pieces: Vec<Piece>,
+    /// For every cell, row by row, the index of the piece covering it.
+    cells: Vec<Option<usize>>,
+    goal: Vec<Target>,
+}
+
+impl BlockBoard {
+    /// A board with the given pieces and no goal. Pieces must lie inside the
+    /// board and must not overlap, and each side is at most `MAX_SIZE`.
+    pub fn new(rows: usize, cols: usize, pieces: Vec<Piece>) -> Result<Self, &'static str> {
+        if rows == 0 || cols == 0 {
+            return Err("Board size must be positive");
+        }
+        if rows > MAX_SIZE || cols > MAX_SIZE {
+            return Err("Board size is too large");
+        }
+        if pieces.len() > MAX_PIECES {
+            return Err("Too many pieces");
+        }
+
+        let mut cells = vec![None; rows * cols];
+        for (index, piece) in pieces.iter().enumerate() {
+            if piece.height == 0 || piece.width == 0 {
+                return Err("Pieces must cover at least one cell");
+            }
+            if !fits(piece.row, piece.height, rows) || !fits(piece.col, piece.width, cols) {
+                return Err("Piece lies outside the board");
+            }
+            for row in piece.row..piece.row + piece.height {
+                for col in piece.col..piece.col + piece.width {
+                    let cell = &mut cells[row * cols + col];
+                    if cell.is_some() {
+                        return Err("Pieces overlap");
+                    }
+                    *cell = Some(index);
+                }
+            }
+        }
+
+        Ok(Self {
+            rows,
+            cols,
+            pieces,
+            cells,
+            goal: Vec::new(),
+        })
+    }
+
+    /// The traditional Klotski layout on a 4-wide, 5-tall board: a 2x2
+    /// block that must reach the middle of the bottom edge, four upright
+    /// 2x1 pieces, one lying 1x2 piece and four single cells. Its shortest
+    /// solution takes 116 one-cell slides.
+    pub fn klotski() -> Self {
+        let board: Self = "ABBC\nABBC\nDEEF\nDGHF\nI..J"
+            .parse()
+            .expect("The Klotski layout is valid");
+        board
+            .with_target(1, 3, 1)
+            .expect("The Klotski goal is valid")
+    }
+
+    /// Adds a target to the goal, replacing any earlier one for the piece.
+    pub fn with_target(
+        mut self,
+        piece: usize,
+        row: usize,
+        col: usize,
+    ) -> Result<Self, &'static str> {
+        let shape = self.pieces.get(piece).ok_or("No such piece")?;
+        if !fits(row, shape.height, self.rows) || !fits(col, shape.width, self.cols) {
+            return Err("Target lies outside the board");
+        }
+        self.goal.retain(|target| target.piece != piece);
+        self.goal.push(Target { piece, row, col });
+        Ok(self)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn goal(&self) -> &[Target] {
+        &self.goal
+    }
+
+    /// The piece covering a cell, or `None` if it is empty or off the board.
+    pub fn piece_at(&self, row: usize, col: usize) -> Option<usize> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        self.cells[row * self.cols + col]
+    }
+
+    /// Whether every target of the goal is met. A board without a goal is
+    /// always solved.
+    pub fn is_solved(&self) -> bool {
+        self.goal.iter().all(|target| {
+            let piece = &self.pieces[target.piece];
+            piece.row == target.row && piece.col == target.col
+        })
+    }
+
+    /// Whether `movement` slides its piece into cells that are on the board
+    /// and empty.
+    pub fn can_move(&self, movement: PieceMove) -> bool {
+        let Some(piece) = self.pieces.get(movement.piece) else {
+            return false;
+        };
+        let Some(moved) = shifted(piece, movement.direction) else {
+            return false;
+        };
+        if !fits(moved.row, moved.height, self.rows) || !fits(moved.col, moved.width, self.cols) {
+            return false;
+        }
+        (moved.row..moved.row + moved.height).all(|row| {
+            (moved.col..moved.col + moved.width).all(|col| {
+                self.cells[row * self.cols + col].is_none_or(|other| other == movement.piece)
+            })
+        })
+    }
+
+    /// Every legal move, by piece and then in `Move::ALL` order.
+    pub fn legal_moves(&self) -> Vec<PieceMove> {
+        (0..self.pieces.len())
+            .flat_map(|piece| {
+                Move::ALL
+                    .into_iter()
+                    .map(move |direction| PieceMove { piece, direction })
+            })
+            .filter(|&movement| self.can_move(movement))
+            .collect()
+    }
+
+    /// Makes `movement` if it is legal, returning whether it was.
+    pub fn apply_move(&mut self, movement: PieceMove) -> bool {
+        if !self.can_move(movement) {
+            return false;
+        }
+        let piece = self.pieces[movement.piece];
+        let moved = shifted(&piece, movement.direction).unwrap();
+        self.fill(&piece, None);
+        self.fill(&moved, Some(movement.piece));
+        self.pieces[movement.piece] = moved;
+        true
+    }
+
+    /// Applies all of `moves` or none of them. On failure, returns the
+    /// position of the first illegal move.
+    pub fn apply_moves(&mut self, moves: &[PieceMove]) -> Result<(), usize> {
+        for (index, &movement) in moves.iter().enumerate() {
+            if !self.apply_move(movement) {
+                for &done in moves[..index].iter().rev() {
+                    self.apply_move(PieceMove {
+                        direction: done.direction.opposite(),
+                        ..done
+                    });
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    /// A shortest solution, counting each one-cell slide of a piece as a
+    /// move, found by A* guided by how far each targeted piece is from its
+    /// target. Pieces of the same shape without a target are
+    /// interchangeable, so positions that differ only by swapping them are
+    /// searched once. Fails if the goal cannot be reached or the search
+    /// stores more than a fixed number of positions.
+    pub fn solve(&self) -> Result<Vec<PieceMove>, &'static str> {
+        // Pieces in the same class can stand in for each other
+        let classes: Vec<u8> = (0..self.pieces.len())
+            .map(|index| {
+                let targeted = |piece: usize| self.goal.iter().any(|t| t.piece == piece);
+                let same = |other: usize| {
+                    !targeted(other)
+                        && self.pieces[other].height == self.pieces[index].height
+                        && self.pieces[other].width == self.pieces[index].width
+                };
+                let class = match targeted(index) {
+                    true => index,
+                    false => (0..index).find(|&other| same(other)).unwrap_or(index),
+                };
+                class as u8 + 1
+            })
+            .collect();
+        let key = |board: &BlockBoard| -> Vec<u8> {
+            board
+                .cells
+                .iter()
+                .map(|cell| cell.map_or(0, |piece| classes[piece]))
+                .collect()
+        };
+
+        // For each position found: the board, its cost, and the position and
+        // move it was reached from
+        let mut states = vec![(self.clone(), 0, None)];
+        let mut expanded = vec![false];
+        let mut index = HashMap::from([(key(self), 0)]);
+        let mut open = BinaryHeap::from([Reverse((self.remaining(), 0))]);
+        while let Some(Reverse((_, node))) = open.pop() {
+            if expanded[node] {
+                continue;
+            }
+            expanded[node] = true;
+            let (board, cost, _) = &states[node];
+            if board.is_solved() {
+                let mut moves = Vec::new();
+                let mut at = node;
+                while let Some((parent, movement)) = states[at].2 {
+                    moves.push(movement);
+                    at = parent;
+                }
+                moves.reverse();
+                return Ok(moves);
+            }
+
+            let cost = cost + 1;
+            for movement in board.legal_moves() {
+                let mut next = states[node].0.clone();
+                next.apply_move(movement);
+                let estimate = cost + next.remaining();
+                let entry = (next, cost, Some((node, movement)));
+                match index.get(&key(&entry.0)) {
+                    Some(&found) if states[found].1 <= cost => continue,
+                    Some(&found) => {
+                        states[found] = entry;
+                        open.push(Reverse((estimate, found)));
+                    }
+                    None => {
+                        if states.len() == MAX_STATES {
+                            return Err("State limit exceeded");
+                        }
+                        index.insert(key(&entry.0), states.len());
+                        open.push(Reverse((estimate, states.len())));
+                        states.push(entry);
+                        expanded.push(false);
+                    }
+                }
+            }
+        }
+        Err("Puzzle is not solvable")
+    }
+
+    /// Cells each targeted piece still has to travel. Never overestimates,
+    /// since a move slides one piece one cell.
+    fn remaining(&self) -> usize {
+        self.goal
+            .iter()
+            .map(|target| {
+                let piece = &self.pieces[target.piece];
+                piece.row.abs_diff(target.row) + piece.col.abs_diff(target.col)
+            })
+            .sum()
+    }
+
+    fn fill(&mut self, piece: &Piece, value: Option<usize>) {
+        for row in piece.row..piece.row + piece.height {
+            for col in piece.col..piece.col + piece.width {
+                self.cells[row * self.cols + col] = value;
+            }
+        }
+    }
+}
+
+/// Whether `length` cells from `start` stay within `limit`, without
+/// overflowing on huge values.
+fn fits(start: usize, length: usize, limit: usize) -> bool {
+    length <= limit && start <= limit - length
+}
+
+/// `piece` moved one cell in `direction`, or `None` if that would leave the
+/// top or left edge.
+fn shifted(piece: &Piece, direction: Move) -> Option<Piece> {
+    // Puzzle offsets point from the empty space to the tile that moves, the
+    // opposite of the way the tile travels
+    let (dr, dc) = direction.as_offset();
+    Some(Piece {
+        row: piece.row.checked_add_signed(-dr)?,
+        col: piece.col.checked_add_signed(-dc)?,
+        ..*piece
+    })
+}
+
+fn piece_letter(piece: usize) -> char {
+    (b'A' + piece as u8) as char
+}
+
+impl fmt::Display for BlockBoard {
+    /// One line per row, with a letter per piece and `.` for empty cells.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.cols {
+                match self.cells[row * self.cols + col] {
+                    Some(piece) => write!(f, "{}", piece_letter(piece))?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BlockBoard {
+    type Err = &'static str;
+
+    /// Parses the form written by `Display`: rows on separate lines, the
+    /// cells of each piece marked with the same letter and empty cells with
+    /// `.`. Pieces are numbered by letter, so `A` is piece 0, and each
+    /// letter must fill a rectangle. The goal is left empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let rows = lines.len();
+        let cols = lines.first().map_or(0, |line| line.len());
+        if lines.iter().any(|line| line.len() != cols) {
+            return Err("Rows have different lengths");
+        }
+
+        let mut bounds: Vec<Option<(usize, usize, usize, usize)>> = vec![None; MAX_PIECES];
+        let mut count = 0;
+        for (row, line) in lines.iter().enumerate() {
+            for (col, cell) in line.bytes().enumerate() {
+                if cell == b'.' {
+                    continue;
+                }
+                if !cell.is_ascii_uppercase() {
+                    return Err("Cells must be letters A to Z or '.'");
+                }
+                let piece = (cell - b'A') as usize;
+                count = count.max(piece + 1);
+                let bound = bounds[piece].get_or_insert((row, col, row, col));
+                *bound = (
+                    bound.0.min(row),
+                    bound.1.min(col),
+                    bound.2.max(row),
+                    bound.3.max(col),
+                );
+            }
+        }
+
+        let pieces = bounds[..count]
+            .iter()
+            .map(|bound| {
+                let (top, left, bottom, right) = bound.ok_or("Piece letters must not skip")?;
+                Ok(Piece::new(bottom - top + 1, right - left + 1, top, left))
+            })
+            .collect::<Result<Vec<_>, &'static str>>()?;
+
+        // Every cell in a piece's bounding box must carry its letter
+        for (index, piece) in pieces.iter().enumerate() {
+            for (row, line) in lines.iter().enumerate() {
+                for (col, cell) in line.bytes().enumerate() {
+                    let marked = cell == piece_letter(index) as u8;
+                    if marked != piece.covers(row, col) {
+                        return Err("Pieces must be rectangles");
+                    }
+                }
+            }
+        }
+
+        Self::new(rows, cols, pieces)
+    }
+}
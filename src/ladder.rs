@@ -0,0 +1,97 @@
+use crate::clock::Instant;
+use crate::config::Config;
+use crate::puzzle::Puzzle;
+use crate::registry::{solve_interruptible, INTERRUPTIBLE_ENGINES};
+use std::fmt;
+use std::time::Duration;
+
+/// Engines that can be stopped when a level runs out of time.
+pub const LADDER_ENGINES: [&str; 5] = INTERRUPTIBLE_ENGINES;
+
+/// The smallest board on the ladder.
+const FIRST_SIZE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderLevel {
+    pub size: usize,
+    /// Solution length, or why the level was not cleared.
+    pub outcome: Result<usize, &'static str>,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LadderReport {
+    pub engine: &'static str,
+    /// Levels attempted, ending with the first one that failed.
+    pub levels: Vec<LadderLevel>,
+}
+
+impl LadderReport {
+    /// The largest board size solved within the budget.
+    pub fn reached(&self) -> Option<usize> {
+        self.levels
+            .iter()
+            .rev()
+            .find(|level| level.outcome.is_ok())
+            .map(|level| level.size)
+    }
+}
+
+/// Solves a 3x3, then a 4x4 and so on up to `max_size` with `engine`, giving
+/// each level `budget`, and stops at the first level that is not cleared.
+/// Boards are derived from `seed` so every run of the ladder is comparable.
+pub fn run_ladder(
+    engine: &'static str,
+    config: &Config,
+    max_size: usize,
+    budget: Duration,
+    seed: &str,
+) -> LadderReport {
+    let mut levels = Vec::new();
+
+    for size in FIRST_SIZE..=max_size {
+        let start = Instant::now();
+        let outcome = Puzzle::daily_in(size, seed, "ladder").and_then(|puzzle| {
+            solve_interruptible(engine, config, &puzzle, |_| start.elapsed() < budget)
+        });
+        let elapsed = start.elapsed();
+
+        let outcome = match outcome {
+            Ok(moves) if elapsed <= budget => Ok(moves.len()),
+            Ok(_) | Err("Search cancelled") => Err("Over budget"),
+            Err(err) => Err(err),
+        };
+        let cleared = outcome.is_ok();
+        levels.push(LadderLevel {
+            size,
+            outcome,
+            elapsed,
+        });
+        if !cleared {
+            break;
+        }
+    }
+
+    LadderReport { engine, levels }
+}
+
+impl fmt::Display for LadderReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.engine)?;
+        for level in &self.levels {
+            let board = format!("{0}x{0}", level.size);
+            match level.outcome {
+                Ok(length) => writeln!(
+                    f,
+                    "  {:<6} {:>5} moves {:>10.2?}",
+                    board, length, level.elapsed
+                )?,
+                Err(err) => writeln!(f, "  {:<6} {}", board, err)?,
+            }
+        }
+        match self.reached() {
+            Some(size) => write!(f, "  reached {0}x{0}", size),
+            None => write!(f, "  reached nothing"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Solvers and tools for the sliding tile puzzle.
+//!
+//! `Puzzle` holds a board and offers the built-in solvers directly, while
+//! `registry::SolverRegistry` looks engines up by name for front ends that
+//! let users choose one. The front ends are packages of their own: the
+//! command line in `slider-cli`, an HTTP service in `slider-server` and a
+//! WebAssembly build for web pages in `slider-wasm`, so depending on this
+//! crate pulls in none of their dependencies.
+
+pub mod adaptive;
+pub mod astar;
+pub mod batch;
+pub mod bench;
+pub mod bidirectional;
+pub mod bounds;
+pub mod catalog;
+pub mod chat;
+pub mod check;
+mod clock;
+pub mod competition;
+pub mod config;
+pub mod curriculum;
+pub mod daily;
+pub mod dedup;
+pub mod design;
+pub mod determinism;
+pub mod entropy;
+pub mod explain;
+pub mod external;
+pub mod faults;
+pub mod filter;
+pub mod first_moves;
+pub mod geometry;
+pub mod glyphs;
+mod goal;
+pub mod heuristic;
+pub mod hints;
+pub mod history;
+pub mod inversion;
+pub mod json;
+pub mod klotski;
+pub mod korf;
+pub mod ladder;
+pub mod loopover;
+pub mod mapping;
+pub mod moveseq;
+pub mod multi_blank;
+pub mod multi_tile;
+pub mod optimal;
+pub mod oracle;
+pub mod orientation;
+pub mod pareto;
+pub mod pdb;
+pub mod progress;
+pub mod puzzle;
+pub mod registry;
+pub mod repro;
+pub mod rescramble;
+pub mod reverse;
+pub mod scheduler;
+pub mod schema;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod search;
+pub mod solution;
+pub mod solve_cache;
+pub mod solver;
+pub mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub mod strip;
+pub mod suboptimal;
+pub mod tablebase;
+pub mod tile_stats;
+pub mod timing;
+pub mod torus;
+mod transposition;
+pub mod tune;
+pub mod walking;
+pub mod walls;
+pub mod watch;
+
+pub use puzzle::{Move, MoveSet, Puzzle};
+pub use solver::Solver;
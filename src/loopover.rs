@@ -0,0 +1,353 @@
+//! Loopover, where there is no empty space: a move rotates a whole row or
+//! column one cell, and the tile pushed off one end comes back on the other.
+//!
+//! Every cell holds a tile, so positions are plain permutations. A rotation
+//! of a line with an odd number of cells is an even permutation; when every
+//! line is odd in length only even permutations can be reached, and
+//! otherwise every arrangement can be solved. A board a single cell across
+//! can only turn its tiles round as a ring.
+
+use crate::progress::Progress;
+use crate::puzzle::{Puzzle, MAX_SIZE};
+use crate::search::{self, SearchState, StateSearch};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use std::fmt;
+use std::str::FromStr;
+
+/// A rotation of one row or column by one cell, named by the way its tiles
+/// travel and the index of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    Left(usize),
+    Right(usize),
+    Up(usize),
+    Down(usize),
+}
+
+impl Rotation {
+    /// The rotation that puts the line back.
+    pub fn inverse(self) -> Self {
+        match self {
+            Rotation::Left(row) => Rotation::Right(row),
+            Rotation::Right(row) => Rotation::Left(row),
+            Rotation::Up(col) => Rotation::Down(col),
+            Rotation::Down(col) => Rotation::Up(col),
+        }
+    }
+
+    /// Whether the rotation turns a row rather than a column.
+    pub fn is_row(self) -> bool {
+        matches!(self, Rotation::Left(_) | Rotation::Right(_))
+    }
+
+    /// The row or column index.
+    pub fn line(self) -> usize {
+        match self {
+            Rotation::Left(line)
+            | Rotation::Right(line)
+            | Rotation::Up(line)
+            | Rotation::Down(line) => line,
+        }
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Rotation::Left(row) => write!(f, "Left {}", row),
+            Rotation::Right(row) => write!(f, "Right {}", row),
+            Rotation::Up(col) => write!(f, "Up {}", col),
+            Rotation::Down(col) => write!(f, "Down {}", col),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoopoverBoard {
+    rows: usize,
+    cols: usize,
+    /// Tiles row by row, numbered from 1.
+    cells: Vec<u32>,
+}
+
+impl LoopoverBoard {
+    /// The solved board, with tiles in order row by row.
+    pub fn new(rows: usize, cols: usize) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        Ok(Self {
+            rows,
+            cols,
+            cells: (1..=(rows * cols) as u32).collect(),
+        })
+    }
+
+    /// Builds a board from its tiles listed row by row, each of 1 up to the
+    /// number of cells once.
+    pub fn from_tiles(rows: usize, cols: usize, tiles: &[u32]) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        if tiles.len() != rows * cols {
+            return Err("Wrong number of tiles for board size");
+        }
+        let mut seen = vec![false; tiles.len() + 1];
+        for &value in tiles {
+            match seen.get_mut(value as usize) {
+                Some(slot) if value != 0 && !*slot => *slot = true,
+                Some(_) if value != 0 => return Err("Duplicate tile"),
+                _ => return Err("Tile value out of range"),
+            }
+        }
+        Ok(Self {
+            rows,
+            cols,
+            cells: tiles.to_vec(),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn tile_at(&self, row: usize, col: usize) -> u32 {
+        self.cells[row * self.cols + col]
+    }
+
+    /// All tiles row by row.
+    pub fn tiles(&self) -> &[u32] {
+        &self.cells
+    }
+
+    /// Every rotation that changes the board: rows first, then columns,
+    /// each line in index order. Lines of a single cell are left out, and
+    /// on lines of two cells only `Left` and `Up` are listed, since the
+    /// other direction does the same.
+    pub fn moves(&self) -> Vec<Rotation> {
+        let mut moves = Vec::new();
+        if self.cols > 1 {
+            for row in 0..self.rows {
+                moves.push(Rotation::Left(row));
+                if self.cols > 2 {
+                    moves.push(Rotation::Right(row));
+                }
+            }
+        }
+        if self.rows > 1 {
+            for col in 0..self.cols {
+                moves.push(Rotation::Up(col));
+                if self.rows > 2 {
+                    moves.push(Rotation::Down(col));
+                }
+            }
+        }
+        moves
+    }
+
+    /// Rotates a line, returning whether it exists on this board.
+    pub fn apply_move(&mut self, rotation: Rotation) -> bool {
+        let cols = self.cols;
+        match rotation {
+            Rotation::Left(row) | Rotation::Right(row) if row < self.rows => {
+                let line = &mut self.cells[row * cols..(row + 1) * cols];
+                if let Rotation::Left(_) = rotation {
+                    line.rotate_left(1);
+                } else {
+                    line.rotate_right(1);
+                }
+            }
+            Rotation::Up(col) | Rotation::Down(col) if col < cols => {
+                let mut line: Vec<u32> = (0..self.rows).map(|row| self.tile_at(row, col)).collect();
+                if let Rotation::Up(_) = rotation {
+                    line.rotate_left(1);
+                } else {
+                    line.rotate_right(1);
+                }
+                for (row, value) in line.into_iter().enumerate() {
+                    self.cells[row * cols + col] = value;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Applies all of `moves` or none of them. On failure, returns the
+    /// position of the first rotation of a line the board does not have.
+    pub fn apply_moves(&mut self, moves: &[Rotation]) -> Result<(), usize> {
+        for (index, &rotation) in moves.iter().enumerate() {
+            if !self.apply_move(rotation) {
+                for &done in moves[..index].iter().rev() {
+                    self.apply_move(done.inverse());
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells
+            .iter()
+            .zip(1..)
+            .all(|(&value, expected)| value == expected)
+    }
+
+    /// Whether the goal can be reached. A board a single cell across only
+    /// turns as a ring, so its tiles must already be in cyclic order. With
+    /// an even number of rows or columns every arrangement can be solved,
+    /// and with both odd exactly the even permutations can.
+    pub fn is_solvable(&self) -> bool {
+        if self.rows == 1 || self.cols == 1 {
+            let first = self.cells.iter().position(|&value| value == 1).unwrap_or(0);
+            return self.cells[first..]
+                .iter()
+                .chain(&self.cells[..first])
+                .zip(1..)
+                .all(|(&value, expected)| value == expected);
+        }
+        if self.rows.is_multiple_of(2) || self.cols.is_multiple_of(2) {
+            return true;
+        }
+        Puzzle::count_inversions(&self.cells).is_multiple_of(2)
+    }
+
+    /// A lower bound on the moves left. A row rotation moves each tile of
+    /// its row one column and leaves rows alone, so the total distance of
+    /// the tiles from their goal columns, the short way round, falls by at
+    /// most `cols` per move; columns likewise.
+    pub fn heuristic(&self) -> usize {
+        let (mut across, mut down) = (0, 0);
+        for (cell, &value) in self.cells.iter().enumerate() {
+            let goal = value as usize - 1;
+            across += cyclic(cell % self.cols, goal % self.cols, self.cols);
+            down += cyclic(cell / self.cols, goal / self.cols, self.rows);
+        }
+        across.div_ceil(self.cols) + down.div_ceil(self.rows)
+    }
+
+    /// A shortest solution, by IDA* guided by `heuristic`. Fails if the
+    /// board cannot be solved or the search grows past a fixed node limit,
+    /// which boards larger than 4x4 soon reach.
+    pub fn solve(&self) -> Result<Vec<Rotation>, &'static str> {
+        self.solve_with_progress(|_| true)
+    }
+
+    /// Like `solve`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<Rotation>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if !self.is_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        search::run(StateSearch::new(self.clone()), callback)
+    }
+
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut thread_rng());
+    }
+
+    /// Like `shuffle`, but draws from `rng` so scrambles can be reproduced
+    /// from a seed. Every solvable arrangement is equally likely.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        loop {
+            self.cells.shuffle(rng);
+            if self.is_solvable() {
+                break;
+            }
+        }
+    }
+}
+
+fn check_size(rows: usize, cols: usize) -> Result<(), &'static str> {
+    if rows == 0 || cols == 0 {
+        return Err("Board size must be positive");
+    }
+    if rows > MAX_SIZE || cols > MAX_SIZE {
+        return Err("Board size is too large");
+    }
+    Ok(())
+}
+
+/// Steps between two cells of a ring of `len` cells, the short way round.
+fn cyclic(a: usize, b: usize, len: usize) -> usize {
+    let direct = a.abs_diff(b);
+    direct.min(len - direct)
+}
+
+impl SearchState for LoopoverBoard {
+    type Move = Rotation;
+
+    fn moves(&self) -> Vec<Rotation> {
+        LoopoverBoard::moves(self)
+    }
+
+    fn apply(&mut self, rotation: Rotation) -> Rotation {
+        self.apply_move(rotation);
+        rotation.inverse()
+    }
+
+    fn is_solved(&self) -> bool {
+        LoopoverBoard::is_solved(self)
+    }
+
+    fn estimate(&self) -> usize {
+        self.heuristic()
+    }
+
+    /// Repeating a rotation on a line of two cells undoes it, and turning
+    /// parallel lines in either order gives the same board, so only
+    /// increasing order is tried.
+    fn redundant_after(&self, previous: Rotation, rotation: Rotation) -> bool {
+        if rotation.is_row() != previous.is_row() {
+            return false;
+        }
+        let len = if rotation.is_row() {
+            self.cols
+        } else {
+            self.rows
+        };
+        (len == 2 && rotation == previous) || rotation.line() < previous.line()
+    }
+}
+
+impl fmt::Display for LoopoverBoard {
+    /// Draws the board as a grid. The alternate flag (`{:#}`) instead
+    /// writes the single-line form `rowsxcols:tile,tile,...`, which parses
+    /// back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let tiles: Vec<String> = self.cells.iter().map(u32::to_string).collect();
+            return write!(f, "{}x{}:{}", self.rows, self.cols, tiles.join(","));
+        }
+        let width = self.cells.len().to_string().len().max(2);
+        for row in self.cells.chunks(self.cols) {
+            for &value in row {
+                write!(f, "{:width$} ", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LoopoverBoard {
+    type Err = &'static str;
+
+    /// Parses `rowsxcols:` followed by the tiles row by row, separated by
+    /// whitespace or commas.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, tiles) = s.split_once(':').ok_or("Missing rowsxcols: header")?;
+        let parse = |text: &str| text.trim().parse().map_err(|_| "Invalid board size");
+        let (rows, cols) = header.split_once('x').ok_or("Invalid board size")?;
+        let tiles = tiles
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse().map_err(|_| "Invalid tile value"))
+            .collect::<Result<Vec<u32>, _>>()?;
+        Self::from_tiles(parse(rows)?, parse(cols)?, &tiles)
+    }
+}
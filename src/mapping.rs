@@ -0,0 +1,185 @@
+use crate::puzzle::Puzzle;
+
+/// Translates between an external tile numbering and the internal one, where
+/// tiles are numbered from 1 and 0 is the empty space.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TileMapping {
+    /// The internal convention.
+    #[default]
+    Standard,
+    /// Tiles numbered from 0, with `blank` marking the empty space.
+    ZeroBased { blank: String },
+    /// Tiles labelled A, B, ..., Z, AA, AB, ..., with `blank` marking the
+    /// empty space. Letters are read in either case.
+    Letters { blank: String },
+    /// The internal numbers in hexadecimal, so tiles up to 255, a 16x16
+    /// board, take two characters. Read in either case.
+    Hex,
+    /// The internal numbers in base 36, digits then letters, so tiles up to
+    /// 1295, a 36x36 board, take two characters. Read in either case.
+    Base36,
+    /// Each tile labelled by its goal cell as `row-col`, counting from 1,
+    /// with `blank` marking the empty space. Shows at a glance where a tile
+    /// belongs on a large board.
+    Coordinates { blank: String },
+}
+
+impl TileMapping {
+    pub fn zero_based(blank: &str) -> Self {
+        TileMapping::ZeroBased {
+            blank: blank.to_string(),
+        }
+    }
+
+    pub fn letters(blank: &str) -> Self {
+        TileMapping::Letters {
+            blank: blank.to_string(),
+        }
+    }
+
+    pub fn coordinates(blank: &str) -> Self {
+        TileMapping::Coordinates {
+            blank: blank.to_string(),
+        }
+    }
+
+    /// The internal value of one external tile label on a board `cols`
+    /// wide. Only coordinates depend on the width.
+    pub fn to_internal(&self, token: &str, cols: usize) -> Result<u32, &'static str> {
+        match self {
+            TileMapping::Standard => token.parse().map_err(|_| "Invalid tile value"),
+            TileMapping::Hex => u32::from_str_radix(token, 16).map_err(|_| "Invalid tile value"),
+            TileMapping::Base36 => u32::from_str_radix(token, 36).map_err(|_| "Invalid tile value"),
+            TileMapping::Coordinates { blank } if token == blank => Ok(0),
+            TileMapping::Coordinates { .. } => {
+                coordinates_to_value(token, cols).ok_or("Invalid tile value")
+            }
+            TileMapping::ZeroBased { blank } if token == blank => Ok(0),
+            TileMapping::ZeroBased { .. } => token
+                .parse::<u32>()
+                .ok()
+                .and_then(|value| value.checked_add(1))
+                .ok_or("Invalid tile value"),
+            TileMapping::Letters { blank } if token == blank => Ok(0),
+            TileMapping::Letters { .. } => letters_to_value(token).ok_or("Invalid tile value"),
+        }
+    }
+
+    /// The external label of an internal tile value on a board `cols` wide.
+    pub fn to_external(&self, value: u32, cols: usize) -> String {
+        match self {
+            TileMapping::Standard => value.to_string(),
+            TileMapping::Hex => format!("{:X}", value),
+            TileMapping::Base36 => value_to_base36(value),
+            TileMapping::ZeroBased { blank }
+            | TileMapping::Letters { blank }
+            | TileMapping::Coordinates { blank }
+                if value == 0 =>
+            {
+                blank.clone()
+            }
+            TileMapping::ZeroBased { .. } => (value - 1).to_string(),
+            TileMapping::Letters { .. } => value_to_letters(value),
+            TileMapping::Coordinates { .. } => {
+                let index = value as usize - 1;
+                let cols = cols.max(1);
+                format!("{}-{}", index / cols + 1, index % cols + 1)
+            }
+        }
+    }
+
+    /// Parses labels separated by whitespace or commas, row by row. The
+    /// board is square if the label count is a square number, and otherwise
+    /// has one row per line of text.
+    pub fn parse(&self, text: &str) -> Result<Puzzle, &'static str> {
+        let tokens: Vec<&str> = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        let size = (tokens.len() as f64).sqrt().round() as usize;
+        let lines = text.lines().filter(|line| !line.trim().is_empty()).count();
+        let (rows, cols) =
+            if size * size == tokens.len() || lines < 2 || !tokens.len().is_multiple_of(lines) {
+                (size, size)
+            } else {
+                (lines, tokens.len() / lines)
+            };
+
+        let tiles = tokens
+            .iter()
+            .map(|token| self.to_internal(token, cols))
+            .collect::<Result<Vec<u32>, _>>()?;
+        Puzzle::from_tiles_rectangular(rows, cols, &tiles)
+    }
+
+    /// Writes the board one row per line with labels separated by spaces and
+    /// right-aligned in columns, in a form `parse` reads back.
+    pub fn format(&self, puzzle: &Puzzle) -> String {
+        let cols = puzzle.cols();
+        let labels: Vec<String> = puzzle
+            .tiles()
+            .into_iter()
+            .map(|value| self.to_external(value, cols))
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+        labels
+            .chunks(cols)
+            .map(|row| {
+                row.iter()
+                    .map(|label| format!("{:>width$}", label))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Bijective base 26: A is 1, Z is 26, AA is 27.
+fn letters_to_value(token: &str) -> Option<u32> {
+    if token.is_empty() {
+        return None;
+    }
+    token.chars().try_fold(0u32, |value, c| {
+        let digit = c.to_ascii_uppercase();
+        if !digit.is_ascii_uppercase() {
+            return None;
+        }
+        value
+            .checked_mul(26)?
+            .checked_add(digit as u32 - 'A' as u32 + 1)
+    })
+}
+
+/// `row-col` of a goal cell, counting from 1, on a board `cols` wide.
+fn coordinates_to_value(token: &str, cols: usize) -> Option<u32> {
+    let (row, col) = token.split_once('-')?;
+    let (row, col): (usize, usize) = (row.parse().ok()?, col.parse().ok()?);
+    if row == 0 || col == 0 || col > cols {
+        return None;
+    }
+    let value = (row - 1).checked_mul(cols)?.checked_add(col)?;
+    u32::try_from(value).ok()
+}
+
+fn value_to_base36(value: u32) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut digits = vec![DIGITS[value as usize % 36]];
+    let mut rest = value / 36;
+    while rest > 0 {
+        digits.push(DIGITS[rest as usize % 36]);
+        rest /= 36;
+    }
+    digits.iter().rev().map(|&digit| digit as char).collect()
+}
+
+fn value_to_letters(mut value: u32) -> String {
+    let mut letters = Vec::new();
+    while value > 0 {
+        value -= 1;
+        letters.push((b'A' + (value % 26) as u8) as char);
+        value /= 26;
+    }
+    letters.iter().rev().collect()
+}
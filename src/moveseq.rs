@@ -0,0 +1,127 @@
+use crate::puzzle::{Move, Puzzle};
+use std::collections::{HashMap, VecDeque};
+
+/// Largest board size for which `reduce` searches exhaustively for a
+/// shortest equivalent sequence.
+const EXACT_REDUCE_MAX_SIZE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MoveSeq {
+    moves: Vec<Move>,
+}
+
+impl MoveSeq {
+    pub fn new(moves: Vec<Move>) -> Self {
+        Self { moves }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Applies the sequence to a copy of `start`, returning `None` if any move is illegal.
+    pub fn apply_to(&self, start: &Puzzle) -> Option<Puzzle> {
+        let mut puzzle = start.clone();
+        for &item in &self.moves {
+            if !puzzle.apply_move(item) {
+                return None;
+            }
+        }
+        Some(puzzle)
+    }
+
+    /// Whether both sequences are legal from `start` and leave the board in
+    /// the same state, however different the moves themselves are.
+    pub fn equivalent(&self, other: &MoveSeq, start: &Puzzle) -> bool {
+        match (self.apply_to(start), other.apply_to(start)) {
+            (Some(ours), Some(theirs)) => ours == theirs,
+            _ => false,
+        }
+    }
+
+    /// Returns a sequence taking `start` to the same end state as `self`.
+    ///
+    /// On boards up to 3x3 the result is a shortest such sequence, found by a
+    /// breadth-first search between the two endpoint states. Larger boards only
+    /// have loops (returns to an earlier state) cut out, which removes
+    /// back-and-forth moves but gives no optimality guarantee.
+    pub fn reduce(&self, start: &Puzzle) -> Option<MoveSeq> {
+        let end = self.apply_to(start)?;
+        let shortened = self.remove_loops(start);
+
+        if start.size() > EXACT_REDUCE_MAX_SIZE {
+            return Some(shortened);
+        }
+
+        Some(Self::shortest_path(start, &end, shortened.len()).unwrap_or(shortened))
+    }
+
+    fn remove_loops(&self, start: &Puzzle) -> MoveSeq {
+        let mut puzzle = start.clone();
+        let mut seen = HashMap::new();
+        let mut moves = Vec::new();
+        seen.insert(puzzle.clone(), 0);
+
+        for &item in &self.moves {
+            puzzle.apply_move(item);
+            moves.push(item);
+
+            if let Some(&len) = seen.get(&puzzle) {
+                // Back at an earlier state: drop the loop and forget its states
+                moves.truncate(len);
+                seen.retain(|_, &mut index| index <= len);
+            } else {
+                seen.insert(puzzle.clone(), moves.len());
+            }
+        }
+
+        MoveSeq::new(moves)
+    }
+
+    fn shortest_path(start: &Puzzle, end: &Puzzle, max_len: usize) -> Option<MoveSeq> {
+        let mut parents: HashMap<Puzzle, Option<(Puzzle, Move)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        parents.insert(start.clone(), None);
+        queue.push_back((start.clone(), 0));
+
+        while let Some((state, depth)) = queue.pop_front() {
+            if state == *end {
+                let mut moves = Vec::new();
+                let mut current = state;
+                while let Some(Some((prev, item))) = parents.get(&current) {
+                    moves.push(*item);
+                    current = prev.clone();
+                }
+                moves.reverse();
+                return Some(MoveSeq::new(moves));
+            }
+            if depth >= max_len {
+                continue;
+            }
+
+            for item in Move::ALL {
+                let mut next = state.clone();
+                if next.apply_move(item) && !parents.contains_key(&next) {
+                    parents.insert(next.clone(), Some((state.clone(), item)));
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl From<Vec<Move>> for MoveSeq {
+    fn from(moves: Vec<Move>) -> Self {
+        Self::new(moves)
+    }
+}
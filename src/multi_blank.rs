@@ -0,0 +1,353 @@
+//! Boards with more than one empty cell.
+//!
+//! Any tile next to any empty cell can slide, so a move names the empty
+//! cell it fills as well as the direction. The goal keeps the tiles in
+//! order and puts every empty cell at the end. With two or more empty cells
+//! the parity argument of the one-blank puzzle no longer applies: on boards
+//! at least two cells in each direction, every arrangement can be solved.
+
+use crate::progress::Progress;
+use crate::puzzle::{misordered, Move, MAX_SIZE};
+use crate::search::{self, SearchState, StateSearch};
+use std::fmt;
+use std::str::FromStr;
+
+/// A tile sliding into the empty cell at `blank`. As with `Puzzle` moves,
+/// `movement` names the side of the empty cell the tile comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlankMove {
+    /// Row and column of the empty cell the tile fills.
+    pub blank: (usize, usize),
+    pub movement: Move,
+}
+
+impl fmt::Display for BlankMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{} {}", self.blank.0, self.blank.1, self.movement)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MultiBlankBoard {
+    rows: usize,
+    cols: usize,
+    /// Tiles row by row, with 0 for every empty cell.
+    cells: Vec<u32>,
+}
+
+impl MultiBlankBoard {
+    /// The solved board: tiles in order row by row, followed by `blanks`
+    /// empty cells.
+    pub fn new(rows: usize, cols: usize, blanks: usize) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        let cells = rows * cols;
+        if blanks == 0 || blanks > cells {
+            return Err("Blank count must be between 1 and the number of cells");
+        }
+        let tiles = (1..=(cells - blanks) as u32)
+            .chain(std::iter::repeat_n(0, blanks))
+            .collect();
+        Ok(Self {
+            rows,
+            cols,
+            cells: tiles,
+        })
+    }
+
+    /// Builds a board from its tiles listed row by row, with 0 for each
+    /// empty cell. The other values must be 1 up to the number of tiles,
+    /// each once.
+    pub fn from_tiles(rows: usize, cols: usize, tiles: &[u32]) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        if tiles.len() != rows * cols {
+            return Err("Wrong number of tiles for board size");
+        }
+        let blanks = tiles.iter().filter(|&&value| value == 0).count();
+        if blanks == 0 {
+            return Err("Board needs an empty cell");
+        }
+        let mut seen = vec![false; tiles.len() - blanks + 1];
+        for &value in tiles.iter().filter(|&&value| value != 0) {
+            match seen.get_mut(value as usize) {
+                Some(slot) if !*slot => *slot = true,
+                Some(_) => return Err("Duplicate tile"),
+                None => return Err("Tile value out of range"),
+            }
+        }
+        Ok(Self {
+            rows,
+            cols,
+            cells: tiles.to_vec(),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The tile at a cell, with 0 for empty cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is off the board; see `get_tile`.
+    pub fn tile_at(&self, row: usize, col: usize) -> u32 {
+        self.get_tile(row, col).expect("Cell is on the board")
+    }
+
+    /// The tile at a cell, or `None` if the cell is off the board.
+    pub fn get_tile(&self, row: usize, col: usize) -> Option<u32> {
+        (row < self.rows && col < self.cols).then(|| self.cells[row * self.cols + col])
+    }
+
+    /// All tiles row by row, with 0 for the empty cells.
+    pub fn tiles(&self) -> &[u32] {
+        &self.cells
+    }
+
+    /// Row and column of every empty cell, row by row.
+    pub fn blanks(&self) -> Vec<(usize, usize)> {
+        (0..self.cells.len())
+            .filter(|&cell| self.cells[cell] == 0)
+            .map(|cell| (cell / self.cols, cell % self.cols))
+            .collect()
+    }
+
+    /// Row and column of the tile that `movement` would slide, or `None` if
+    /// the move is not legal: its cell must be empty and the tile must be on
+    /// the board and not itself an empty cell.
+    pub fn moved_tile_position(&self, movement: BlankMove) -> Option<(usize, usize)> {
+        let (row, col) = movement.blank;
+        if row >= self.rows || col >= self.cols || self.tile_at(row, col) != 0 {
+            return None;
+        }
+        let (dx, dy) = movement.movement.as_offset();
+        let row = row.checked_add_signed(dx)?;
+        let col = col.checked_add_signed(dy)?;
+        (row < self.rows && col < self.cols && self.tile_at(row, col) != 0).then_some((row, col))
+    }
+
+    /// Every legal move, by empty cell and then in `Move::ALL` order.
+    pub fn legal_moves(&self) -> Vec<BlankMove> {
+        self.blanks()
+            .into_iter()
+            .flat_map(|blank| {
+                Move::ALL
+                    .into_iter()
+                    .map(move |movement| BlankMove { blank, movement })
+            })
+            .filter(|&movement| self.moved_tile_position(movement).is_some())
+            .collect()
+    }
+
+    /// Slides a tile into an empty cell, returning whether the move was
+    /// legal.
+    pub fn apply_move(&mut self, movement: BlankMove) -> bool {
+        let Some((row, col)) = self.moved_tile_position(movement) else {
+            return false;
+        };
+        let (blank_row, blank_col) = movement.blank;
+        self.cells[blank_row * self.cols + blank_col] = self.tile_at(row, col);
+        self.cells[row * self.cols + col] = 0;
+        true
+    }
+
+    /// Applies all of `moves` or none of them. On failure, returns the
+    /// position of the first illegal move.
+    pub fn apply_moves(&mut self, moves: &[BlankMove]) -> Result<(), usize> {
+        for (index, &movement) in moves.iter().enumerate() {
+            if !self.apply_move(movement) {
+                for &done in moves[..index].iter().rev() {
+                    self.apply_move(undo(done));
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        let tiles = self.cells.iter().filter(|&&value| value != 0).count();
+        self.cells[..tiles]
+            .iter()
+            .zip(1..)
+            .all(|(&value, expected)| value == expected)
+    }
+
+    /// Whether the goal can be reached. With one empty cell this is the
+    /// usual parity test. With more, tiles can be reordered freely unless
+    /// the board is a single row or column, where they can never pass each
+    /// other.
+    pub fn is_solvable(&self) -> bool {
+        let blanks = self.cells.len() - self.cells.iter().filter(|&&value| value != 0).count();
+        let in_order = || {
+            let tiles: Vec<u32> = self.cells.iter().copied().filter(|&v| v != 0).collect();
+            tiles.windows(2).all(|pair| pair[0] < pair[1])
+        };
+        if self.rows == 1 || self.cols == 1 {
+            return in_order();
+        }
+        if blanks > 1 {
+            return true;
+        }
+        let inversions = crate::puzzle::Puzzle::count_inversions(&self.cells);
+        let blank_row = self.cells.iter().position(|&value| value == 0).unwrap_or(0) / self.cols;
+        if self.cols % 2 == 1 {
+            inversions.is_multiple_of(2)
+        } else {
+            (inversions + self.rows - 1 - blank_row).is_multiple_of(2)
+        }
+    }
+
+    /// Sum of the distances of the tiles from their goal cells. Never
+    /// overestimates, since each move shifts one tile one cell.
+    pub fn manhattan_distance(&self) -> usize {
+        (0..self.cells.len())
+            .filter(|&cell| self.cells[cell] != 0)
+            .map(|cell| {
+                let (goal_row, goal_col) = self.goal_of(self.cells[cell]);
+                (cell / self.cols).abs_diff(goal_row) + (cell % self.cols).abs_diff(goal_col)
+            })
+            .sum()
+    }
+
+    /// Tiles that must leave their goal row or column to let another past.
+    /// Extra empty cells give them more room to step aside but never let
+    /// two tiles pass within a line, so each still costs two moves.
+    pub fn linear_conflicts(&self) -> usize {
+        let rows: usize = (0..self.rows)
+            .map(|row| {
+                misordered((0..self.cols).filter_map(|col| {
+                    let value = self.tile_at(row, col);
+                    let (goal_row, goal_col) = self.goal_of(value);
+                    (value != 0 && goal_row == row).then_some(goal_col)
+                }))
+            })
+            .sum();
+        let cols: usize = (0..self.cols)
+            .map(|col| {
+                misordered((0..self.rows).filter_map(|row| {
+                    let value = self.tile_at(row, col);
+                    let (goal_row, goal_col) = self.goal_of(value);
+                    (value != 0 && goal_col == col).then_some(goal_row)
+                }))
+            })
+            .sum();
+        rows + cols
+    }
+
+    /// Manhattan distance plus two moves per linear conflict. Never
+    /// overestimates.
+    pub fn heuristic(&self) -> usize {
+        self.manhattan_distance() + 2 * self.linear_conflicts()
+    }
+
+    /// A shortest solution, by IDA* guided by `heuristic`. Fails if the
+    /// board cannot be solved or the search grows past a fixed node limit.
+    pub fn solve(&self) -> Result<Vec<BlankMove>, &'static str> {
+        self.solve_with_progress(|_| true)
+    }
+
+    /// Like `solve`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<BlankMove>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if !self.is_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        search::run(StateSearch::new(self.clone()), callback)
+    }
+
+    /// Goal row and column of a tile.
+    fn goal_of(&self, value: u32) -> (usize, usize) {
+        let index = (value as usize).saturating_sub(1);
+        (index / self.cols, index % self.cols)
+    }
+}
+
+fn check_size(rows: usize, cols: usize) -> Result<(), &'static str> {
+    if rows == 0 || cols == 0 {
+        return Err("Board size must be positive");
+    }
+    if rows > MAX_SIZE || cols > MAX_SIZE {
+        return Err("Board size is too large");
+    }
+    Ok(())
+}
+
+/// The move that puts the tile moved by `movement` back.
+fn undo(movement: BlankMove) -> BlankMove {
+    let (dx, dy) = movement.movement.as_offset();
+    let (row, col) = movement.blank;
+    BlankMove {
+        blank: (row.wrapping_add_signed(dx), col.wrapping_add_signed(dy)),
+        movement: movement.movement.opposite(),
+    }
+}
+
+impl SearchState for MultiBlankBoard {
+    type Move = BlankMove;
+
+    fn moves(&self) -> Vec<BlankMove> {
+        self.legal_moves()
+    }
+
+    fn apply(&mut self, movement: BlankMove) -> BlankMove {
+        self.apply_move(movement);
+        undo(movement)
+    }
+
+    fn is_solved(&self) -> bool {
+        MultiBlankBoard::is_solved(self)
+    }
+
+    fn estimate(&self) -> usize {
+        self.heuristic()
+    }
+}
+
+impl fmt::Display for MultiBlankBoard {
+    /// Draws the board as a grid with `.` for empty cells. The alternate
+    /// flag (`{:#}`) instead writes the single-line form
+    /// `rowsxcols:tile,tile,...` with 0 for empty cells, which parses back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let tiles: Vec<String> = self.cells.iter().map(u32::to_string).collect();
+            return write!(f, "{}x{}:{}", self.rows, self.cols, tiles.join(","));
+        }
+        let width = (self.cells.len() - 1).to_string().len().max(2);
+        for row in self.cells.chunks(self.cols) {
+            for &value in row {
+                match value {
+                    0 => write!(f, "{:>width$} ", ".")?,
+                    value => write!(f, "{:width$} ", value)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MultiBlankBoard {
+    type Err = &'static str;
+
+    /// Parses `rowsxcols:` followed by the tiles row by row, separated by
+    /// whitespace or commas, with 0 for each empty cell.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, tiles) = s.split_once(':').ok_or("Missing rowsxcols: header")?;
+        let parse = |text: &str| text.trim().parse().map_err(|_| "Invalid board size");
+        let (rows, cols) = header.split_once('x').ok_or("Invalid board size")?;
+        let tiles = tiles
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse().map_err(|_| "Invalid tile value"))
+            .collect::<Result<Vec<u32>, _>>()?;
+        Self::from_tiles(parse(rows)?, parse(cols)?, &tiles)
+    }
+}
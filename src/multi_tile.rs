@@ -0,0 +1,279 @@
+//! The multi-tile move metric, where sliding a whole line of tiles toward
+//! the empty space counts as one move, as on physical and most online
+//! puzzles.
+//!
+//! A slide of `tiles` in one direction is that many single moves in a row,
+//! so any solution converts to slides by merging runs, and back again.
+//! Solutions shortest in slides are usually longer in single moves than
+//! solutions shortest in single moves.
+//!
+//! A slide of several tiles also moves the empty space several cells, which
+//! for a robot solver is the physical effort. `SlideCost` weighs slides
+//! against that travel, so the solvers can minimise either or a mix.
+
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, SearchState, StateSearch};
+use std::fmt;
+
+/// Several tiles sliding together toward the empty space. As with single
+/// moves, `movement` names the side of the empty space the tiles come from
+/// and the way the empty space travels through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slide {
+    pub movement: Move,
+    /// Tiles moved, at least 1.
+    pub tiles: usize,
+}
+
+impl fmt::Display for Slide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.movement, self.tiles)
+    }
+}
+
+/// What a multi-tile solution costs: `slides` for each slide plus `travel`
+/// for each cell the empty space travels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlideCost {
+    pub slides: usize,
+    pub travel: usize,
+}
+
+impl SlideCost {
+    /// The fewest slides, the usual multi-tile metric.
+    pub const SLIDES: Self = Self {
+        slides: 1,
+        travel: 0,
+    };
+
+    /// The least travel of the empty space, which is the fewest single
+    /// moves.
+    pub const TRAVEL: Self = Self {
+        slides: 0,
+        travel: 1,
+    };
+
+    pub fn of(&self, slide: Slide) -> usize {
+        self.slides + self.travel * slide.tiles
+    }
+
+    /// The total cost of `slides`.
+    pub fn total(&self, slides: &[Slide]) -> usize {
+        slides.iter().map(|&slide| self.of(slide)).sum()
+    }
+}
+
+impl Default for SlideCost {
+    fn default() -> Self {
+        Self::SLIDES
+    }
+}
+
+/// Cells the empty space travels over `slides`.
+pub fn blank_travel(slides: &[Slide]) -> usize {
+    slides.iter().map(|slide| slide.tiles).sum()
+}
+
+/// Merges runs of single moves in the same direction into slides.
+pub fn to_slides(moves: &[Move]) -> Vec<Slide> {
+    let mut slides: Vec<Slide> = Vec::new();
+    for &movement in moves {
+        match slides.last_mut() {
+            Some(last) if last.movement == movement => last.tiles += 1,
+            _ => slides.push(Slide { movement, tiles: 1 }),
+        }
+    }
+    slides
+}
+
+/// The single moves that make up `slides`.
+pub fn to_moves(slides: &[Slide]) -> Vec<Move> {
+    slides
+        .iter()
+        .flat_map(|slide| std::iter::repeat_n(slide.movement, slide.tiles))
+        .collect()
+}
+
+impl Puzzle {
+    /// Tiles on the side `movement` names, between the empty space and the
+    /// edge, which is the most one slide that way can move.
+    pub fn slide_reach(&self, movement: Move) -> usize {
+        let (row, col) = self.blank_position();
+        match movement {
+            Move::Up => self.rows() - 1 - row,
+            Move::Down => row,
+            Move::Left => self.cols() - 1 - col,
+            Move::Right => col,
+        }
+    }
+
+    /// Every legal slide, by direction in `Move::ALL` order and then by
+    /// number of tiles.
+    pub fn legal_slides(&self) -> Vec<Slide> {
+        Move::ALL
+            .into_iter()
+            .flat_map(|movement| {
+                (1..=self.slide_reach(movement)).map(move |tiles| Slide { movement, tiles })
+            })
+            .collect()
+    }
+
+    /// Slides a line of tiles, returning whether the slide was legal. An
+    /// illegal slide leaves the board unchanged.
+    pub fn apply_slide(&mut self, slide: Slide) -> bool {
+        if slide.tiles == 0 || slide.tiles > self.slide_reach(slide.movement) {
+            return false;
+        }
+        for _ in 0..slide.tiles {
+            self.apply_move(slide.movement);
+        }
+        true
+    }
+
+    /// Applies all of `slides` or none of them. On failure, returns the
+    /// position of the first illegal slide.
+    pub fn apply_slides(&mut self, slides: &[Slide]) -> Result<(), usize> {
+        for (index, &slide) in slides.iter().enumerate() {
+            if !self.apply_slide(slide) {
+                for &done in slides[..index].iter().rev() {
+                    self.apply_slide(Slide {
+                        movement: done.movement.opposite(),
+                        tiles: done.tiles,
+                    });
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    /// A lower bound on the slides left. Slides along a row move the tiles
+    /// of that row one column each and leave rows alone, so the tiles'
+    /// total distance from their goal columns falls by at most one less
+    /// than the width per slide, and the farthest tile needs a slide for
+    /// every column it is off; columns likewise.
+    pub fn slide_distance(&self) -> usize {
+        let goals = self.goals();
+        let (mut across, mut down) = (0, 0);
+        let (mut widest, mut tallest) = (0, 0);
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                let value = self.tile_at(row, col);
+                if value == 0 {
+                    continue;
+                }
+                let (goal_row, goal_col) = goals[value as usize];
+                across += col.abs_diff(goal_col);
+                down += row.abs_diff(goal_row);
+                widest = widest.max(col.abs_diff(goal_col));
+                tallest = tallest.max(row.abs_diff(goal_row));
+            }
+        }
+        let per_slide = |len: usize| len.saturating_sub(1).max(1);
+        across.div_ceil(per_slide(self.cols())).max(widest)
+            + down.div_ceil(per_slide(self.rows())).max(tallest)
+    }
+
+    /// A lower bound on what solving costs under `cost`. Every solution
+    /// has at least `slide_distance` slides and, since the empty space
+    /// travels one cell per single move, at least the Manhattan distance in
+    /// travel.
+    pub fn slide_cost_estimate(&self, cost: SlideCost) -> usize {
+        cost.slides * self.slide_distance() + cost.travel * self.manhattan_distance()
+    }
+
+    /// A solution with the fewest slides, by IDA* guided by
+    /// `slide_distance`. Fails if the board cannot be solved or the search
+    /// grows past a fixed node limit, which most 4x4 boards reach.
+    pub fn solve_slides(&self) -> Result<Vec<Slide>, &'static str> {
+        self.solve_slides_weighted(1)
+    }
+
+    /// Like `solve_slides`, with the estimate multiplied by `weight`, so
+    /// the solution may take up to `weight` times the fewest slides. A
+    /// weight of 3 solves most 4x4 boards in well under a second.
+    pub fn solve_slides_weighted(&self, weight: usize) -> Result<Vec<Slide>, &'static str> {
+        self.solve_slides_with_progress(SlideCost::SLIDES, weight, |_| true)
+    }
+
+    /// A solution of least `cost`, by IDA* guided by
+    /// `slide_cost_estimate`. With `SlideCost::TRAVEL` it is a shortest
+    /// solution in single moves, merged into slides.
+    pub fn solve_slides_by(&self, cost: SlideCost) -> Result<Vec<Slide>, &'static str> {
+        self.solve_slides_with_progress(cost, 1, |_| true)
+    }
+
+    /// Like `solve_slides_by`, with the estimate multiplied by `weight`,
+    /// reporting progress to `callback`; returning `false` from the
+    /// callback cancels the search.
+    pub fn solve_slides_with_progress<F>(
+        &self,
+        cost: SlideCost,
+        weight: usize,
+        callback: F,
+    ) -> Result<Vec<Slide>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if weight == 0 {
+            return Err("Weight must be positive");
+        }
+        if cost.slides == 0 && cost.travel == 0 {
+            return Err("Slides or travel must have a cost");
+        }
+        if !self.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        let state = SlideState {
+            puzzle: self.clone(),
+            cost,
+            weight,
+        };
+        search::run(StateSearch::new(state), callback)
+    }
+}
+
+/// A board searched in slides priced by `cost`, with its estimate scaled by
+/// `weight`.
+struct SlideState {
+    puzzle: Puzzle,
+    cost: SlideCost,
+    /// Multiplies the estimate, trading solution cost for speed.
+    weight: usize,
+}
+
+impl SearchState for SlideState {
+    type Move = Slide;
+
+    fn moves(&self) -> Vec<Slide> {
+        self.puzzle.legal_slides()
+    }
+
+    fn apply(&mut self, slide: Slide) -> Slide {
+        self.puzzle.apply_slide(slide);
+        Slide {
+            movement: slide.movement.opposite(),
+            tiles: slide.tiles,
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.puzzle.is_solved()
+    }
+
+    fn cost(&self, slide: Slide) -> usize {
+        self.cost.of(slide)
+    }
+
+    fn estimate(&self) -> usize {
+        self.weight * self.puzzle.slide_cost_estimate(self.cost)
+    }
+
+    /// Two slides along the same line in a row are never needed: they make
+    /// one slide, or none, which is never more slides or more travel.
+    fn redundant_after(&self, previous: Slide, slide: Slide) -> bool {
+        let vertical = |movement: Move| matches!(movement, Move::Up | Move::Down);
+        vertical(previous.movement) == vertical(slide.movement)
+    }
+}
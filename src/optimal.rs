@@ -0,0 +1,116 @@
+//! Every shortest solution of a board, not just the one a search happens
+//! to find first.
+//!
+//! Trainers can show players the alternatives to their own solution, and
+//! tests can check that tie-breaking picks a solution from the full set.
+//! Some boards have a great many shortest solutions, so they are produced
+//! lazily. Enumeration is quick on 3x3 boards, where the tablebase only
+//! lets it follow optimal moves, and on 4x4 boards a few dozen moves from
+//! solved.
+
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+
+/// Shortest solutions in `Move::ALL` order of their moves, from
+/// `Puzzle::optimal_solutions`.
+#[derive(Debug, Clone)]
+pub struct OptimalSolutions {
+    state: Puzzle,
+    length: usize,
+    path: Vec<Move>,
+    /// For each depth up to the path's length, how many of `Move::ALL`
+    /// have been tried there.
+    tried: Vec<usize>,
+    done: bool,
+}
+
+impl OptimalSolutions {
+    /// Moves in each solution.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl Iterator for OptimalSolutions {
+    type Item = Vec<Move>;
+
+    fn next(&mut self) -> Option<Vec<Move>> {
+        if self.done {
+            return None;
+        }
+        if self.length == 0 {
+            self.done = true;
+            return Some(Vec::new());
+        }
+
+        loop {
+            let depth = self.path.len();
+            let Some(&movement) = Move::ALL.get(self.tried[depth]) else {
+                // Every move from here has been tried
+                self.tried.pop();
+                let Some(last) = self.path.pop() else {
+                    self.done = true;
+                    return None;
+                };
+                self.state.apply_move(last.opposite());
+                continue;
+            };
+            self.tried[depth] += 1;
+
+            let undoes = self.path.last() == Some(&movement.opposite());
+            if undoes || !self.state.apply_move(movement) {
+                continue;
+            }
+            let remaining = self.length - depth - 1;
+            if remaining == 0 {
+                let solved = self.state.is_solved();
+                self.state.apply_move(movement.opposite());
+                if solved {
+                    let mut solution = self.path.clone();
+                    solution.push(movement);
+                    return Some(solution);
+                }
+                continue;
+            }
+            if lower_bound(&self.state) > remaining {
+                self.state.apply_move(movement.opposite());
+                continue;
+            }
+            self.path.push(movement);
+            self.tried.push(0);
+        }
+    }
+}
+
+impl Puzzle {
+    /// Lazily yields every distinct shortest solution. Finds the shortest
+    /// length first, by tablebase on 3x3 boards and by `solve_walking_distance`
+    /// otherwise.
+    pub fn optimal_solutions(&self) -> Result<OptimalSolutions, &'static str> {
+        if !self.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        let length = match tablebase::distance_to_goal(self) {
+            Some(distance) => distance,
+            None => self.solve_walking_distance()?.len(),
+        };
+        Ok(OptimalSolutions {
+            state: self.clone(),
+            length,
+            path: Vec::with_capacity(length),
+            tried: vec![0],
+            done: false,
+        })
+    }
+
+    /// Up to `limit` distinct shortest solutions, in the order of
+    /// `optimal_solutions`.
+    pub fn solve_all_optimal(&self, limit: usize) -> Result<Vec<Vec<Move>>, &'static str> {
+        Ok(self.optimal_solutions()?.take(limit).collect())
+    }
+}
+
+/// Exact on 3x3 boards, so only optimal moves are followed there.
+fn lower_bound(puzzle: &Puzzle) -> usize {
+    tablebase::distance_to_goal(puzzle).unwrap_or_else(|| puzzle.walking_distance())
+}
@@ -0,0 +1,101 @@
+use crate::curriculum::scramble_line;
+use crate::puzzle::{Move, Puzzle};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// An external solver used as an independent reference for solution lengths.
+///
+/// The binary is run once per board, receiving the tiles on stdin as a
+/// single comma-separated line in reading order (0 for the blank). The first
+/// line of its output must be either the solution length, or the solution
+/// itself as whitespace-separated moves. A binary that exits with an error,
+/// or answers with anything else, fails the check instead of counting as a
+/// solution of some length.
+#[derive(Debug, Clone)]
+pub struct Oracle {
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub puzzle: Puzzle,
+    pub ours: Result<usize, &'static str>,
+    pub theirs: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CrossValidation {
+    pub checked: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl Oracle {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn solution_length(&self, puzzle: &Puzzle) -> io::Result<usize> {
+        let mut child = Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", scramble_line(puzzle))?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "Oracle failed with {}",
+                output.status
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().next().unwrap_or("").trim();
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        if line.is_empty() {
+            return Err(invalid("Oracle gave no answer"));
+        }
+        if let Ok(length) = line.parse() {
+            return Ok(length);
+        }
+        let mut length = 0;
+        for word in line.split_whitespace() {
+            word.parse::<Move>()
+                .map_err(|_| invalid("Oracle answer is neither a length nor moves"))?;
+            length += 1;
+        }
+        Ok(length)
+    }
+
+    /// Solves `count` random boards of the given size with both IDA* and the
+    /// oracle, recording every board where the solution lengths differ.
+    pub fn cross_validate(&self, size: usize, count: usize) -> io::Result<CrossValidation> {
+        let goal =
+            Puzzle::new(size).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let mut report = CrossValidation::default();
+
+        for _ in 0..count {
+            let mut puzzle = goal.clone();
+            puzzle.shuffle();
+
+            let theirs = self.solution_length(&puzzle)?;
+            let ours = puzzle.solve().map(|moves| moves.len());
+            report.checked += 1;
+
+            if ours != Ok(theirs) {
+                report.discrepancies.push(Discrepancy {
+                    puzzle,
+                    ours,
+                    theirs,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::puzzle::{Move, Puzzle};
+
+/// How a board is seen relative to its upright position: turned clockwise by
+/// `quarter_turns` and then, if `mirrored`, flipped left to right. Tile
+/// labels are physical, so they stay attached to their tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Orientation {
+    pub quarter_turns: u8,
+    pub mirrored: bool,
+}
+
+impl Orientation {
+    pub const UPRIGHT: Orientation = Orientation::new(0, false);
+    pub const ROTATE_90: Orientation = Orientation::new(1, false);
+    pub const ROTATE_180: Orientation = Orientation::new(2, false);
+    pub const ROTATE_270: Orientation = Orientation::new(3, false);
+    pub const MIRROR: Orientation = Orientation::new(0, true);
+
+    pub const fn new(quarter_turns: u8, mirrored: bool) -> Self {
+        Self {
+            quarter_turns: quarter_turns % 4,
+            mirrored,
+        }
+    }
+
+    /// The orientation that undoes this one.
+    pub fn inverse(&self) -> Self {
+        if self.mirrored {
+            // A mirror conjugates rotations into their inverses, so a mirrored
+            // orientation is its own inverse.
+            *self
+        } else {
+            Self::new(4 - self.quarter_turns, false)
+        }
+    }
+
+    /// Where the cell at `(row, col)` of an upright board ends up, or
+    /// `None` if the cell is off the board.
+    pub fn transform_position(
+        &self,
+        size: usize,
+        position: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        (position.0 < size && position.1 < size)
+            .then(|| self.transform_cell((size, size), position))
+    }
+
+    /// Like `transform_position`, on an upright board of `rows` rows and
+    /// `cols` columns. Each quarter turn swaps the two.
+    fn transform_cell(
+        &self,
+        (mut rows, mut cols): (usize, usize),
+        (row, col): (usize, usize),
+    ) -> (usize, usize) {
+        let (mut row, mut col) = (row, col);
+        for _ in 0..self.quarter_turns {
+            (row, col) = (col, rows - 1 - row);
+            (rows, cols) = (cols, rows);
+        }
+        if self.mirrored {
+            col = cols - 1 - col;
+        }
+        (row, col)
+    }
+
+    /// The move that looks like `movement` once the board is reoriented.
+    pub fn transform_move(&self, movement: Move) -> Move {
+        let (mut dx, mut dy) = movement.as_offset();
+        for _ in 0..self.quarter_turns {
+            (dx, dy) = (dy, -dx);
+        }
+        if self.mirrored {
+            dy = -dy;
+        }
+        Move::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_offset() == (dx, dy))
+            .unwrap()
+    }
+
+    /// `puzzle` as seen from this orientation. The goal turns with the
+    /// tiles, so the board stays as far from solved as it was.
+    pub fn apply(&self, puzzle: &Puzzle) -> Puzzle {
+        self.apply_tiles(puzzle)
+            .with_goal(&self.apply_tiles(&puzzle.goal()))
+            .expect("reorienting keeps the goal the board's shape")
+    }
+
+    /// The tiles of `puzzle` as seen from this orientation, towards the
+    /// standard goal.
+    fn apply_tiles(&self, puzzle: &Puzzle) -> Puzzle {
+        let (rows, cols) = (puzzle.rows(), puzzle.cols());
+        let (new_rows, new_cols) = if self.quarter_turns % 2 == 1 {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        };
+        let mut tiles = vec![0; rows * cols];
+        for row in 0..rows {
+            for col in 0..cols {
+                let (new_row, new_col) = self.transform_cell((rows, cols), (row, col));
+                tiles[new_row * new_cols + new_col] = puzzle.tile_at(row, col);
+            }
+        }
+        Puzzle::from_tiles_rectangular(new_rows, new_cols, &tiles)
+            .expect("reorienting a valid board keeps it valid")
+    }
+
+    /// Turns a board seen from this orientation back upright, ready to solve.
+    pub fn normalize(&self, puzzle: &Puzzle) -> Puzzle {
+        self.inverse().apply(puzzle)
+    }
+
+    /// The solved board as seen from this orientation.
+    pub fn goal(&self, size: usize) -> Result<Puzzle, &'static str> {
+        Puzzle::new(size).map(|goal| self.apply(&goal))
+    }
+
+    /// Maps moves found on the upright board to the moves to make on the
+    /// board as seen from this orientation.
+    pub fn map_solution(&self, moves: &[Move]) -> Vec<Move> {
+        moves
+            .iter()
+            .map(|&movement| self.transform_move(movement))
+            .collect()
+    }
+}
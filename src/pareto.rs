@@ -0,0 +1,165 @@
+//! Several solutions of one board trading length against the number of
+//! pushes, for physical execution or animation.
+//!
+//! A run of moves in the same direction slides a line of tiles along
+//! together, which a hand or a robot arm does as one push and an animation
+//! can show as one stroke. The shortest solution rarely has the fewest
+//! pushes, so `pareto_solutions` finds solutions under a few weightings of
+//! the two and keeps those no other one beats on both.
+
+use crate::puzzle::{Move, Puzzle};
+use crate::solution::Solution;
+
+/// Costs of one move and of starting a new push, from favouring short
+/// solutions to favouring few pushes. A weight of 100 makes the other
+/// measure a tie-breaker, as no 4x4 solution worth finding has 100 pushes.
+const WEIGHTINGS: [(usize, usize); 5] = [(100, 1), (3, 1), (1, 1), (1, 3), (1, 100)];
+
+/// Nodes expanded per weighting before giving up. Enough for any 3x3 board
+/// and easy 4x4 ones.
+const MAX_NODES: usize = 20_000_000;
+
+/// A solution and what it costs to carry out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tradeoff {
+    pub solution: Solution,
+    pub moves: usize,
+    pub pushes: usize,
+}
+
+impl Tradeoff {
+    fn new(solution: Solution) -> Self {
+        Self {
+            moves: solution.len(),
+            pushes: solution.pushes(),
+            solution,
+        }
+    }
+
+    /// Whether `self` is no worse than `other` on both measures and better
+    /// on at least one.
+    pub fn dominates(&self, other: &Tradeoff) -> bool {
+        let key = |t: &Tradeoff| [t.moves, t.pushes];
+        let (mine, theirs) = (key(self), key(other));
+        mine.iter().zip(&theirs).all(|(a, b)| a <= b) && mine != theirs
+    }
+}
+
+/// The solutions of `puzzle` that no other found one beats on both length
+/// and pushes, shortest first. The first is a shortest solution with the
+/// fewest pushes among those, the last has the fewest pushes possible.
+/// Weightings that run out of nodes are left out, so on hard 4x4 boards the
+/// set may miss either end, and it is an error only if none finished.
+pub fn pareto_solutions(puzzle: &Puzzle) -> Result<Vec<Tradeoff>, &'static str> {
+    if !puzzle.is_current_state_solvable() {
+        return Err("Puzzle is not solvable");
+    }
+
+    let mut found: Vec<Tradeoff> = Vec::new();
+    let mut failure = None;
+    for (move_cost, push_cost) in WEIGHTINGS {
+        let moves = match PushSearch::new(puzzle, move_cost, push_cost).run() {
+            Ok(moves) => moves,
+            Err(err) => {
+                failure = Some(err);
+                continue;
+            }
+        };
+        let candidate = Tradeoff::new(Solution::new(moves));
+        if found
+            .iter()
+            .any(|other| other.dominates(&candidate) || other.solution == candidate.solution)
+        {
+            continue;
+        }
+        found.retain(|other| !candidate.dominates(other));
+        found.push(candidate);
+    }
+    if let (true, Some(err)) = (found.is_empty(), failure) {
+        return Err(err);
+    }
+    found.sort_by_key(|tradeoff| (tradeoff.moves, tradeoff.pushes));
+    Ok(found)
+}
+
+/// IDA* minimising `move_cost` per move plus `push_cost` per push.
+struct PushSearch {
+    state: Puzzle,
+    move_cost: usize,
+    push_cost: usize,
+    path: Vec<Move>,
+    nodes: usize,
+}
+
+impl PushSearch {
+    fn new(puzzle: &Puzzle, move_cost: usize, push_cost: usize) -> Self {
+        Self {
+            state: puzzle.clone(),
+            move_cost,
+            push_cost,
+            path: Vec::new(),
+            nodes: 0,
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<Move>, &'static str> {
+        let mut bound = self.estimate(None);
+        loop {
+            match self.visit(0, bound)? {
+                None => return Ok(self.path),
+                Some(next) => bound = next,
+            }
+        }
+    }
+
+    /// Never overestimates the remaining cost. Every move shifts one tile
+    /// one cell, and a push shifts at most a row's worth of tiles less one,
+    /// except that a push under way may carry on without a new one.
+    fn estimate(&self, last_move: Option<Move>) -> usize {
+        let line = (self.state.size() - 1).max(1);
+        let moves = self.state.walking_distance();
+        let pushes = self
+            .state
+            .manhattan_distance()
+            .div_ceil(line)
+            .saturating_sub(last_move.is_some() as usize);
+        self.move_cost * moves + self.push_cost * pushes
+    }
+
+    /// Depth-first search below `bound`. Returns `None` once solved, with
+    /// the solution in `path`, or the smallest total that exceeded the bound.
+    fn visit(&mut self, cost: usize, bound: usize) -> Result<Option<usize>, &'static str> {
+        let last_move = self.path.last().copied();
+        let total = cost + self.estimate(last_move);
+        if total > bound {
+            return Ok(Some(total));
+        }
+        if self.state.is_solved() {
+            return Ok(None);
+        }
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return Err("Node limit exceeded");
+        }
+
+        let mut next_bound = usize::MAX;
+        for movement in Move::ALL {
+            if last_move == Some(movement.opposite()) || !self.state.apply_move(movement) {
+                continue;
+            }
+            let step = if last_move == Some(movement) {
+                self.move_cost
+            } else {
+                self.move_cost + self.push_cost
+            };
+            self.path.push(movement);
+            match self.visit(cost + step, bound)? {
+                None => return Ok(None),
+                Some(exceeded) => next_bound = next_bound.min(exceeded),
+            }
+            self.path.pop();
+            self.state.apply_move(movement.opposite());
+        }
+        Ok(Some(next_bound))
+    }
+}
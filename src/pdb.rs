@@ -0,0 +1,410 @@
+//! Additive pattern databases for the 4x4 board.
+//!
+//! The tiles are split into disjoint groups, by default of six, six and
+//! three, or in any partition the caller chooses. For each group a table
+//! holds, for every placement of its tiles, the fewest moves of
+//! those tiles needed to bring them home, whatever the other tiles do. Moves
+//! of other tiles are free, so no move is counted twice and the three
+//! estimates can be added without overestimating. Larger groups give
+//! stronger estimates but need far more memory: a table has one byte for
+//! every placement of its group's tiles.
+
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, IdaStarSearch};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+const SIZE: usize = 4;
+const CELLS: usize = SIZE * SIZE;
+const UNSEEN: u8 = u8::MAX;
+
+/// Tiles in each group of the default partition. Each group is a compact
+/// block of the goal, so its tiles interfere with each other often and the
+/// tables stay informative.
+pub const DEFAULT_PARTITION: [&[u8]; 3] =
+    [&[1, 5, 6, 9, 10, 13], &[7, 8, 11, 12, 14, 15], &[2, 3, 4]];
+
+/// Largest group a table can be built for. An eight-tile table takes about
+/// 500 MB, and building it needs sixteen times as much.
+pub const MAX_GROUP: usize = 8;
+
+/// Where `pdb_file` falls back to when a front end needs a default.
+pub const DEFAULT_FILE: &str = "pdb663.bin";
+
+/// Identifies a saved database and its layout: the partition, then the
+/// tables in group order.
+const MAGIC: &[u8; 5] = b"SPPD2";
+/// Files written before partitions could be chosen hold the default
+/// partition's tables without a partition header.
+const MAGIC_DEFAULT: &[u8; 5] = b"SPPD1";
+
+static INSTALLED: OnceLock<PatternDatabase> = OnceLock::new();
+
+/// Databases handed out by `shared`, at most one per partition.
+static SHARED: Mutex<Vec<Arc<PatternDatabase>>> = Mutex::new(Vec::new());
+
+#[derive(PartialEq, Eq)]
+pub struct PatternDatabase {
+    groups: Vec<Vec<u8>>,
+    /// One table per group, indexed by `rank`.
+    tables: Vec<Vec<u8>>,
+}
+
+impl PatternDatabase {
+    /// Builds the default partition's tables by breadth-first search back
+    /// from the goal. Takes about twenty seconds in a release build and
+    /// needs about 100 MB while a six-tile table is being built, so prefer
+    /// `load_or_build`.
+    pub fn build() -> Self {
+        Self::build_partition(&DEFAULT_PARTITION).expect("The default partition is valid")
+    }
+
+    /// Builds tables for the given groups of tiles, such as three groups of
+    /// five, or groups of seven and eight on machines with the memory for
+    /// them. Groups must not share tiles. Tiles left out of every group are
+    /// not counted, which keeps the estimate admissible but weakens it, so
+    /// `estimate` takes the Manhattan distance where that is larger.
+    pub fn build_partition<G: AsRef<[u8]>>(groups: &[G]) -> Result<Self, &'static str> {
+        let groups = validate(groups)?;
+        let tables = groups.iter().map(|group| build_table(group)).collect();
+        Ok(Self { groups, tables })
+    }
+
+    /// The groups of tiles, one per table.
+    pub fn partition(&self) -> &[Vec<u8>] {
+        &self.groups
+    }
+
+    /// Size of all tables together.
+    pub fn bytes(&self) -> usize {
+        self.tables.iter().map(Vec::len).sum()
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let (groups, mut rest) = if let Some(rest) = bytes.strip_prefix(MAGIC_DEFAULT) {
+            (validate(&DEFAULT_PARTITION).unwrap(), rest)
+        } else {
+            let rest = bytes
+                .strip_prefix(MAGIC)
+                .ok_or_else(|| invalid("Not a pattern database file"))?;
+            read_partition(rest).ok_or_else(|| invalid("Pattern database file is corrupt"))?
+        };
+        if rest.len() != partition_bytes(&groups) {
+            return Err(invalid("Pattern database file has the wrong length"));
+        }
+
+        let mut tables = Vec::with_capacity(groups.len());
+        for group in &groups {
+            let (table, tail) = rest.split_at(placements(group.len()));
+            if table.contains(&UNSEEN) {
+                return Err(invalid("Pattern database file is corrupt"));
+            }
+            tables.push(table.to_vec());
+            rest = tail;
+        }
+        Ok(Self { groups, tables })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + CELLS + self.bytes());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(self.groups.len() as u8);
+        for group in &self.groups {
+            bytes.push(group.len() as u8);
+            bytes.extend_from_slice(group);
+        }
+        for table in &self.tables {
+            bytes.extend_from_slice(table);
+        }
+        fs::write(path, bytes)
+    }
+
+    /// Loads the default partition's database from `path`, or builds it and
+    /// tries to save it there if the file is missing, unreadable or holds
+    /// another partition. A failed save is ignored, as the database is still
+    /// usable.
+    pub fn load_or_build(path: &Path) -> Self {
+        Self::load_or_build_partition(path, &DEFAULT_PARTITION)
+            .expect("The default partition is valid")
+    }
+
+    /// Like `load_or_build`, for the given groups of tiles; see
+    /// `build_partition`.
+    pub fn load_or_build_partition<G: AsRef<[u8]>>(
+        path: &Path,
+        groups: &[G],
+    ) -> Result<Self, &'static str> {
+        let groups = validate(groups)?;
+        if let Ok(database) = Self::load(path) {
+            if database.groups == groups {
+                return Ok(database);
+            }
+        }
+        let database = Self::build_partition(&groups)?;
+        let _ = database.save(path);
+        Ok(database)
+    }
+
+    /// A lower bound on the moves to the standard goal, or `None` if the
+    /// board is not 4x4. Never below the Manhattan distance.
+    pub fn estimate(&self, puzzle: &Puzzle) -> Option<usize> {
+        if puzzle.rows() != SIZE || puzzle.cols() != SIZE || !puzzle.has_standard_goal() {
+            return None;
+        }
+        let mut cell_of = [0; CELLS];
+        for cell in 0..CELLS {
+            cell_of[puzzle.tile_at(cell / SIZE, cell % SIZE) as usize] = cell as u8;
+        }
+
+        let estimate: usize = self
+            .groups
+            .iter()
+            .zip(&self.tables)
+            .map(|(group, table)| {
+                let mut positions = [0; CELLS];
+                for (position, &tile) in positions.iter_mut().zip(group.iter()) {
+                    *position = cell_of[tile as usize];
+                }
+                table[rank(&positions[..group.len()])] as usize
+            })
+            .sum();
+        // Tiles left out of every group count nothing, so the tables alone
+        // can fall below the Manhattan distance, even to 0 off the goal
+        let covered: usize = self.groups.iter().map(Vec::len).sum();
+        if covered < CELLS - 1 {
+            return Some(estimate.max(puzzle.manhattan_distance()));
+        }
+        Some(estimate)
+    }
+}
+
+/// The database for `groups`, loaded or built as `load_or_build_partition`
+/// does the first time a partition is asked for, and shared by every later
+/// call for it. Other callers wait while a database is being built, rather
+/// than build the same tables twice.
+pub fn shared<G: AsRef<[u8]>>(
+    path: &Path,
+    groups: &[G],
+) -> Result<Arc<PatternDatabase>, &'static str> {
+    let groups = validate(groups)?;
+    let mut shared = SHARED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(database) = shared.iter().find(|database| database.groups == groups) {
+        return Ok(Arc::clone(database));
+    }
+    let database = Arc::new(PatternDatabase::load_or_build_partition(path, &groups)?);
+    shared.push(Arc::clone(&database));
+    Ok(database)
+}
+
+/// Makes `database` the one `Puzzle::pattern_database_distance` reads and
+/// returns it. Only the first call has an effect; later ones return the
+/// database installed first.
+pub fn install(database: PatternDatabase) -> &'static PatternDatabase {
+    INSTALLED.get_or_init(|| database)
+}
+
+pub fn installed() -> Option<&'static PatternDatabase> {
+    INSTALLED.get()
+}
+
+/// Size of all tables of the default partition together.
+pub fn table_bytes() -> usize {
+    partition_bytes(&DEFAULT_PARTITION)
+}
+
+/// Parses groups of tiles separated by `/`, each a list of tile numbers
+/// separated by commas, e.g. `1,2,3,4,5/6,7,8,9,10/11,12,13,14,15`.
+pub fn parse_partition(text: &str) -> Result<Vec<Vec<u8>>, &'static str> {
+    let groups = text
+        .split('/')
+        .map(|group| {
+            group
+                .split(',')
+                .map(|tile| tile.trim().parse().map_err(|_| "Invalid tile number"))
+                .collect::<Result<Vec<u8>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    validate(&groups)
+}
+
+/// Checks that every group is small enough and holds distinct tiles that no
+/// other group holds.
+fn validate<G: AsRef<[u8]>>(groups: &[G]) -> Result<Vec<Vec<u8>>, &'static str> {
+    if groups.is_empty() {
+        return Err("A partition needs at least one group");
+    }
+    let mut used = [false; CELLS];
+    for group in groups {
+        let group = group.as_ref();
+        if group.is_empty() {
+            return Err("Groups must not be empty");
+        }
+        if group.len() > MAX_GROUP {
+            return Err("Group has too many tiles");
+        }
+        for &tile in group {
+            let slot = used
+                .get_mut(tile as usize)
+                .filter(|_| tile != 0)
+                .ok_or("Tile number out of range")?;
+            if *slot {
+                return Err("Tile appears in more than one group");
+            }
+            *slot = true;
+        }
+    }
+    Ok(groups.iter().map(|group| group.as_ref().to_vec()).collect())
+}
+
+/// The partition at the start of a saved file, and the bytes after it.
+fn read_partition(bytes: &[u8]) -> Option<(Vec<Vec<u8>>, &[u8])> {
+    let (&count, mut rest) = bytes.split_first()?;
+    let mut groups = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (&len, tail) = rest.split_first()?;
+        let (group, tail) = tail.split_at_checked(len as usize)?;
+        groups.push(group.to_vec());
+        rest = tail;
+    }
+    Some((validate(&groups).ok()?, rest))
+}
+
+fn partition_bytes<G: AsRef<[u8]>>(groups: &[G]) -> usize {
+    groups
+        .iter()
+        .map(|group| placements(group.as_ref().len()))
+        .sum()
+}
+
+/// Shows the partition and size rather than the tables.
+impl fmt::Debug for PatternDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternDatabase")
+            .field("groups", &self.groups)
+            .field("bytes", &self.bytes())
+            .finish()
+    }
+}
+
+impl Puzzle {
+    /// The installed pattern database's estimate for 4x4 boards, and the
+    /// Manhattan distance for other sizes or when none is installed. Never
+    /// overestimates.
+    pub fn pattern_database_distance(&self) -> usize {
+        installed()
+            .and_then(|database| database.estimate(self))
+            .unwrap_or_else(|| self.manhattan_distance())
+    }
+
+    /// Optimal IDA* guided by `pattern_database_distance`, which makes hard
+    /// 4x4 boards practical to solve optimally. Without an installed
+    /// database this is `solve_optimal`.
+    pub fn solve_pattern_database(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_pattern_database_with_progress(|_| true)
+    }
+
+    pub fn solve_pattern_database_with_progress<F>(
+        &self,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(
+            IdaStarSearch::with_heuristic(self, Puzzle::pattern_database_distance)?,
+            callback,
+        )
+    }
+}
+
+/// Ways to place `tiles` distinct tiles on the board.
+fn placements(tiles: usize) -> usize {
+    (CELLS - tiles + 1..=CELLS).product()
+}
+
+/// Dense index of the cells holding a group's tiles, in group order.
+fn rank(positions: &[u8]) -> usize {
+    let mut index = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        let before = positions[..i].iter().filter(|&&p| p < position).count();
+        index = index * (CELLS - i) + position as usize - before;
+    }
+    index
+}
+
+/// A group's cells and the blank cell, four bits each.
+fn pack(positions: &[u8], blank: usize) -> u64 {
+    positions.iter().fold(blank as u64, |packed, &position| {
+        packed << 4 | position as u64
+    })
+}
+
+fn unpack(mut packed: u64, positions: &mut [u8]) -> usize {
+    for position in positions.iter_mut().rev() {
+        *position = (packed & 0xf) as u8;
+        packed >>= 4;
+    }
+    packed as usize
+}
+
+/// Distances for one group, searched over placements of its tiles together
+/// with the blank, since the blank decides which tiles can move. A move of
+/// one of the group's tiles costs 1 and any other move is free, so the
+/// search keeps a double-ended queue, putting free moves at the front.
+fn build_table(group: &[u8]) -> Vec<u8> {
+    let tiles = group.len();
+    let mut distances = vec![UNSEEN; placements(tiles) * CELLS];
+    let goal: Vec<u8> = group.iter().map(|&tile| tile - 1).collect();
+    distances[rank(&goal) * CELLS + CELLS - 1] = 0;
+    let mut queue = VecDeque::from([pack(&goal, CELLS - 1)]);
+
+    let mut positions = [0; CELLS];
+    let positions = &mut positions[..tiles];
+    while let Some(packed) = queue.pop_front() {
+        let blank = unpack(packed, positions);
+        let depth = distances[rank(positions) * CELLS + blank];
+        let (row, col) = (blank / SIZE, blank % SIZE);
+        let neighbours = [
+            (row > 0).then(|| blank - SIZE),
+            (row + 1 < SIZE).then(|| blank + SIZE),
+            (col > 0).then(|| blank - 1),
+            (col + 1 < SIZE).then(|| blank + 1),
+        ];
+
+        for neighbour in neighbours.into_iter().flatten() {
+            let mut next = [0; CELLS];
+            let next = &mut next[..tiles];
+            next.copy_from_slice(positions);
+            let moved = next.iter().position(|&cell| cell as usize == neighbour);
+            if let Some(tile) = moved {
+                next[tile] = blank as u8;
+            }
+            let cost = moved.is_some() as u8;
+
+            let index = rank(next) * CELLS + neighbour;
+            if depth + cost < distances[index] {
+                distances[index] = depth + cost;
+                let packed = pack(next, neighbour);
+                if moved.is_some() {
+                    queue.push_back(packed);
+                } else {
+                    queue.push_front(packed);
+                }
+            }
+        }
+    }
+
+    distances
+        .chunks(CELLS)
+        .map(|by_blank| *by_blank.iter().min().unwrap())
+        .collect()
+}
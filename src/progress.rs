@@ -0,0 +1,134 @@
+use crate::clock::Instant;
+use std::time::Duration;
+
+/// How many expanded nodes pass between two progress reports.
+pub(crate) const REPORT_INTERVAL: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// Current IDA* cost bound.
+    pub bound: usize,
+    /// Nodes expanded since the search started.
+    pub nodes: u64,
+    /// Nodes expanded during the current bound iteration.
+    pub bound_nodes: u64,
+    pub elapsed: Duration,
+    pub nodes_per_second: f64,
+    /// Rough time left until the current bound iteration is exhausted,
+    /// assuming node counts keep growing by the same ratio as between the
+    /// previous two iterations. `None` until two iterations have completed.
+    pub eta: Option<Duration>,
+}
+
+/// Counts of expanded nodes by depth and by heuristic value, summed over all
+/// iterations. Only collected when a caller asks for search statistics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SearchProfile {
+    pub(crate) depth_nodes: Vec<u64>,
+    pub(crate) heuristic_nodes: Vec<u64>,
+}
+
+/// Node counts and timings of a running search, from which `Progress`
+/// reports are built.
+pub(crate) struct ProgressTracker {
+    start: Instant,
+    nodes: u64,
+    bound: usize,
+    bound_nodes: u64,
+    completed: Vec<u64>,
+    profile: Option<SearchProfile>,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            nodes: 0,
+            bound: 0,
+            bound_nodes: 0,
+            completed: Vec::new(),
+            profile: None,
+        }
+    }
+
+    /// Also records a `SearchProfile` of the expanded nodes.
+    pub(crate) fn with_profile(mut self) -> Self {
+        self.profile = Some(SearchProfile::default());
+        self
+    }
+
+    pub(crate) fn start_iteration(&mut self, bound: usize) {
+        self.bound = bound;
+        self.bound_nodes = 0;
+    }
+
+    pub(crate) fn finish_iteration(&mut self) {
+        self.completed.push(self.bound_nodes);
+    }
+
+    pub(crate) fn node(&mut self) {
+        self.nodes += 1;
+        self.bound_nodes += 1;
+    }
+
+    /// Records a node at `depth` with heuristic value `estimate` that is
+    /// within the current bound and will be expanded.
+    pub(crate) fn expanded(&mut self, depth: usize, estimate: usize) {
+        if let Some(profile) = &mut self.profile {
+            for (counts, index) in [
+                (&mut profile.depth_nodes, depth),
+                (&mut profile.heuristic_nodes, estimate),
+            ] {
+                if counts.len() <= index {
+                    counts.resize(index + 1, 0);
+                }
+                counts[index] += 1;
+            }
+        }
+    }
+
+    pub(crate) fn profile(&self) -> Option<&SearchProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Nodes visited in each completed iteration.
+    pub(crate) fn iteration_nodes(&self) -> &[u64] {
+        &self.completed
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub(crate) fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub(crate) fn progress(&self) -> Progress {
+        let elapsed = self.start.elapsed();
+        let nodes_per_second = if elapsed.as_secs_f64() > 0.0 {
+            self.nodes as f64 / elapsed.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        let eta = match self.completed.as_slice() {
+            [.., prev, last] if *prev > 0 && nodes_per_second > 0.0 => {
+                let growth = *last as f64 / *prev as f64;
+                let expected = *last as f64 * growth;
+                let remaining = (expected - self.bound_nodes as f64).max(0.0);
+                Some(Duration::from_secs_f64(remaining / nodes_per_second))
+            }
+            _ => None,
+        };
+
+        Progress {
+            bound: self.bound,
+            nodes: self.nodes,
+            bound_nodes: self.bound_nodes,
+            elapsed,
+            nodes_per_second,
+            eta,
+        }
+    }
+}
@@ -1,7 +1,17 @@
-use rand::{seq::SliceRandom, thread_rng};
+use crate::goal::{goal_positions, goal_positions_of};
+use crate::heuristic::Manhattan;
+use crate::mapping::TileMapping;
+use crate::progress::Progress;
+use crate::search::{self, IdaStarSearch, Search, SearchStatus};
+use crate::solve_cache;
+use crate::stats::SolveStats;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Move {
     Up,
     Left,
@@ -10,6 +20,8 @@ pub enum Move {
 }
 
 impl Move {
+    pub const ALL: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+
     pub fn as_offset(&self) -> (isize, isize) {
         match self {
             Move::Up => (1, 0),
@@ -41,23 +53,197 @@ impl fmt::Display for Move {
     }
 }
 
-#[derive(Clone)]
+impl FromStr for Move {
+    type Err = &'static str;
+
+    /// Accepts the names printed by `Display` in any case, or their first letter.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "up" | "u" => Ok(Move::Up),
+            "left" | "l" => Ok(Move::Left),
+            "down" | "d" => Ok(Move::Down),
+            "right" | "r" => Ok(Move::Right),
+            _ => Err("Invalid move"),
+        }
+    }
+}
+
+/// A set of moves, stored as one bit per direction so it is cheap to copy,
+/// test and combine. Iterating it yields its moves in `Move::ALL` order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MoveSet(u8);
+
+impl MoveSet {
+    pub const EMPTY: MoveSet = MoveSet(0);
+    pub const ALL: MoveSet = MoveSet(0b1111);
+
+    pub const fn only(movement: Move) -> Self {
+        MoveSet(Self::bit(movement))
+    }
+
+    pub const fn contains(self, movement: Move) -> bool {
+        self.0 & Self::bit(movement) != 0
+    }
+
+    pub const fn with(self, movement: Move) -> Self {
+        MoveSet(self.0 | Self::bit(movement))
+    }
+
+    pub const fn without(self, movement: Move) -> Self {
+        MoveSet(self.0 & !Self::bit(movement))
+    }
+
+    pub const fn union(self, other: MoveSet) -> Self {
+        MoveSet(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: MoveSet) -> Self {
+        MoveSet(self.0 & other.0)
+    }
+
+    /// The moves not in this set.
+    pub const fn complement(self) -> Self {
+        MoveSet(!self.0 & Self::ALL.0)
+    }
+
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The bit for a move, in `Move::ALL` order.
+    const fn bit(movement: Move) -> u8 {
+        match movement {
+            Move::Up => 1,
+            Move::Down => 2,
+            Move::Left => 4,
+            Move::Right => 8,
+        }
+    }
+}
+
+impl Iterator for MoveSet {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        let movement = Move::ALL.get(self.0.trailing_zeros() as usize).copied()?;
+        self.0 &= self.0 - 1;
+        Some(movement)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = MoveSet::len(*self);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for MoveSet {}
+
+impl From<Move> for MoveSet {
+    fn from(movement: Move) -> Self {
+        Self::only(movement)
+    }
+}
+
+impl FromIterator<Move> for MoveSet {
+    fn from_iter<I: IntoIterator<Item = Move>>(moves: I) -> Self {
+        moves.into_iter().fold(Self::EMPTY, Self::with)
+    }
+}
+
+/// Why a move could not be made.
+///
+/// There is no variant for a tile locked in place, since no board in the
+/// crate locks cells yet. One belongs here once region locking exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The empty space is on the edge of the board, so there is no tile to
+    /// slide in that direction.
+    OutOfBounds(Move),
+    /// A wall is on that side of the empty space, on a board from
+    /// `walls::WalledBoard`.
+    Wall(Move),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::OutOfBounds(movement) => {
+                write!(
+                    f,
+                    "Cannot move {}: no tile on that side of the empty space",
+                    movement
+                )
+            }
+            MoveError::Wall(movement) => {
+                write!(
+                    f,
+                    "Cannot move {}: a wall is on that side of the empty space",
+                    movement
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// A move sequence that could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApplyError {
+    /// Position of the first illegal move in the sequence.
+    pub index: usize,
+    pub error: MoveError,
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move {}: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for ApplyError {}
+
+/// Largest supported board size, and the most rows or columns of a
+/// rectangular board. Every tile value of a board this size fits in 16
+/// bits, and the board takes well under a megabyte.
+pub const MAX_SIZE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Puzzle {
-    size: usize,
+    rows: usize,
+    cols: usize,
     board: Vec<Vec<u32>>,
     x_pos: usize,
     y_pos: usize,
+    /// Goal row and column of every tile value, or `None` for the standard
+    /// goal with the tiles in order and the empty space bottom-right.
+    goal: Option<Arc<[(usize, usize)]>>,
 }
 
 impl Puzzle {
-    pub fn new(size: usize) -> Self {
+    /// The solved board of the given size. A 1x1 board holds only the empty
+    /// space and is always solved. Sizes of 0 or above `MAX_SIZE` are rejected.
+    pub fn new(size: usize) -> Result<Self, &'static str> {
+        Self::new_rectangular(size, size)
+    }
+
+    /// The solved board with `rows` rows and `cols` columns: the tiles in
+    /// order row by row, with the empty space bottom-right.
+    pub fn new_rectangular(rows: usize, cols: usize) -> Result<Self, &'static str> {
+        Self::check_size(rows)?;
+        Self::check_size(cols)?;
+
         let mut board = Vec::new();
         let mut value = 1;
 
-        for i in 0..size {
+        for i in 0..rows {
             let mut row = Vec::new();
-            for j in 0..size {
-                if i == size - 1 && j == size - 1 {
+            for j in 0..cols {
+                if i == rows - 1 && j == cols - 1 {
                     row.push(0); // The empty space is represented by 0
                 } else {
                     row.push(value);
@@ -67,38 +253,215 @@ impl Puzzle {
             board.push(row);
         }
 
-        Self {
-            size,
+        Ok(Self {
+            rows,
+            cols,
             board,
-            x_pos: size - 1,
-            y_pos: size - 1,
+            x_pos: rows - 1,
+            y_pos: cols - 1,
+            goal: None,
+        })
+    }
+
+    fn check_size(size: usize) -> Result<(), &'static str> {
+        match size {
+            0 => Err("Board size must be positive"),
+            size if size > MAX_SIZE => Err("Board size exceeds the maximum"),
+            _ => Ok(()),
         }
     }
 
-    pub fn apply_move(&mut self, movement: Move) -> bool {
+    /// Builds a puzzle from its tiles listed row by row, with 0 for the empty space.
+    pub fn from_tiles(size: usize, tiles: &[u32]) -> Result<Self, &'static str> {
+        Self::from_tiles_rectangular(size, size, tiles)
+    }
+
+    /// Like `from_tiles`, for a board with `rows` rows and `cols` columns.
+    pub fn from_tiles_rectangular(
+        rows: usize,
+        cols: usize,
+        tiles: &[u32],
+    ) -> Result<Self, &'static str> {
+        Self::check_size(rows)?;
+        Self::check_size(cols)?;
+        if tiles.len() != rows * cols {
+            return Err("Wrong number of tiles for board size");
+        }
+
+        let mut seen = vec![false; tiles.len()];
+        for &value in tiles {
+            match seen.get_mut(value as usize) {
+                Some(slot) if !*slot => *slot = true,
+                Some(_) => return Err("Duplicate tile"),
+                None => return Err("Tile value out of range"),
+            }
+        }
+
+        let board: Vec<Vec<u32>> = tiles.chunks(cols).map(|row| row.to_vec()).collect();
+        let blank = tiles.iter().position(|&value| value == 0).unwrap();
+
+        Ok(Self {
+            rows,
+            cols,
+            board,
+            x_pos: blank / cols,
+            y_pos: blank % cols,
+            goal: None,
+        })
+    }
+
+    /// The side length of a square board. Rectangular boards have no single
+    /// size, so this is their longer side; code that handles them reads
+    /// `rows` and `cols` instead, and code that only handles square boards
+    /// checks `is_square` first.
+    pub fn size(&self) -> usize {
+        self.rows.max(self.cols)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// This board with `goal`'s arrangement as the one to solve towards,
+    /// such as the empty space top-left or the tiles in a spiral.
+    /// `is_solved`, the solvability check, the heuristics and the searches
+    /// built on them all follow it. Estimates tied to the standard goal,
+    /// like the pattern database and walking distance, fall back to the
+    /// Manhattan distance. Only the canonical text form (`{:#}`) and saved
+    /// failing cases (`repro::FailingCase`) record the goal.
+    pub fn with_goal(mut self, goal: &Puzzle) -> Result<Self, &'static str> {
+        if (goal.rows, goal.cols) != (self.rows, self.cols) {
+            return Err("Goal must have the same dimensions as the board");
+        }
+        let standard = Self::new_rectangular(self.rows, self.cols)?;
+        self.goal = (goal.board != standard.board).then(|| goal_positions_of(goal));
+        Ok(self)
+    }
+
+    /// Whether the board is solved towards the standard goal.
+    pub fn has_standard_goal(&self) -> bool {
+        self.goal.is_none()
+    }
+
+    /// The goal board: the solved arrangement, with the same dimensions and
+    /// goal as this one.
+    pub fn goal(&self) -> Self {
+        let mut goal =
+            Self::new_rectangular(self.rows, self.cols).expect("The dimensions were valid");
+        if let Some(table) = &self.goal {
+            for (value, &(row, col)) in table.iter().enumerate() {
+                goal.board[row][col] = value as u32;
+            }
+            (goal.x_pos, goal.y_pos) = table[0];
+            goal.goal = self.goal.clone();
+        }
+        goal
+    }
+
+    /// Goal row and column of every tile value, indexed by value.
+    pub(crate) fn goals(&self) -> Cow<'_, [(usize, usize)]> {
+        match &self.goal {
+            Some(table) => Cow::Borrowed(table),
+            None => goal_positions(self.rows, self.cols),
+        }
+    }
+
+    /// Row and column of the empty space.
+    pub fn blank_position(&self) -> (usize, usize) {
+        (self.x_pos, self.y_pos)
+    }
+
+    /// The tile at a cell, with 0 for the empty space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is off the board; see `get_tile`.
+    pub fn tile_at(&self, row: usize, col: usize) -> u32 {
+        self.board[row][col]
+    }
+
+    /// The tile at a cell, or `None` if the cell is off the board.
+    pub fn get_tile(&self, row: usize, col: usize) -> Option<u32> {
+        self.board.get(row)?.get(col).copied()
+    }
+
+    /// All tiles row by row, with 0 for the empty space.
+    pub fn tiles(&self) -> Vec<u32> {
+        self.board
+            .iter()
+            .flat_map(|row| row.iter().cloned())
+            .collect()
+    }
+
+    /// Row and column of the tile that `movement` would slide into the empty
+    /// space, or `None` if the move is not legal.
+    pub fn moved_tile_position(&self, movement: Move) -> Option<(usize, usize)> {
         let (dx, dy) = movement.as_offset();
+        let row = self.x_pos.checked_add_signed(dx)?;
+        let col = self.y_pos.checked_add_signed(dy)?;
+        (row < self.rows && col < self.cols).then_some((row, col))
+    }
 
-        let new_x = self.x_pos as isize + dx;
-        let new_y = self.y_pos as isize + dy;
+    /// The moves that are legal on this board, iterated in `Move::ALL`
+    /// order. Never allocates.
+    pub fn legal_moves(&self) -> MoveSet {
+        Move::ALL
+            .into_iter()
+            .filter(|&movement| self.moved_tile_position(movement).is_some())
+            .collect()
+    }
 
-        if new_x >= 0 && new_x < self.size as isize && new_y >= 0 && new_y < self.size as isize {
-            let new_x = new_x as usize;
-            let new_y = new_y as usize;
+    /// Slides a tile into the empty space, returning whether the move was
+    /// legal. Never allocates, so it is safe to call every frame.
+    pub fn apply_move(&mut self, movement: Move) -> bool {
+        self.try_apply(movement).is_ok()
+    }
 
-            self.board[self.x_pos][self.y_pos] = self.board[new_x][new_y];
-            self.board[new_x][new_y] = 0;
+    /// Like `apply_move`, but says why an illegal move was rejected. Never
+    /// allocates.
+    pub fn try_apply(&mut self, movement: Move) -> Result<(), MoveError> {
+        let (new_x, new_y) = self
+            .moved_tile_position(movement)
+            .ok_or(MoveError::OutOfBounds(movement))?;
 
-            self.x_pos = new_x;
-            self.y_pos = new_y;
-            true
-        } else {
-            false
+        self.board[self.x_pos][self.y_pos] = self.board[new_x][new_y];
+        self.board[new_x][new_y] = 0;
+
+        self.x_pos = new_x;
+        self.y_pos = new_y;
+        Ok(())
+    }
+
+    /// Applies all of `moves` or none of them: if one is illegal, the moves
+    /// before it are undone and its position is reported. Never allocates.
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), ApplyError> {
+        for (index, &movement) in moves.iter().enumerate() {
+            if let Err(error) = self.try_apply(movement) {
+                for &done in moves[..index].iter().rev() {
+                    self.try_apply(done.opposite())
+                        .expect("undoing a legal move is legal");
+                }
+                return Err(ApplyError { index, error });
+            }
         }
+        Ok(())
     }
 
     pub fn shuffle(&mut self) {
-        let mut rng = thread_rng();
+        self.shuffle_with(&mut thread_rng());
+    }
 
+    /// Like `shuffle`, but draws from `rng` so scrambles can be reproduced
+    /// from a seed.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
         // Flatten the board
         let mut flattened: Vec<u32> = self
             .board
@@ -108,12 +471,21 @@ impl Puzzle {
             .collect();
 
         loop {
-            flattened.shuffle(&mut rng);
+            if self.rows == 1 || self.cols == 1 {
+                // Tiles in a single line keep their order, so only the
+                // empty space can be placed at random
+                let goals = self.goals();
+                flattened.retain(|&value| value != 0);
+                flattened.sort_by_key(|&value| goals[value as usize]);
+                flattened.insert(rng.gen_range(0..=flattened.len()), 0);
+            } else {
+                flattened.shuffle(rng);
+            }
 
             // Reconstruct the board
-            for i in 0..self.size {
-                for j in 0..self.size {
-                    self.board[i][j] = flattened[i * self.size + j];
+            for i in 0..self.rows {
+                for j in 0..self.cols {
+                    self.board[i][j] = flattened[i * self.cols + j];
                     if self.board[i][j] == 0 {
                         self.x_pos = i;
                         self.y_pos = j;
@@ -121,13 +493,65 @@ impl Puzzle {
                 }
             }
 
-            if Self::is_solvable(&flattened, self.size, self.x_pos) {
+            if self.is_current_state_solvable() {
                 break;
             }
         }
     }
 
+    /// Applies `steps` random legal moves, never immediately undoing the
+    /// previous one unless nothing else is legal, and returns the moves
+    /// made. Stops early on a board with no legal moves at all.
+    pub fn random_walk(&mut self, steps: usize) -> Vec<Move> {
+        self.random_walk_with(steps, &mut thread_rng())
+    }
+
+    /// Like `random_walk`, but draws from `rng` so walks can be reproduced
+    /// from a seed.
+    pub fn random_walk_with<R: Rng + ?Sized>(&mut self, steps: usize, rng: &mut R) -> Vec<Move> {
+        let mut moves: Vec<Move> = Vec::new();
+
+        while moves.len() < steps {
+            let undo = moves.last().map(Move::opposite);
+            if self.legal_moves().all(|movement| Some(movement) == undo) {
+                // A board one cell wide can only go back the way it came
+                let Some(only) = self.legal_moves().next() else {
+                    break;
+                };
+                self.apply_move(only);
+                moves.push(only);
+                continue;
+            }
+            let dir = *Move::ALL.choose(rng).unwrap();
+            if moves.last().is_some_and(|last| dir == last.opposite()) {
+                continue;
+            }
+            if self.apply_move(dir) {
+                moves.push(dir);
+            }
+        }
+
+        moves
+    }
+
     pub fn is_current_state_solvable(&self) -> bool {
+        if self.rows == 1 || self.cols == 1 {
+            // Tiles in a single line can never pass each other, so they must
+            // already be in their goal order
+            let goals = self.goals();
+            let order: Vec<(usize, usize)> = self
+                .board
+                .iter()
+                .flatten()
+                .filter(|&&value| value != 0)
+                .map(|&value| goals[value as usize])
+                .collect();
+            return order.windows(2).all(|pair| pair[0] < pair[1]);
+        }
+        if let Some(goals) = &self.goal {
+            return self.matches_goal_parity(goals);
+        }
+
         // Convert the 2D board to a 1D array for easier processing
         let flat_board: Vec<u32> = self
             .board
@@ -135,22 +559,58 @@ impl Puzzle {
             .flat_map(|row| row.iter().cloned())
             .collect();
 
-        Self::is_solvable(&flat_board, self.size, self.x_pos)
+        Self::is_solvable(&flat_board, self.rows, self.cols, self.x_pos)
+    }
+
+    /// Every move swaps the empty space with a tile, so a board can reach
+    /// its goal only if the parity of the permutation taking each tile to
+    /// its goal cell matches that of the empty space's distance to its own.
+    fn matches_goal_parity(&self, goals: &[(usize, usize)]) -> bool {
+        let cells = self.rows * self.cols;
+        let mut visited = vec![false; cells];
+        let mut cycles = 0;
+        for start in 0..cells {
+            let mut cell = start;
+            if visited[cell] {
+                continue;
+            }
+            cycles += 1;
+            while !visited[cell] {
+                visited[cell] = true;
+                let (row, col) = goals[self.board[cell / self.cols][cell % self.cols] as usize];
+                cell = row * self.cols + col;
+            }
+        }
+        let blank_distance = manhattan(self.blank_position(), goals[0]);
+        (cells - cycles) % 2 == blank_distance % 2
     }
 
-    fn is_solvable(flattened: &[u32], size: usize, empty_row: usize) -> bool {
+    /// Number of pairs of tiles that appear in the wrong order when the board
+    /// is read row by row, ignoring the empty space.
+    pub fn inversions(&self) -> usize {
+        let flat_board: Vec<u32> = self
+            .board
+            .iter()
+            .flat_map(|row| row.iter().cloned())
+            .collect();
+
+        Self::count_inversions(&flat_board)
+    }
+
+    fn is_solvable(flattened: &[u32], rows: usize, cols: usize, empty_row: usize) -> bool {
         let inversions = Self::count_inversions(flattened);
 
-        if size % 2 == 1 {
-            // Odd-sized puzzle: solvable if inversions count is even
-            inversions % 2 == 0
+        if cols % 2 == 1 {
+            // Odd width: solvable if inversions count is even
+            inversions.is_multiple_of(2)
         } else {
-            // Even-sized puzzle: solvable if (inversions + empty row index) is odd
-            (inversions + empty_row) % 2 == 1
+            // Even width: each vertical move flips the inversion parity, so
+            // it must match the empty space's distance from the bottom row
+            (inversions + rows - 1 - empty_row).is_multiple_of(2)
         }
     }
 
-    fn count_inversions(flattened: &[u32]) -> usize {
+    pub(crate) fn count_inversions(flattened: &[u32]) -> usize {
         flattened
             .iter()
             .enumerate()
@@ -165,11 +625,17 @@ impl Puzzle {
     }
 
     pub fn is_solved(&self) -> bool {
+        if let Some(goals) = &self.goal {
+            return goals[1..]
+                .iter()
+                .zip(1..)
+                .all(|(&(row, col), value)| self.board[row][col] == value);
+        }
         let mut expected = 1;
 
-        for i in 0..self.size {
-            for j in 0..self.size {
-                if i == self.size - 1 && j == self.size - 1 {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if i == self.rows - 1 && j == self.cols - 1 {
                     if self.board[i][j] != 0 {
                         return false;
                     }
@@ -186,173 +652,434 @@ impl Puzzle {
     }
 
     pub fn solve(&self) -> Result<Vec<Move>, &'static str> {
-        let mut path = Vec::new();
-        let mut bound = self.heuristic();
-        let mut iterations = 0;
-        const MAX_ITERATIONS: usize = 1000000;
-    
-        if !self.is_current_state_solvable() {
-            return Err("Puzzle is not solvable");
-        }
-    
-        loop {
-            iterations += 1;
-            if iterations > MAX_ITERATIONS {
-                return Err("Maximum iterations exceeded");
-            }
-    
-            let result = self.ida_star_search(0, bound, &mut path, None);
-            match result {
-                Ok(solution) => return Ok(solution),
-                Err(new_bound) => {
-                    if new_bound == usize::MAX {
-                        return Err("No solution found");
-                    }
-                    if new_bound <= bound {
-                        return Err("No progress possible");
-                    }
-                    bound = new_bound;
-                }
-            }
-        }
+        self.solve_with_progress(|_| true)
     }
 
-    fn ida_star_search(
-        &self,
-        g: usize,
-        bound: usize,
-        path: &mut Vec<Move>,
-        last_move: Option<Move>,
-    ) -> Result<Vec<Move>, usize> {
-        let f = g + self.heuristic();
-        if f > bound {
-            return Err(f);
-        }
-        if self.is_solved() {
-            return Ok(path.clone());
+    /// Like `solve`, but periodically reports search progress (including a
+    /// rough ETA for the current bound) to `callback`. Returning `false` from
+    /// the callback cancels the search. Answers from `solve_cache` without
+    /// searching while it is enabled and holds the board.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if let Some(moves) = solve_cache::get(self) {
+            return Ok(moves);
         }
+        let moves = search::run(IdaStarSearch::new(self)?, callback)?;
+        solve_cache::insert(self, &moves);
+        Ok(moves)
+    }
 
-        let mut min = usize::MAX;
-        let moves = [Move::Up, Move::Down, Move::Left, Move::Right];
+    /// Like `solve`, but searches with the Manhattan distance alone and never
+    /// answers from `solve_cache`. Both estimates are admissible, so the
+    /// solution is shortest either way; this one is slower, but shares no
+    /// code with the linear-conflict estimate, so it can check `solve`.
+    pub fn solve_optimal(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_optimal_with_progress(|_| true)
+    }
 
-        for &dir in &moves {
-            if let Some(last) = last_move {
-                if dir == last.opposite() {
-                    continue;
-                }
-            }
+    pub fn solve_optimal_with_progress<F>(&self, callback: F) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(IdaStarSearch::with_heuristic(self, Manhattan)?, callback)
+    }
 
-            if let Some(new_puzzle) = self.try_move(dir) {
-                // Add cycle detection
-                let is_cycle = path.windows(2).any(|window| {
-                    if let [prev, next] = window {
-                        *prev == dir.opposite() && *next == dir
-                    } else {
-                        false
-                    }
-                });
+    /// A shortest sequence of moves taking this board to `target`, any
+    /// arrangement of the same dimensions, rather than to the goal. Editors
+    /// and tutorials can use it to walk between two chosen boards.
+    pub fn solve_to(&self, target: &Puzzle) -> Result<Vec<Move>, &'static str> {
+        let board = self.clone().with_goal(target)?;
+        if !board.is_current_state_solvable() {
+            return Err("Target is not reachable from this board");
+        }
+        board.solve()
+    }
 
-                if is_cycle {
-                    continue;
-                }
+    /// Like `solve`, but skips states already searched at the same or a
+    /// lower cost in the current iteration, using up to `table_bytes` of
+    /// memory to remember them. Expands fewer nodes on boards with many
+    /// transpositions, at the cost of a hash lookup per node.
+    pub fn solve_with_table(&self, table_bytes: usize) -> Result<Vec<Move>, &'static str> {
+        search::run(
+            IdaStarSearch::new(self)?.with_transposition_table(table_bytes),
+            |_| true,
+        )
+    }
 
-                path.push(dir);
-                
-                // Add depth limit to prevent stack overflow
-                if path.len() > self.size * self.size * 4 {
-                    path.pop();
-                    continue;
-                }
+    /// Like `solve_optimal`, but only makes the moves `filter` allows from
+    /// each state, for callers that rule out directions, such as a front end
+    /// with a disabled arrow. The solution is the shortest that keeps to the
+    /// filter.
+    pub fn solve_with_move_filter(
+        &self,
+        filter: impl Fn(&Puzzle) -> MoveSet + 'static,
+    ) -> Result<Vec<Move>, &'static str> {
+        search::run(
+            IdaStarSearch::with_heuristic(self, Manhattan)?.with_move_filter(filter),
+            |_| true,
+        )
+    }
 
-                match new_puzzle.ida_star_search(g + 1, bound, path, Some(dir)) {
-                    Ok(solution) => return Ok(solution),
-                    Err(t) => {
-                        if t < min {
-                            min = t;
-                        }
-                    }
-                }
-                path.pop();
+    /// Like `solve`, but also profiles the search: node counts per iteration
+    /// and per depth, the spread of heuristic values over expanded nodes, and
+    /// the effective branching factor.
+    pub fn solve_with_stats(&self) -> (Result<Vec<Move>, &'static str>, SolveStats) {
+        let mut search = match IdaStarSearch::new(self) {
+            Ok(search) => search.with_profile(),
+            Err(err) => return (Err(err), SolveStats::default()),
+        };
+        let result = loop {
+            match search.step(u64::MAX) {
+                SearchStatus::Running => continue,
+                SearchStatus::Solved(moves) => break Ok(moves),
+                SearchStatus::Failed(err) => break Err(err),
             }
-        }
+        };
+        let length = result.as_ref().ok().map(Vec::len);
+        (result, SolveStats::from_tracker(search.tracker(), length))
+    }
 
-        Err(min)
+    pub fn heuristic(&self) -> usize {
+        self.manhattan_distance() + 2 * self.linear_conflicts()
     }
 
-    fn try_move(&self, dir: Move) -> Option<Self> {
-        let mut new_puzzle = self.clone(); // Clone the current puzzle
-        if new_puzzle.apply_move(dir) {
-            Some(new_puzzle)
-        } else {
-            None
+    pub fn manhattan_distance(&self) -> usize {
+        self.manhattan_distance_to(&self.goals())
+    }
+
+    /// Tiles not on their goal cell. Never overestimates, since each needs
+    /// at least one move, but is far weaker than the Manhattan distance;
+    /// mostly useful for teaching and for comparing heuristics.
+    pub fn misplaced_tiles(&self) -> usize {
+        let goals = self.goals();
+        let mut misplaced = 0;
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                let value = self.board[i][j];
+                if value != 0 && goals[value as usize] != (i, j) {
+                    misplaced += 1;
+                }
+            }
         }
+        misplaced
     }
 
-    fn heuristic(&self) -> usize {
-        self.manhattan_distance() + 2 * self.linear_conflicts()
+    /// Gaschnig's estimate: the fewest swaps needed to sort the board if the
+    /// empty space could swap with any tile, not just its neighbours. Never
+    /// overestimates, and is at least `misplaced_tiles`.
+    pub fn gaschnig_distance(&self) -> usize {
+        let goals = self.goals();
+        let cells = self.rows * self.cols;
+        // Goal cell of the tile in each cell, with the empty space as a tile
+        let target: Vec<usize> = (0..cells)
+            .map(|cell| {
+                let (row, col) = goals[self.board[cell / self.cols][cell % self.cols] as usize];
+                row * self.cols + col
+            })
+            .collect();
+        let blank = self.x_pos * self.cols + self.y_pos;
+
+        // Each cycle of misplaced tiles takes one swap per tile, plus two to
+        // bring the empty space in and out unless it is already part of it
+        let mut swaps = 0;
+        let mut visited = vec![false; cells];
+        for start in 0..cells {
+            if visited[start] || target[start] == start {
+                continue;
+            }
+            let mut length = 0;
+            let mut has_blank = false;
+            let mut cell = start;
+            while !visited[cell] {
+                visited[cell] = true;
+                has_blank |= cell == blank;
+                length += 1;
+                cell = target[cell];
+            }
+            swaps += if has_blank { length - 1 } else { length + 1 };
+        }
+        swaps
     }
 
-    fn manhattan_distance(&self) -> usize {
+    /// Manhattan distance to the goal described by `goals`, a table of goal
+    /// positions indexed by tile value.
+    pub(crate) fn manhattan_distance_to(&self, goals: &[(usize, usize)]) -> usize {
         let mut distance = 0;
-        for i in 0..self.size {
-            for j in 0..self.size {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
                 let value = self.board[i][j];
                 if value != 0 {
-                    let target_x = (value - 1) / self.size as u32;
-                    let target_y = (value - 1) % self.size as u32;
-                    distance += (i as isize - target_x as isize).unsigned_abs();
-                    distance += (j as isize - target_y as isize).unsigned_abs();
+                    let (target_x, target_y) = goals[value as usize];
+                    distance += i.abs_diff(target_x);
+                    distance += j.abs_diff(target_y);
                 }
             }
         }
         distance
     }
 
-    fn linear_conflicts(&self) -> usize {
-        let mut conflicts = 0;
+    pub fn linear_conflicts(&self) -> usize {
+        self.linear_conflicts_to(&self.goals())
+    }
+
+    /// Linear conflicts with respect to the goal described by `goals`. Tiles
+    /// are ordered by their goal column within a row, and by their goal row
+    /// within a column.
+    pub(crate) fn linear_conflicts_to(&self, goals: &[(usize, usize)]) -> usize {
+        let rows: usize = (0..self.rows)
+            .map(|row| self.row_conflicts(row, goals))
+            .sum();
+        let cols: usize = (0..self.cols)
+            .map(|col| self.column_conflicts(col, goals))
+            .sum();
+        rows + cols
+    }
+
+    /// `heuristic` plus `corner_conflicts`. Never overestimates, and is
+    /// often two or four moves stronger on boards whose edges are mostly in
+    /// place.
+    pub fn corner_tiles_distance(&self) -> usize {
+        let goals = self.goals();
+        self.manhattan_distance_to(&goals)
+            + 2 * self.linear_conflicts_to(&goals)
+            + self.corner_conflicts_to(&goals)
+    }
+
+    /// `corner_tiles_distance` plus `last_moves`, all of Korf and Taylor's
+    /// enhancements of the Manhattan distance together. Never overestimates.
+    /// Not to be added to a pattern database estimate, which already counts
+    /// every move of the tiles it covers.
+    pub fn last_moves_distance(&self) -> usize {
+        let goals = self.goals();
+        self.manhattan_distance_to(&goals)
+            + 2 * self.linear_conflicts_to(&goals)
+            + self.corner_conflicts_to(&goals)
+            + self.last_moves_to(&goals)
+    }
 
-        // Row conflicts
-        for row in 0..self.size {
-            let mut max_seen = 0;
-            for col in 0..self.size {
+    /// Korf and Taylor's last moves: two moves beyond the Manhattan distance
+    /// and linear conflicts if none of the tiles that could make the final
+    /// move is placed to make it on a shortest path.
+    pub fn last_moves(&self) -> usize {
+        self.last_moves_to(&self.goals())
+    }
+
+    /// The final move slides a tile from the blank's goal cell into a
+    /// neighbouring goal cell, so one of the tiles belonging next to the
+    /// blank's goal has to pass through it. A tile whose shortest paths all
+    /// avoid that cell needs two more moves to pass it, so if every such
+    /// tile does, two moves are added. The rule is skipped if one of them is
+    /// caught in a linear conflict or already at home, as its detour may
+    /// then be counted by `linear_conflicts_to` or `corner_conflicts_to`.
+    pub(crate) fn last_moves_to(&self, goals: &[(usize, usize)]) -> usize {
+        let blank_goal = goals[0];
+        let mut candidates = 0;
+        for row in 0..self.rows {
+            for col in 0..self.cols {
                 let value = self.board[row][col];
-                if value != 0 && (value - 1) / self.size as u32 == row as u32 {
-                    if value > max_seen {
-                        max_seen = value;
-                    } else {
-                        conflicts += 1;
-                    }
+                if value == 0 {
+                    continue;
+                }
+                let goal = goals[value as usize];
+                if manhattan(goal, blank_goal) != 1 {
+                    continue;
+                }
+                candidates += 1;
+                let through_blank_goal = manhattan((row, col), blank_goal) + 1;
+                if (row, col) == goal
+                    || through_blank_goal == manhattan((row, col), goal)
+                    || self.in_conflict(row, col, goals)
+                {
+                    return 0;
                 }
             }
         }
+        if candidates == 0 {
+            0
+        } else {
+            2
+        }
+    }
 
-        // Column conflicts
-        for col in 0..self.size {
-            let mut max_seen = 0;
-            for row in 0..self.size {
-                let value = self.board[row][col];
-                if value != 0 && (value - 1) % self.size as u32 == col as u32 {
-                    if value > max_seen {
-                        max_seen = value;
-                    } else {
-                        conflicts += 1;
-                    }
-                }
+    /// Korf and Taylor's corner tiles: moves beyond the Manhattan distance
+    /// and linear conflicts needed to bring each missing corner tile home.
+    pub fn corner_conflicts(&self) -> usize {
+        self.corner_conflicts_to(&self.goals())
+    }
+
+    /// A corner tile enters its corner from one of the two neighbouring
+    /// cells, so if both neighbours hold their own tiles one of them has to
+    /// step aside and come back, two moves the Manhattan distance does not
+    /// see. Neighbours caught in a linear conflict do not count, as those
+    /// moves may already be counted there, and neither does a corner sharing
+    /// a neighbour with one counted before it, as on a 3x3 board, where one
+    /// detour could serve both.
+    pub(crate) fn corner_conflicts_to(&self, goals: &[(usize, usize)]) -> usize {
+        if self.rows < 3 || self.cols < 3 {
+            return 0;
+        }
+        let (last_row, last_col) = (self.rows - 1, self.cols - 1);
+        let mut used = Vec::new();
+        let mut extra = 0;
+        for corner in [(0, 0), (0, last_col), (last_row, 0), (last_row, last_col)] {
+            let Some(value) = goals.iter().position(|&goal| goal == corner) else {
+                continue;
+            };
+            if value == 0 || self.board[corner.0][corner.1] == value as u32 {
+                continue;
+            }
+            let row_neighbour = (corner.0, if corner.1 == 0 { 1 } else { last_col - 1 });
+            let col_neighbour = (if corner.0 == 0 { 1 } else { last_row - 1 }, corner.1);
+            let neighbours = [row_neighbour, col_neighbour];
+            let settled = neighbours.iter().all(|&(row, col)| {
+                let tile = self.board[row][col];
+                tile != 0
+                    && goals[tile as usize] == (row, col)
+                    && !self.in_conflict(row, col, goals)
+            });
+            if settled && neighbours.iter().all(|cell| !used.contains(cell)) {
+                extra += 2;
+                used.extend(neighbours);
             }
         }
+        extra
+    }
+
+    /// Whether the tile at (`row`, `col`) is in a linear conflict with another
+    /// tile of its row or column.
+    fn in_conflict(&self, row: usize, col: usize, goals: &[(usize, usize)]) -> bool {
+        let (goal_row, goal_col) = goals[self.board[row][col] as usize];
+        let row_conflict = goal_row == row
+            && (0..self.cols).any(|other| {
+                let value = self.board[row][other];
+                let (other_row, other_col) = goals[value as usize];
+                value != 0
+                    && other_row == goal_row
+                    && other != col
+                    && (other < col) != (other_col < goal_col)
+            });
+        let col_conflict = goal_col == col
+            && (0..self.rows).any(|other| {
+                let value = self.board[other][col];
+                let (other_row, other_col) = goals[value as usize];
+                value != 0
+                    && other_col == goal_col
+                    && other != row
+                    && (other < row) != (other_row < goal_row)
+            });
+        row_conflict || col_conflict
+    }
+
+    /// Linear conflicts among the tiles of `row` that belong in it.
+    pub(crate) fn row_conflicts(&self, row: usize, goals: &[(usize, usize)]) -> usize {
+        misordered(self.board[row].iter().filter_map(|&value| {
+            let (goal_row, goal_col) = goals[value as usize];
+            (value != 0 && goal_row == row).then_some(goal_col)
+        }))
+    }
+
+    /// Linear conflicts among the tiles of `col` that belong in it.
+    pub(crate) fn column_conflicts(&self, col: usize, goals: &[(usize, usize)]) -> usize {
+        misordered(self.board.iter().filter_map(|line| {
+            let value = line[col];
+            let (goal_row, goal_col) = goals[value as usize];
+            (value != 0 && goal_col == col).then_some(goal_row)
+        }))
+    }
+}
+
+fn manhattan(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
 
-        conflicts
+/// The fewest tiles that must leave a line so that no two tiles left in it
+/// are in conflict, given the goal place of each of the line's tiles in
+/// order. Two tiles conflict when their goals are in the opposite order to
+/// the tiles, so the tiles that can stay are the longest increasing run of
+/// goals, found by patience sorting. Each tile that leaves costs two moves
+/// beyond its Manhattan distance, to step out of the line and back.
+pub(crate) fn misordered(goals: impl Iterator<Item = usize>) -> usize {
+    // Smallest last goal of an increasing run of each length
+    let mut tails = [0u16; MAX_SIZE];
+    let mut longest = 0;
+    let mut count = 0;
+    for goal in goals {
+        let goal = goal as u16;
+        let length = tails[..longest].partition_point(|&tail| tail < goal);
+        tails[length] = goal;
+        longest = longest.max(length + 1);
+        count += 1;
     }
+    count - longest
 }
 
 impl fmt::Display for Puzzle {
+    /// Draws the board as a grid. The alternate flag (`{:#}`) instead writes
+    /// the canonical single-line form `size:tile,tile,...`, or
+    /// `rowsxcols:tile,tile,...` for a rectangular board, row by row with 0
+    /// for the empty space, followed by `/` and the goal's tiles when the
+    /// goal is not the standard one. It suits logs and always parses back
+    /// into an equal puzzle.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let line = |puzzle: &Puzzle| {
+                let tiles: Vec<String> =
+                    puzzle.board.iter().flatten().map(u32::to_string).collect();
+                tiles.join(",")
+            };
+            if self.is_square() {
+                write!(f, "{}:{}", self.rows, line(self))?;
+            } else {
+                write!(f, "{}x{}:{}", self.rows, self.cols, line(self))?;
+            }
+            if !self.has_standard_goal() {
+                write!(f, "/{}", line(&self.goal()))?;
+            }
+            return Ok(());
+        }
+        // Wide enough for the largest tile, so big boards stay aligned
+        let width = (self.rows * self.cols - 1).to_string().len().max(2);
         for row in &self.board {
             for &val in row {
-                write!(f, "{:2} ", val)?;
+                write!(f, "{:width$} ", val)?;
             }
             writeln!(f)?;
         }
         Ok(())
     }
 }
+
+impl FromStr for Puzzle {
+    type Err = &'static str;
+
+    /// Parses tile numbers separated by whitespace or commas, row by row, with
+    /// 0 for the empty space. The board size is inferred as
+    /// `TileMapping::parse` does unless the text starts with a `size:` or
+    /// `rowsxcols:` header as in the canonical form, which may also end in
+    /// `/` and the goal's tiles, so both forms written by `Display` parse
+    /// back into the same puzzle.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((header, tiles)) = s.split_once(':') else {
+            return TileMapping::Standard.parse(s);
+        };
+        let parse = |text: &str| text.trim().parse().map_err(|_| "Invalid board size");
+        let (rows, cols) = match header.split_once('x') {
+            Some((rows, cols)) => (parse(rows)?, parse(cols)?),
+            None => (parse(header)?, parse(header)?),
+        };
+        let board = |text: &str| {
+            let tiles = text
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty())
+                .map(|token| TileMapping::Standard.to_internal(token, cols))
+                .collect::<Result<Vec<u32>, _>>()?;
+            Self::from_tiles_rectangular(rows, cols, &tiles)
+        };
+        match tiles.split_once('/') {
+            Some((tiles, goal)) => board(tiles)?.with_goal(&board(goal)?),
+            None => board(tiles),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::adaptive::Budget;
+use crate::catalog;
+use crate::config::Config;
+use crate::external::ExternalOptions;
+use crate::heuristic;
+use crate::pdb;
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use crate::search;
+use crate::solution::Solution;
+use crate::solver::{
+    AStar, Adaptive, Beam, Bidirectional, External, IdaStar, Solver, WeightedAStar,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub type SolveFn = Box<dyn Fn(&Puzzle) -> Result<Vec<Move>, &'static str>>;
+/// Builds a solver, reading any engine parameters from the config.
+pub type SolverFactory = Box<dyn Fn(&Config) -> SolveFn>;
+
+pub const DEFAULT_WEIGHT: f64 = 2.0;
+pub const DEFAULT_BEAM_WIDTH: usize = 1000;
+pub const DEFAULT_BUDGET_NODES: u64 = 50_000_000;
+
+/// Checks the `require_optimal` config key. When it is set, only engines
+/// described as optimal by `catalog::engines` may run. Their default
+/// linear-conflict estimate is admissible, so it needs no change; a chosen
+/// `heuristic` must be admissible too, see `chosen_heuristic`.
+pub fn check_optimal(engine: &str, config: &Config) -> Result<(), &'static str> {
+    if !config.parse_or("require_optimal", false) {
+        return Ok(());
+    }
+    if catalog::engines()
+        .iter()
+        .any(|info| info.name == engine && info.optimal)
+    {
+        Ok(())
+    } else {
+        Err("Engine does not guarantee optimal solutions")
+    }
+}
+
+/// Built-in engines that report progress and can be stopped part way through.
+pub const INTERRUPTIBLE_ENGINES: [&str; 5] = [
+    "ida-star",
+    "a-star",
+    "bidirectional",
+    "weighted-a-star",
+    "beam",
+];
+
+/// Runs one of `INTERRUPTIBLE_ENGINES`, reading its parameters from `config`
+/// like the registered factories do. Returning `false` from `callback`
+/// cancels the search.
+pub fn solve_interruptible<F>(
+    engine: &str,
+    config: &Config,
+    puzzle: &Puzzle,
+    callback: F,
+) -> Result<Vec<Move>, &'static str>
+where
+    F: FnMut(&Progress) -> bool,
+{
+    check_optimal(engine, config)?;
+    match engine {
+        "ida-star" => search::run(ida_star(config)?.search(puzzle)?, callback),
+        "a-star" => search::run(a_star(config)?.search(puzzle)?, callback),
+        "bidirectional" => puzzle.solve_bidirectional_with_progress(callback),
+        "weighted-a-star" => {
+            let weight = config.parse_or("weight", DEFAULT_WEIGHT);
+            puzzle.solve_weighted_with_progress(weight, callback)
+        }
+        "beam" => {
+            let width = config.parse_or("beam_width", DEFAULT_BEAM_WIDTH);
+            puzzle.solve_beam_with_progress(width, callback)
+        }
+        _ => Err("Engine cannot be interrupted"),
+    }
+}
+
+/// Searches with the pattern database named by `pdb_file`, building and
+/// saving it first if the file does not exist or holds a different
+/// partition than `pdb_partition` asks for. A partition already loaded by
+/// an earlier config is reused; see `pdb::shared`.
+fn ida_star(config: &Config) -> Result<IdaStar, &'static str> {
+    let pattern_database = match config.get("pdb_file") {
+        Some(path) => {
+            let path = Path::new(path);
+            Some(match config.get("pdb_partition") {
+                Some(text) => pdb::shared(path, &pdb::parse_partition(text)?)?,
+                None => pdb::shared(path, &pdb::DEFAULT_PARTITION)?,
+            })
+        }
+        None => None,
+    };
+    Ok(IdaStar {
+        table_bytes: config.parse_or("table_bytes", 0),
+        pattern_database,
+        heuristic: chosen_heuristic(config)?,
+    })
+}
+
+fn a_star(config: &Config) -> Result<AStar, &'static str> {
+    Ok(AStar {
+        heuristic: chosen_heuristic(config)?,
+    })
+}
+
+/// The estimate named by the `heuristic` key, if any. With `require_optimal`
+/// set it must be one the catalog lists as admissible.
+fn chosen_heuristic(config: &Config) -> Result<Option<&'static str>, &'static str> {
+    let Some(name) = config.get("heuristic") else {
+        return Ok(None);
+    };
+    let name = heuristic::NAMES
+        .into_iter()
+        .find(|&known| known == name)
+        .ok_or("Unknown heuristic")?;
+    if config.parse_or("require_optimal", false)
+        && !catalog::heuristics()
+            .iter()
+            .any(|info| info.name == name && info.admissible)
+    {
+        return Err("Heuristic may overestimate, so solutions would not be optimal");
+    }
+    Ok(Some(name))
+}
+
+/// Maps engine names to factories so that downstream crates can add their own
+/// solvers and have them picked up by name from the command line or a config file.
+#[derive(Default)]
+pub struct SolverRegistry {
+    factories: BTreeMap<String, SolverFactory>,
+}
+
+impl SolverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry pre-populated with the engines shipped by this crate.
+    /// `ida-star` and `a-star` read `require_optimal` and `heuristic`, `ida-star` also reads
+    /// `table_bytes`, `pdb_file` and `pdb_partition`, `external-a-star` reads `external_dir` and
+    /// `external_buffer`, `weighted-a-star` reads `weight`, `beam` reads
+    /// `beam_width` and `adaptive` reads `budget_secs` from the config, or
+    /// `budget_nodes` when `deterministic` is set; see `determinism`.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register("ida-star", |config| match ida_star(config) {
+            Ok(solver) => {
+                Box::new(move |puzzle: &Puzzle| solver.solve(puzzle).map(Solution::into_moves))
+            }
+            Err(err) => Box::new(move |_: &Puzzle| Err(err)),
+        });
+        registry.register("a-star", |config| match a_star(config) {
+            Ok(solver) => {
+                Box::new(move |puzzle: &Puzzle| solver.solve(puzzle).map(Solution::into_moves))
+            }
+            Err(err) => Box::new(move |_: &Puzzle| Err(err)),
+        });
+        registry.register_solver("bidirectional", |_| Bidirectional);
+        registry.register_solver("external-a-star", |config| {
+            let defaults = ExternalOptions::default();
+            External {
+                options: ExternalOptions {
+                    dir: config
+                        .get("external_dir")
+                        .map_or(defaults.dir, PathBuf::from),
+                    buffer_limit: config.parse_or("external_buffer", defaults.buffer_limit),
+                    ..defaults
+                },
+            }
+        });
+        registry.register_solver("weighted-a-star", |config| WeightedAStar {
+            weight: config.parse_or("weight", DEFAULT_WEIGHT),
+        });
+        registry.register_solver("beam", |config| Beam {
+            width: config.parse_or("beam_width", DEFAULT_BEAM_WIDTH),
+        });
+        registry.register_solver("adaptive", |config| Adaptive {
+            budget: if config.parse_or("deterministic", false) {
+                Budget::Nodes(config.parse_or("budget_nodes", DEFAULT_BUDGET_NODES))
+            } else {
+                Budget::Time(Duration::from_secs(config.parse_or("budget_secs", 10)))
+            },
+        });
+        registry
+    }
+
+    /// Registers a factory under `name`, replacing any previous registration.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Config) -> SolveFn + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    /// Registers a `Solver` under `name`, built from the config by `make`.
+    pub fn register_solver<S, F>(&mut self, name: &str, make: F)
+    where
+        S: Solver + 'static,
+        F: Fn(&Config) -> S + 'static,
+    {
+        self.register(name, move |config| {
+            let solver = make(config);
+            Box::new(move |puzzle: &Puzzle| solver.solve(puzzle).map(Solution::into_moves))
+        });
+    }
+
+    pub fn create(&self, name: &str, config: &Config) -> Option<SolveFn> {
+        self.factories.get(name).map(|factory| factory(config))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+}
@@ -0,0 +1,237 @@
+//! Failing solver cases: checking a solver's answer, shrinking a board it
+//! fails on to a smaller one, and saving the case so `repro` can replay it.
+//!
+//! Shrinking works on a `Scramble`, a board size and the moves that mix it
+//! up from solved. Dropping a row or column, or some of the moves, always
+//! gives another valid board, so a failure can be pared down toward the
+//! smallest board and the fewest moves from solved that still fail. With
+//! the `proptest` feature, `strategy` generates scrambles the same way, so
+//! proptest's own shrinking heads the same direction.
+
+use crate::json::Json;
+use crate::puzzle::{Move, Puzzle};
+use crate::schema;
+use crate::tablebase;
+
+/// Largest board, in cells, on which solutions are checked for length
+/// against an exact search.
+const EXACT_MAX_CELLS: usize = 9;
+
+/// Most candidates `shrink` tries before settling for what it has.
+const MAX_SHRINK_STEPS: usize = 10_000;
+
+/// A board as the moves that mix it up from the solved board of its size.
+/// Moves that would leave the board are skipped, so every scramble gives a
+/// valid, solvable board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scramble {
+    pub rows: usize,
+    pub cols: usize,
+    pub moves: Vec<Move>,
+}
+
+impl Scramble {
+    pub fn puzzle(&self) -> Result<Puzzle, &'static str> {
+        let mut puzzle = Puzzle::new_rectangular(self.rows, self.cols)?;
+        for &movement in &self.moves {
+            puzzle.apply_move(movement);
+        }
+        Ok(puzzle)
+    }
+
+    /// The scramble that undoes a solution of `puzzle`, which must be
+    /// solvable and have the standard goal.
+    pub fn from_puzzle(puzzle: &Puzzle) -> Result<Self, &'static str> {
+        if !puzzle.has_standard_goal() {
+            return Err("Only boards with the standard goal can be shrunk");
+        }
+        let solution = puzzle.solve()?;
+        Ok(Self {
+            rows: puzzle.rows(),
+            cols: puzzle.cols(),
+            moves: solution
+                .iter()
+                .rev()
+                .map(|movement| movement.opposite())
+                .collect(),
+        })
+    }
+
+    /// Smaller scrambles to try, most reduced first: a row or column fewer,
+    /// then the moves cut in halves and quarters, then one move fewer.
+    pub fn candidates(&self) -> Vec<Scramble> {
+        let mut candidates = Vec::new();
+        if self.rows > 2 {
+            candidates.push(Scramble {
+                rows: self.rows - 1,
+                ..self.clone()
+            });
+        }
+        if self.cols > 2 {
+            candidates.push(Scramble {
+                cols: self.cols - 1,
+                ..self.clone()
+            });
+        }
+        let len = self.moves.len();
+        for parts in [2, 4] {
+            let chunk = len / parts;
+            if chunk == 0 {
+                continue;
+            }
+            for start in (0..len).step_by(chunk) {
+                let mut moves = self.moves[..start].to_vec();
+                moves.extend_from_slice(&self.moves[(start + chunk).min(len)..]);
+                candidates.push(Scramble {
+                    moves,
+                    ..self.clone()
+                });
+            }
+        }
+        for index in 0..len {
+            let mut moves = self.moves.clone();
+            moves.remove(index);
+            candidates.push(Scramble {
+                moves,
+                ..self.clone()
+            });
+        }
+        candidates
+    }
+}
+
+/// Repeatedly replaces `scramble` with its first candidate whose board
+/// still `fails`, until none does. The result fails whenever `scramble`
+/// did.
+pub fn shrink<F>(mut scramble: Scramble, mut fails: F) -> Scramble
+where
+    F: FnMut(&Puzzle) -> bool,
+{
+    let mut steps = 0;
+    'shrinking: loop {
+        for candidate in scramble.candidates() {
+            steps += 1;
+            if steps > MAX_SHRINK_STEPS {
+                break 'shrinking;
+            }
+            if candidate.puzzle().is_ok_and(|puzzle| fails(&puzzle)) {
+                scramble = candidate;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    scramble
+}
+
+/// Checks a solver's answer for `puzzle`, giving the reason it is wrong.
+/// With `optimal`, solutions must also be shortest, which is checked on
+/// boards of up to 9 cells.
+pub fn check_answer(
+    puzzle: &Puzzle,
+    answer: Result<Vec<Move>, &'static str>,
+    optimal: bool,
+) -> Result<(), String> {
+    let solvable = puzzle.is_current_state_solvable();
+    let moves = match answer {
+        Ok(moves) => moves,
+        Err(_) if !solvable => return Ok(()),
+        Err(err) => return Err(format!("Solver failed: {}", err)),
+    };
+    if !solvable {
+        return Err("Solver answered an unsolvable board".to_string());
+    }
+    let mut end = puzzle.clone();
+    if end.apply_moves(&moves).is_err() || !end.is_solved() {
+        return Err("Moves do not solve the board".to_string());
+    }
+    if optimal && puzzle.rows() * puzzle.cols() <= EXACT_MAX_CELLS {
+        let shortest = match tablebase::distance_to_goal(puzzle) {
+            Some(distance) => distance,
+            None => puzzle.solve_optimal().map_err(str::to_string)?.len(),
+        };
+        if moves.len() != shortest {
+            return Err(format!(
+                "Solution has {} moves, the shortest has {}",
+                moves.len(),
+                shortest
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A board a solver gets wrong, with what is needed to run it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailingCase {
+    /// The engine's name in `registry::SolverRegistry`.
+    pub solver: String,
+    /// Whether solutions had to be shortest.
+    pub require_optimal: bool,
+    pub puzzle: Puzzle,
+    /// What was wrong when the case was saved.
+    pub reason: String,
+}
+
+impl FailingCase {
+    /// The case as a document. Boards with a custom goal also record the
+    /// goal's tiles under `goal`.
+    pub fn to_json(&self) -> Json {
+        let mut fields = vec![
+            ("solver", Json::from(self.solver.as_str())),
+            ("require_optimal", Json::from(self.require_optimal)),
+            ("rows", Json::from(self.puzzle.rows())),
+            ("cols", Json::from(self.puzzle.cols())),
+            ("tiles", Json::from(self.puzzle.tiles())),
+        ];
+        if !self.puzzle.has_standard_goal() {
+            fields.push(("goal", Json::from(self.puzzle.goal().tiles())));
+        }
+        fields.push(("reason", Json::from(self.reason.as_str())));
+        schema::document("failing_case", fields)
+    }
+
+    pub fn from_json(document: &Json) -> Result<Self, &'static str> {
+        let document = schema::migrate(document.clone())?;
+        if document.get("kind").and_then(Json::as_str) != Some("failing_case") {
+            return Err("Document is not a failing case");
+        }
+        let number = |key: &str| {
+            document
+                .get(key)
+                .and_then(Json::as_f64)
+                .map(|value| value as usize)
+                .ok_or("Missing board size")
+        };
+        let tiles = |key: &str| {
+            let Some(Json::Array(values)) = document.get(key) else {
+                return Err("Missing tiles");
+            };
+            values
+                .iter()
+                .map(|value| value.as_f64().map(|value| value as u32))
+                .collect::<Option<Vec<u32>>>()
+                .ok_or("Invalid tile value")
+        };
+        let (rows, cols) = (number("rows")?, number("cols")?);
+        let mut puzzle = Puzzle::from_tiles_rectangular(rows, cols, &tiles("tiles")?)?;
+        if document.get("goal").is_some() {
+            let goal = Puzzle::from_tiles_rectangular(rows, cols, &tiles("goal")?)?;
+            puzzle = puzzle.with_goal(&goal)?;
+        }
+        Ok(Self {
+            solver: document
+                .get("solver")
+                .and_then(Json::as_str)
+                .ok_or("Missing solver")?
+                .to_string(),
+            require_optimal: document.get("require_optimal") == Some(&Json::Bool(true)),
+            puzzle,
+            reason: document
+                .get("reason")
+                .and_then(Json::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use crate::goal::goal_positions_of;
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+
+/// Random walks tried before settling for the closest distance found.
+const ATTEMPTS: usize = 20;
+
+impl Puzzle {
+    /// Moves the puzzle to a nearby state whose optimal distance from the
+    /// current one is `k` if possible, for game modes where each round starts
+    /// close to where the last one ended. Returns the verified distance, which
+    /// may fall short of `k` on very small boards.
+    pub fn rescramble_nearby(&mut self, k: usize) -> usize {
+        let mut best: Option<(Puzzle, usize)> = None;
+
+        for attempt in 0..ATTEMPTS {
+            // Random walks tend to undo themselves, so lengthen them as attempts fail
+            let steps = k + attempt * k / ATTEMPTS;
+            let mut candidate = self.clone();
+            candidate.random_walk(steps);
+
+            let distance = distance_between(self, &candidate, k);
+            if best
+                .as_ref()
+                .is_none_or(|(_, d)| k.abs_diff(distance) < k.abs_diff(*d))
+            {
+                best = Some((candidate, distance));
+            }
+            if distance == k {
+                break;
+            }
+        }
+
+        let (state, distance) = best.unwrap_or((self.clone(), 0));
+        *self = state;
+        distance
+    }
+}
+
+/// Optimal distance between two states, capped at `limit + 1` so that
+/// checking an overshooting walk stays cheap.
+pub(crate) fn distance_between(from: &Puzzle, to: &Puzzle, limit: usize) -> usize {
+    if let Some(distance) = tablebase::distance_between(from, to) {
+        return distance;
+    }
+
+    let targets = goal_positions_of(to);
+    let mut state = from.clone();
+    for bound in 0..=limit {
+        if search(&mut state, to, &targets, 0, bound, None) {
+            return bound;
+        }
+    }
+    limit + 1
+}
+
+fn search(
+    state: &mut Puzzle,
+    target: &Puzzle,
+    targets: &[(usize, usize)],
+    g: usize,
+    bound: usize,
+    last_move: Option<Move>,
+) -> bool {
+    if g + state.manhattan_distance_to(targets) > bound {
+        return false;
+    }
+    if state == target {
+        return true;
+    }
+
+    for dir in Move::ALL {
+        if last_move.is_some_and(|last| dir == last.opposite()) {
+            continue;
+        }
+        if state.apply_move(dir) {
+            let found = search(state, target, targets, g + 1, bound, Some(dir));
+            state.apply_move(dir.opposite());
+            if found {
+                return true;
+            }
+        }
+    }
+
+    false
+}
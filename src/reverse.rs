@@ -0,0 +1,117 @@
+use crate::puzzle::{Move, Puzzle};
+use crate::tablebase;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// A move sequence in which some steps may be left open. Written as move
+/// names or letters separated by spaces, with `*` for any move, e.g. `U L * R`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovePattern {
+    steps: Vec<Option<Move>>,
+}
+
+/// A start state together with the concrete sequence that solves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseMatch {
+    pub start: Puzzle,
+    pub moves: Vec<Move>,
+}
+
+impl MovePattern {
+    pub fn new(steps: Vec<Option<Move>>) -> Self {
+        Self { steps }
+    }
+
+    pub fn steps(&self) -> &[Option<Move>] {
+        &self.steps
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl FromStr for MovePattern {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(|token| match token {
+                "*" => Ok(None),
+                token => token.parse().map(Some),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+}
+
+/// Finds up to `limit` start states that the pattern solves on a `size` x
+/// `size` board. With `require_optimal`, only states for which the sequence
+/// is also a shortest solution are kept.
+///
+/// The search runs backwards from the goal, undoing the pattern's last step
+/// first, so every result is solved by its sequence by construction.
+pub fn find_starts(
+    size: usize,
+    pattern: &MovePattern,
+    require_optimal: bool,
+    limit: usize,
+) -> Result<Vec<ReverseMatch>, &'static str> {
+    let goal = Puzzle::new(size)?;
+    let mut matches = Vec::new();
+    let mut seen = HashSet::new();
+    let mut reversed = Vec::with_capacity(pattern.len());
+
+    expand(
+        &goal,
+        pattern.steps(),
+        &mut reversed,
+        &mut |start, reversed| {
+            if matches.len() >= limit || !seen.insert(start.clone()) {
+                return;
+            }
+            if require_optimal && exact_distance(start) != Some(reversed.len()) {
+                return;
+            }
+            matches.push(ReverseMatch {
+                start: start.clone(),
+                moves: reversed.iter().rev().copied().collect(),
+            });
+        },
+    );
+
+    Ok(matches)
+}
+
+/// Undoes the remaining `steps` from the back, calling `found` with each
+/// reachable start state and the moves undone so far, last move first.
+fn expand<F>(state: &Puzzle, steps: &[Option<Move>], reversed: &mut Vec<Move>, found: &mut F)
+where
+    F: FnMut(&Puzzle, &[Move]),
+{
+    let Some((&step, rest)) = steps.split_last() else {
+        found(state, reversed);
+        return;
+    };
+
+    let candidates: &[Move] = match &step {
+        Some(movement) => std::slice::from_ref(movement),
+        None => &Move::ALL,
+    };
+    for &movement in candidates {
+        let mut previous = state.clone();
+        if previous.apply_move(movement.opposite()) {
+            reversed.push(movement);
+            expand(&previous, rest, reversed, found);
+            reversed.pop();
+        }
+    }
+}
+
+fn exact_distance(puzzle: &Puzzle) -> Option<usize> {
+    tablebase::distance_to_goal(puzzle).or_else(|| puzzle.solve().ok().map(|moves| moves.len()))
+}
@@ -0,0 +1,123 @@
+use crate::clock::Instant;
+use crate::config::Config;
+use crate::puzzle::{Move, Puzzle};
+use crate::registry::solve_interruptible;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Solves several boards at once while only letting `workers` of them run at
+/// any moment. Each solve runs for at most `slice` before handing its turn to
+/// the next waiting one, so a hard board cannot hold a worker until it times
+/// out while easy ones queue behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scheduler {
+    pub workers: usize,
+    pub slice: Duration,
+    /// Running time allowed per board, not counting time spent waiting.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledSolve {
+    /// Position of the board in the input.
+    pub index: usize,
+    pub outcome: Result<Vec<Move>, &'static str>,
+    /// Time the board spent running.
+    pub active: Duration,
+    /// Time from the start of the schedule until the board finished.
+    pub finished_after: Duration,
+}
+
+/// FIFO turn-taking: waiting solves queue up and the front one runs as soon
+/// as fewer than `workers` are running.
+struct Turns {
+    state: Mutex<(usize, VecDeque<usize>)>,
+    changed: Condvar,
+    workers: usize,
+}
+
+impl Turns {
+    fn acquire(&self, id: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.1.push_back(id);
+        self.changed.notify_all();
+        while !(state.0 < self.workers && state.1.front() == Some(&id)) {
+            state = self.changed.wait(state).unwrap();
+        }
+        state.1.pop_front();
+        state.0 += 1;
+        self.changed.notify_all();
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.0 -= 1;
+        self.changed.notify_all();
+    }
+
+    fn has_waiting(&self) -> bool {
+        !self.state.lock().unwrap().1.is_empty()
+    }
+}
+
+impl Scheduler {
+    /// Solves every board with `engine`, one of the interruptible engines,
+    /// and returns the results in input order.
+    pub fn solve_all(
+        &self,
+        puzzles: &[Puzzle],
+        engine: &str,
+        config: &Config,
+    ) -> Vec<ScheduledSolve> {
+        let turns = Turns {
+            state: Mutex::new((0, VecDeque::new())),
+            changed: Condvar::new(),
+            workers: self.workers.max(1),
+        };
+        let start = Instant::now();
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = puzzles
+                .iter()
+                .enumerate()
+                .map(|(index, puzzle)| {
+                    let turns = &turns;
+                    scope.spawn(move || {
+                        turns.acquire(index);
+                        let mut active = Duration::ZERO;
+                        let mut slice_start = Instant::now();
+
+                        let outcome = solve_interruptible(engine, config, puzzle, |_| {
+                            if slice_start.elapsed() >= self.slice && turns.has_waiting() {
+                                active += slice_start.elapsed();
+                                turns.release();
+                                turns.acquire(index);
+                                slice_start = Instant::now();
+                            }
+                            active + slice_start.elapsed() < self.timeout
+                        });
+                        active += slice_start.elapsed();
+                        turns.release();
+
+                        ScheduledSolve {
+                            index,
+                            outcome: match outcome {
+                                Err("Search cancelled") => Err("Timed out"),
+                                outcome => outcome,
+                            },
+                            active,
+                            finished_after: start.elapsed(),
+                        }
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+}
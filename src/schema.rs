@@ -0,0 +1,70 @@
+//! Versioning for the crate's JSON outputs.
+//!
+//! Every document is an object starting with `schema_version` and `kind`.
+//! Adding fields does not change the version, so readers must ignore fields
+//! they do not know. Renaming, removing or changing the meaning of a field
+//! bumps `SCHEMA_VERSION`, and `migrate` gains a step that upgrades documents
+//! from the previous version.
+
+use crate::json::Json;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Wraps `fields` in an object tagged with the current schema version and `kind`.
+pub fn document<I>(kind: &str, fields: I) -> Json
+where
+    I: IntoIterator<Item = (&'static str, Json)>,
+{
+    let header = [
+        ("schema_version", Json::from(SCHEMA_VERSION)),
+        ("kind", Json::from(kind)),
+    ];
+    Json::object(header.into_iter().chain(fields))
+}
+
+/// Version of a document; files written before versioning count as version 0.
+pub fn version_of(document: &Json) -> u32 {
+    document
+        .get("schema_version")
+        .and_then(Json::as_f64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Upgrades a document to the current schema version, one step at a time.
+pub fn migrate(mut document: Json) -> Result<Json, &'static str> {
+    if !matches!(document, Json::Object(_)) {
+        return Err("Document is not a JSON object");
+    }
+
+    let mut version = version_of(&document);
+    if version > SCHEMA_VERSION {
+        return Err("Document is newer than this version of the crate");
+    }
+
+    while version < SCHEMA_VERSION {
+        document = match version {
+            0 => migrate_v0(document)?,
+            _ => unreachable!("missing migration step"),
+        };
+        version += 1;
+    }
+
+    Ok(document)
+}
+
+/// Version 0 documents had no header. The only such output was the
+/// animation timeline, recognisable by its `total_ms` field.
+fn migrate_v0(document: Json) -> Result<Json, &'static str> {
+    if document.get("total_ms").is_none() {
+        return Err("Unrecognised unversioned document");
+    }
+
+    let Json::Object(fields) = document else {
+        unreachable!()
+    };
+    let header = [
+        ("schema_version".to_string(), Json::from(1u32)),
+        ("kind".to_string(), Json::from("timeline")),
+    ];
+    Ok(Json::Object(header.into_iter().chain(fields).collect()))
+}
@@ -0,0 +1,216 @@
+//! Heuristics, scramble filters and move-selection policies written as
+//! [Rhai](https://rhai.rs) scripts, for trying out ideas without
+//! recompiling. Only built with the `scripting` feature.
+//!
+//! A script defines any of these functions, each given the board as a
+//! `Board`:
+//!
+//! ```text
+//! fn estimate(board) { board.heuristic("linear-conflict") }
+//! fn accept(board) { board.blank_row == 0 }
+//! fn choose(board, moves) { moves[0] }
+//! ```
+//!
+//! `estimate` returns the moves left, `accept` whether a scramble should be
+//! kept, and `choose` one of the legal moves it is given, such as `"up"`.
+//! A `Board` offers `rows`, `cols`, `blank_row` and `blank_col`, and the
+//! methods `tile(row, col)` (0 for the empty space), `goal_row(value)`,
+//! `goal_col(value)`, `heuristic(name)` for any name in `heuristic::NAMES`,
+//! `is_solved()`, `moves()` listing the legal moves, `after(move)` for the
+//! board a move leads to, and `to_string()`.
+//!
+//! Scripts are sandboxed: they see nothing but the board, cannot reach
+//! files, the network or `eval`, and each call is cut off after a fixed
+//! number of operations, so a runaway script fails instead of hanging.
+
+use crate::heuristic::{self, Heuristic};
+use crate::puzzle::{Move, Puzzle};
+use rand::Rng;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope, AST, INT};
+use std::rc::Rc;
+
+/// Operations a single call of a script function may take.
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// A compiled script. Cloning shares the compiled form.
+#[derive(Clone)]
+pub struct Script {
+    engine: Rc<Engine>,
+    ast: Rc<AST>,
+}
+
+impl Script {
+    /// Compiles `source`, failing with the position and cause of the first
+    /// syntax error.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let engine = sandboxed_engine();
+        let ast = engine.compile(source).map_err(|err| err.to_string())?;
+        Ok(Self {
+            engine: Rc::new(engine),
+            ast: Rc::new(ast),
+        })
+    }
+
+    /// Whether the script defines a function `name` taking `params`
+    /// arguments.
+    pub fn defines(&self, name: &str, params: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == params)
+    }
+
+    /// The script's `estimate` for `puzzle`, which must be a non-negative
+    /// integer.
+    pub fn estimate(&self, puzzle: &Puzzle) -> Result<usize, String> {
+        let estimate: INT = self.call("estimate", (puzzle.clone(),))?;
+        usize::try_from(estimate).map_err(|_| format!("estimate returned {}", estimate))
+    }
+
+    /// Whether the script's `accept` keeps `puzzle`.
+    pub fn accept(&self, puzzle: &Puzzle) -> Result<bool, String> {
+        self.call("accept", (puzzle.clone(),))
+    }
+
+    /// The move the script's `choose` picks from the legal moves of
+    /// `puzzle`, which must be one of them.
+    pub fn choose(&self, puzzle: &Puzzle) -> Result<Move, String> {
+        let legal: Vec<Move> = puzzle.legal_moves().collect();
+        let choice: String = self.call("choose", (puzzle.clone(), move_names(&legal)))?;
+        choice
+            .parse()
+            .ok()
+            .filter(|movement| legal.contains(movement))
+            .ok_or_else(|| format!("choose returned {:?}, not a legal move", choice))
+    }
+
+    /// Shuffles copies of `goal` until the script's `accept` keeps one,
+    /// trying at most `attempts` scrambles.
+    pub fn scramble<R: Rng + ?Sized>(
+        &self,
+        goal: &Puzzle,
+        attempts: usize,
+        rng: &mut R,
+    ) -> Result<Puzzle, String> {
+        for _ in 0..attempts {
+            let mut puzzle = goal.clone();
+            puzzle.shuffle_with(rng);
+            if self.accept(&puzzle)? {
+                return Ok(puzzle);
+            }
+        }
+        Err(format!("accept kept none of {} scrambles", attempts))
+    }
+
+    /// Follows the script's `choose` from `puzzle` until the board is solved
+    /// or `max_moves` moves have been made, returning the moves and whether
+    /// they solve it.
+    pub fn play(&self, puzzle: &Puzzle, max_moves: usize) -> Result<(Vec<Move>, bool), String> {
+        let mut board = puzzle.clone();
+        let mut moves = Vec::new();
+        while !board.is_solved() {
+            if moves.len() == max_moves {
+                return Ok((moves, false));
+            }
+            let movement = self.choose(&board)?;
+            board.apply_move(movement);
+            moves.push(movement);
+        }
+        Ok((moves, true))
+    }
+
+    fn call<T: Clone + 'static>(&self, name: &str, args: impl rhai::FuncArgs) -> Result<T, String> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, name, args)
+            .map_err(|err| format!("{}: {}", name, err))
+    }
+}
+
+/// Searches with the script's `estimate`. A call that fails counts as the
+/// Manhattan distance instead, which keeps the search going and never
+/// overestimates; check a script with `Script::estimate` first to see its
+/// errors.
+impl Heuristic for Script {
+    fn estimate(&self, puzzle: &Puzzle) -> usize {
+        Script::estimate(self, puzzle).unwrap_or_else(|_| puzzle.manhattan_distance())
+    }
+}
+
+/// An engine whose scripts see only the `Board` API, with limits on how
+/// long and how deep they can run.
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(10_000)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000)
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {});
+    engine.disable_symbol("eval");
+
+    engine
+        .register_type_with_name::<Puzzle>("Board")
+        .register_get("rows", |board: &mut Puzzle| board.rows() as INT)
+        .register_get("cols", |board: &mut Puzzle| board.cols() as INT)
+        .register_get("blank_row", |board: &mut Puzzle| {
+            board.blank_position().0 as INT
+        })
+        .register_get("blank_col", |board: &mut Puzzle| {
+            board.blank_position().1 as INT
+        })
+        .register_fn("tile", tile)
+        .register_fn("goal_row", |board: &mut Puzzle, value: INT| {
+            goal_of(board, value).map(|(row, _)| row)
+        })
+        .register_fn("goal_col", |board: &mut Puzzle, value: INT| {
+            goal_of(board, value).map(|(_, col)| col)
+        })
+        .register_fn("heuristic", heuristic_named)
+        .register_fn("is_solved", |board: &mut Puzzle| board.is_solved())
+        .register_fn("moves", |board: &mut Puzzle| {
+            move_names(&board.legal_moves().collect::<Vec<_>>())
+        })
+        .register_fn("after", after)
+        .register_fn("to_string", |board: &mut Puzzle| format!("{:#}", board));
+    engine
+}
+
+fn tile(board: &mut Puzzle, row: INT, col: INT) -> Result<INT, Box<EvalAltResult>> {
+    usize::try_from(row)
+        .ok()
+        .zip(usize::try_from(col).ok())
+        .and_then(|(row, col)| board.get_tile(row, col))
+        .map(|value| value as INT)
+        .ok_or_else(|| format!("No cell at row {}, column {}", row, col).into())
+}
+
+fn goal_of(board: &mut Puzzle, value: INT) -> Result<(INT, INT), Box<EvalAltResult>> {
+    let goals = board.goals();
+    let (row, col) = usize::try_from(value)
+        .ok()
+        .and_then(|value| goals.get(value))
+        .ok_or_else(|| format!("No tile {}", value))?;
+    Ok((*row as INT, *col as INT))
+}
+
+fn heuristic_named(board: &mut Puzzle, name: &str) -> Result<INT, Box<EvalAltResult>> {
+    let estimate = heuristic::by_name(name).ok_or_else(|| format!("Unknown heuristic {}", name))?;
+    Ok(estimate.estimate(board) as INT)
+}
+
+fn after(board: &mut Puzzle, movement: &str) -> Result<Puzzle, Box<EvalAltResult>> {
+    let mut next = board.clone();
+    match movement.parse() {
+        Ok(movement) if next.apply_move(movement) => Ok(next),
+        _ => Err(format!("Cannot move {}", movement).into()),
+    }
+}
+
+fn move_names(moves: &[Move]) -> Array {
+    moves
+        .iter()
+        .map(|movement| Dynamic::from(movement.to_string().to_ascii_lowercase()))
+        .collect()
+}
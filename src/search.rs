@@ -0,0 +1,530 @@
+//! Search engines as resumable state machines.
+//!
+//! Each engine keeps its whole search state (stack or frontier, bound and
+//! counters) in a struct and advances it a bounded number of nodes per call
+//! to `step`, so a search can be paused, resumed, time-sliced or polled for
+//! progress between steps.
+
+use crate::heuristic::{Heuristic, LinearConflict};
+use crate::progress::{Progress, ProgressTracker, REPORT_INTERVAL};
+use crate::puzzle::{Move, MoveSet, Puzzle};
+use crate::transposition::TranspositionTable;
+
+/// Where a search stands after a step. Searches of boards other than
+/// `Puzzle` report solutions in their own kind of move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStatus<M = Move> {
+    /// More steps are needed.
+    Running,
+    Solved(Vec<M>),
+    Failed(&'static str),
+}
+
+/// Error reported when the heuristic is 0 on an unsolved state, or positive
+/// on a solved one. Either means the heuristic measures distance to a
+/// different goal than the one the search checks for, and continuing would
+/// only wander until a node or iteration limit is reached.
+pub const HEURISTIC_GOAL_MISMATCH: &str = "Heuristic disagrees with the goal state";
+
+pub trait Search<M = Move> {
+    /// Visits at most `nodes` more nodes. Engines may also return early at a
+    /// natural boundary, such as the end of an IDA* iteration. Once the search
+    /// has finished, every further call returns the same outcome.
+    fn step(&mut self, nodes: u64) -> SearchStatus<M>;
+
+    fn progress(&self) -> Progress;
+}
+
+/// Steps `search` to completion, reporting progress to `callback` between
+/// steps. Returning `false` from the callback cancels the search.
+pub(crate) fn run<M, S, F>(mut search: S, mut callback: F) -> Result<Vec<M>, &'static str>
+where
+    S: Search<M>,
+    F: FnMut(&Progress) -> bool,
+{
+    if !callback(&search.progress()) {
+        return Err("Search cancelled");
+    }
+    loop {
+        match search.step(REPORT_INTERVAL) {
+            SearchStatus::Running => {
+                if !callback(&search.progress()) {
+                    return Err("Search cancelled");
+                }
+            }
+            SearchStatus::Solved(moves) => {
+                callback(&search.progress());
+                return Ok(moves);
+            }
+            SearchStatus::Failed(err) => return Err(err),
+        }
+    }
+}
+
+const MAX_ITERATIONS: usize = 1000000;
+
+/// The moves a search may make from a state; see
+/// `IdaStarSearch::with_move_filter`.
+type MoveFilter = Box<dyn Fn(&Puzzle) -> MoveSet>;
+
+struct Frame {
+    state: Puzzle,
+    /// Moves to try from `state`: legal, allowed by the filter and not
+    /// undoing the move that led here.
+    allowed: MoveSet,
+    /// Index into `Move::ALL` of the next move to try.
+    next: usize,
+    /// The heuristic's estimate for `state`.
+    h: usize,
+}
+
+enum Visit {
+    Pruned,
+    Expanded,
+    Solved,
+    Mismatch,
+}
+
+/// Optimal IDA* search with the crate's default heuristic.
+pub struct IdaStarSearch {
+    start: Puzzle,
+    bound: usize,
+    /// Smallest f-value seen above `bound` in the current iteration.
+    next_bound: usize,
+    iterations: usize,
+    /// The current path's states; empty before an iteration's root is visited.
+    stack: Vec<Frame>,
+    path: Vec<Move>,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus>,
+    heuristic: Box<dyn Heuristic>,
+    table: Option<TranspositionTable>,
+    filter: Option<MoveFilter>,
+}
+
+impl IdaStarSearch {
+    pub fn new(start: &Puzzle) -> Result<Self, &'static str> {
+        Self::with_heuristic(start, LinearConflict)
+    }
+
+    /// Searches with `heuristic` in place of the default estimate. Solutions
+    /// are only guaranteed to be shortest if it never overestimates.
+    pub fn with_heuristic(
+        start: &Puzzle,
+        heuristic: impl Heuristic + 'static,
+    ) -> Result<Self, &'static str> {
+        if !start.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+
+        let bound = heuristic.estimate(start);
+        let mut tracker = ProgressTracker::new();
+        tracker.start_iteration(bound);
+        Ok(Self {
+            start: start.clone(),
+            bound,
+            next_bound: usize::MAX,
+            iterations: 1,
+            stack: Vec::new(),
+            path: Vec::new(),
+            tracker,
+            outcome: None,
+            heuristic: Box::new(heuristic),
+            table: None,
+            filter: None,
+        })
+    }
+
+    /// Skips states already reached at the same or a lower cost in the
+    /// current iteration, remembering up to `bytes` worth of states.
+    /// Solutions stay shortest with an admissible heuristic.
+    pub fn with_transposition_table(mut self, bytes: usize) -> Self {
+        self.table = (bytes > 0).then(|| TranspositionTable::new(bytes));
+        self
+    }
+
+    /// Only makes the moves `filter` allows from each state, such as
+    /// directions a front end has disabled. Solutions are the shortest that
+    /// keep to the filter, given an admissible heuristic. A filter that
+    /// rules out every solution leaves the search deepening until its
+    /// iteration limit.
+    pub fn with_move_filter(mut self, filter: impl Fn(&Puzzle) -> MoveSet + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Also profiles expanded nodes by depth and heuristic value.
+    pub(crate) fn with_profile(mut self) -> Self {
+        self.tracker = self.tracker.with_profile();
+        self
+    }
+
+    pub(crate) fn tracker(&self) -> &ProgressTracker {
+        &self.tracker
+    }
+
+    /// Counts a node at the end of the current path and pushes it if it is
+    /// within the bound.
+    fn visit(&mut self, state: Puzzle, last_move: Option<Move>, h: usize) -> Visit {
+        self.tracker.node();
+        let g = self.path.len();
+        let f = g + h;
+        if f > self.bound {
+            self.next_bound = self.next_bound.min(f);
+            return Visit::Pruned;
+        }
+        if let Some(table) = &mut self.table {
+            if !table.visit(&state, g, self.iterations) {
+                return Visit::Pruned;
+            }
+        }
+        self.tracker.expanded(g, h);
+        if state.is_solved() {
+            return if h == 0 {
+                Visit::Solved
+            } else {
+                Visit::Mismatch
+            };
+        }
+        if h == 0 {
+            return Visit::Mismatch;
+        }
+
+        let mut allowed = state.legal_moves();
+        if let Some(filter) = &self.filter {
+            allowed = allowed.intersection(filter(&state));
+        }
+        if let Some(last) = last_move {
+            allowed = allowed.without(last.opposite());
+        }
+        self.stack.push(Frame {
+            state,
+            allowed,
+            next: 0,
+            h,
+        });
+        Visit::Expanded
+    }
+
+    fn finish(&mut self, status: SearchStatus) -> SearchStatus {
+        if matches!(status, SearchStatus::Solved(_)) {
+            self.tracker.finish_iteration();
+        }
+        self.outcome = Some(status.clone());
+        status
+    }
+
+    /// Moves on to the next bound once an iteration has been exhausted.
+    fn next_iteration(&mut self) -> SearchStatus {
+        self.tracker.finish_iteration();
+        if self.next_bound == usize::MAX {
+            return self.finish(SearchStatus::Failed("No solution found"));
+        }
+        if self.next_bound <= self.bound {
+            return self.finish(SearchStatus::Failed("No progress possible"));
+        }
+
+        self.iterations += 1;
+        if self.iterations > MAX_ITERATIONS {
+            return self.finish(SearchStatus::Failed("Maximum iterations exceeded"));
+        }
+        self.bound = self.next_bound;
+        self.next_bound = usize::MAX;
+        self.tracker.start_iteration(self.bound);
+        SearchStatus::Running
+    }
+}
+
+impl Search for IdaStarSearch {
+    fn step(&mut self, nodes: u64) -> SearchStatus {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        let mut iteration_started = !self.stack.is_empty();
+        loop {
+            if self.stack.is_empty() {
+                if iteration_started {
+                    return self.next_iteration();
+                }
+                iteration_started = true;
+                visited += 1;
+                let h = self.heuristic.estimate(&self.start);
+                match self.visit(self.start.clone(), None, h) {
+                    Visit::Solved => return self.finish(SearchStatus::Solved(Vec::new())),
+                    Visit::Mismatch => {
+                        return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH))
+                    }
+                    Visit::Pruned | Visit::Expanded => continue,
+                }
+            }
+            if visited >= nodes {
+                return SearchStatus::Running;
+            }
+
+            let size = self.start.size();
+            let frame = self.stack.last_mut().unwrap();
+            let Some(&dir) = Move::ALL.get(frame.next) else {
+                self.stack.pop();
+                // The root has no move on the path
+                if !self.stack.is_empty() {
+                    self.path.pop();
+                }
+                continue;
+            };
+            frame.next += 1;
+
+            if !frame.allowed.contains(dir) {
+                continue;
+            }
+            let mut next = frame.state.clone();
+            next.apply_move(dir);
+
+            self.path.push(dir);
+
+            // Add depth limit to prevent stack overflow
+            if self.path.len() > size * size * 4 {
+                self.path.pop();
+                continue;
+            }
+
+            visited += 1;
+            let h = self
+                .heuristic
+                .estimate_after(&frame.state, frame.h, dir, &next);
+            match self.visit(next, Some(dir), h) {
+                Visit::Pruned => {
+                    self.path.pop();
+                }
+                Visit::Expanded => {}
+                Visit::Solved => {
+                    let moves = self.path.clone();
+                    return self.finish(SearchStatus::Solved(moves));
+                }
+                Visit::Mismatch => {
+                    return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH))
+                }
+            }
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
+
+/// Nodes a `StateSearch` expands before giving up.
+const MAX_STATE_NODES: u64 = 20_000_000;
+
+/// A board `StateSearch` can solve, such as a variant of the puzzle with
+/// its own rules for moving.
+pub trait SearchState {
+    type Move: Copy + PartialEq;
+
+    /// The legal moves, in a fixed order.
+    fn moves(&self) -> Vec<Self::Move>;
+
+    /// Makes `movement`, one of `moves`, and returns the move that takes it
+    /// back.
+    fn apply(&mut self, movement: Self::Move) -> Self::Move;
+
+    fn is_solved(&self) -> bool;
+
+    /// What making `movement` costs, at least 1. Searches minimise the
+    /// total, which by default is the number of moves.
+    fn cost(&self, movement: Self::Move) -> usize {
+        let _ = movement;
+        1
+    }
+
+    /// A lower bound on the cost left, 0 on the goal and only there.
+    fn estimate(&self) -> usize;
+
+    /// Whether `movement` straight after `previous` is never needed in a
+    /// cheapest solution, beyond taking `previous` back, which is always
+    /// skipped.
+    fn redundant_after(&self, previous: Self::Move, movement: Self::Move) -> bool {
+        let _ = (previous, movement);
+        false
+    }
+}
+
+struct StateFrame<M> {
+    moves: Vec<M>,
+    /// Index into `moves` of the next move to try.
+    next: usize,
+}
+
+/// Optimal IDA* over any `SearchState`, making and taking back moves on a
+/// single board, for the solution of least total `SearchState::cost`. Fails
+/// once it has expanded a fixed number of nodes.
+pub struct StateSearch<S: SearchState> {
+    state: S,
+    bound: usize,
+    /// Smallest f-value seen above `bound` in the current iteration.
+    next_bound: usize,
+    iterations: usize,
+    expanded: u64,
+    /// One frame per expanded state on the current path; empty before an
+    /// iteration's root is visited.
+    stack: Vec<StateFrame<S::Move>>,
+    path: Vec<S::Move>,
+    /// The move taking back each move of `path`.
+    undo: Vec<S::Move>,
+    /// The cost of `path` up to and including each move.
+    costs: Vec<usize>,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus<S::Move>>,
+}
+
+impl<S: SearchState> StateSearch<S> {
+    pub fn new(state: S) -> Self {
+        let bound = state.estimate();
+        let mut tracker = ProgressTracker::new();
+        tracker.start_iteration(bound);
+        Self {
+            state,
+            bound,
+            next_bound: usize::MAX,
+            iterations: 1,
+            expanded: 0,
+            stack: Vec::new(),
+            path: Vec::new(),
+            undo: Vec::new(),
+            costs: Vec::new(),
+            tracker,
+            outcome: None,
+        }
+    }
+
+    /// Counts the state at the end of the current path and pushes it if it
+    /// is within the bound.
+    fn visit(&mut self) -> Visit {
+        self.tracker.node();
+        let g = self.costs.last().copied().unwrap_or(0);
+        let h = self.state.estimate();
+        let f = g + h;
+        if f > self.bound {
+            self.next_bound = self.next_bound.min(f);
+            return Visit::Pruned;
+        }
+        self.tracker.expanded(g, h);
+        if self.state.is_solved() {
+            return if h == 0 {
+                Visit::Solved
+            } else {
+                Visit::Mismatch
+            };
+        }
+        if h == 0 {
+            return Visit::Mismatch;
+        }
+        self.expanded += 1;
+
+        let mut moves = self.state.moves();
+        if let (Some(&previous), Some(&back)) = (self.path.last(), self.undo.last()) {
+            moves.retain(|&movement| {
+                movement != back && !self.state.redundant_after(previous, movement)
+            });
+        }
+        self.stack.push(StateFrame { moves, next: 0 });
+        Visit::Expanded
+    }
+
+    /// Takes back the last move of the path.
+    fn retreat(&mut self) {
+        if let Some(back) = self.undo.pop() {
+            self.path.pop();
+            self.costs.pop();
+            self.state.apply(back);
+        }
+    }
+
+    fn finish(&mut self, status: SearchStatus<S::Move>) -> SearchStatus<S::Move> {
+        if matches!(status, SearchStatus::Solved(_)) {
+            self.tracker.finish_iteration();
+        }
+        self.outcome = Some(status.clone());
+        status
+    }
+
+    /// Moves on to the next bound once an iteration has been exhausted.
+    fn next_iteration(&mut self) -> SearchStatus<S::Move> {
+        self.tracker.finish_iteration();
+        if self.next_bound == usize::MAX {
+            return self.finish(SearchStatus::Failed("No solution found"));
+        }
+        self.iterations += 1;
+        if self.iterations > MAX_ITERATIONS {
+            return self.finish(SearchStatus::Failed("Maximum iterations exceeded"));
+        }
+        self.bound = self.next_bound;
+        self.next_bound = usize::MAX;
+        self.tracker.start_iteration(self.bound);
+        SearchStatus::Running
+    }
+}
+
+impl<S: SearchState> Search<S::Move> for StateSearch<S> {
+    fn step(&mut self, nodes: u64) -> SearchStatus<S::Move> {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        let mut iteration_started = !self.stack.is_empty();
+        loop {
+            if self.expanded > MAX_STATE_NODES {
+                return self.finish(SearchStatus::Failed("Node limit exceeded"));
+            }
+            if self.stack.is_empty() {
+                if iteration_started {
+                    return self.next_iteration();
+                }
+                iteration_started = true;
+                visited += 1;
+                match self.visit() {
+                    Visit::Solved => return self.finish(SearchStatus::Solved(Vec::new())),
+                    Visit::Mismatch => {
+                        return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH))
+                    }
+                    Visit::Pruned | Visit::Expanded => continue,
+                }
+            }
+            if visited >= nodes {
+                return SearchStatus::Running;
+            }
+
+            let frame = self.stack.last_mut().unwrap();
+            let Some(&movement) = frame.moves.get(frame.next) else {
+                self.stack.pop();
+                self.retreat();
+                continue;
+            };
+            frame.next += 1;
+
+            let cost = self.costs.last().copied().unwrap_or(0) + self.state.cost(movement);
+            let back = self.state.apply(movement);
+            self.path.push(movement);
+            self.undo.push(back);
+            self.costs.push(cost);
+            visited += 1;
+            match self.visit() {
+                Visit::Pruned => self.retreat(),
+                Visit::Expanded => {}
+                Visit::Solved => {
+                    let moves = self.path.clone();
+                    return self.finish(SearchStatus::Solved(moves));
+                }
+                Visit::Mismatch => {
+                    return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH))
+                }
+            }
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::json::Json;
+use crate::puzzle::{Move, Puzzle};
+use crate::schema;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Solution {
+    moves: Vec<Move>,
+}
+
+impl Solution {
+    pub fn new(moves: Vec<Move>) -> Self {
+        Self { moves }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Cells the empty space travels, a measure of physical effort for
+    /// robot solvers. Every single move slides one tile one cell, so this is
+    /// always `len`. In the multi-tile metric the two differ; see
+    /// `multi_tile::SlideCost`.
+    pub fn blank_travel(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Runs of moves in the same direction, each of which slides a line of
+    /// tiles along together and can be done as one push, as by hand or with
+    /// a robot arm.
+    pub fn pushes(&self) -> usize {
+        let continued = self
+            .moves
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .count();
+        self.moves.len() - continued
+    }
+
+    pub fn into_moves(self) -> Vec<Move> {
+        self.moves
+    }
+
+    /// The solution together with the board it solves.
+    pub fn to_json(&self, start: &Puzzle) -> Json {
+        let moves: Vec<String> = self.moves.iter().map(Move::to_string).collect();
+        schema::document(
+            "solution",
+            [
+                ("size", Json::from(start.size())),
+                ("rows", Json::from(start.rows())),
+                ("cols", Json::from(start.cols())),
+                ("start", Json::from(start.tiles())),
+                ("length", Json::from(self.len())),
+                ("moves", Json::from(moves)),
+            ],
+        )
+    }
+}
+
+impl From<Vec<Move>> for Solution {
+    fn from(moves: Vec<Move>) -> Self {
+        Self::new(moves)
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.moves.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! A process-wide cache of recent solutions, off until `enable` is called.
+//!
+//! Interactive front ends ask for a solution or a hint after nearly every
+//! move, usually for a board one move along the last solution. While the
+//! cache is on, `Puzzle::solve` and everything built on it, such as
+//! `hints::CachedHint`, look the board up first, and every solution found is
+//! stored for each board along it, so following a solution or undoing a
+//! move never searches again. The least recently used boards are dropped
+//! once the capacity is reached.
+
+use crate::puzzle::{Move, Puzzle};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+static CACHE: Mutex<Option<SolveCache>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Boards currently cached.
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Solutions keyed by board, evicting the least recently used board first.
+#[derive(Debug, Clone)]
+pub struct SolveCache {
+    /// Each board's solution is a suffix of a stored one, shared by every
+    /// board along it.
+    entries: HashMap<Puzzle, Entry>,
+    /// Boards by the time of their last use, oldest first.
+    recency: BTreeMap<u64, Puzzle>,
+    clock: u64,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    moves: Arc<[Move]>,
+    start: usize,
+    used: u64,
+}
+
+impl SolveCache {
+    /// A cache holding up to `capacity` boards. A capacity of 0 caches
+    /// nothing but still counts misses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached solution for `puzzle`, counted as a hit or a miss.
+    pub fn get(&mut self, puzzle: &Puzzle) -> Option<Vec<Move>> {
+        let Some(entry) = self.entries.get_mut(puzzle) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        self.clock += 1;
+        let board = self
+            .recency
+            .remove(&entry.used)
+            .expect("Every entry is in the recency order");
+        entry.used = self.clock;
+        self.recency.insert(self.clock, board);
+        Some(entry.moves[entry.start..].to_vec())
+    }
+
+    /// Stores `moves` as the solution of `puzzle` and of every board along
+    /// it, evicting the least recently used boards to make room. Boards
+    /// already cached keep their solutions.
+    pub fn insert(&mut self, puzzle: &Puzzle, moves: &[Move]) {
+        let shared: Arc<[Move]> = moves.into();
+        let mut board = puzzle.clone();
+        for start in 0..=moves.len() {
+            if self.capacity == 0 {
+                return;
+            }
+            if !self.entries.contains_key(&board) {
+                while self.entries.len() >= self.capacity {
+                    self.evict();
+                }
+                self.clock += 1;
+                self.recency.insert(self.clock, board.clone());
+                self.entries.insert(
+                    board.clone(),
+                    Entry {
+                        moves: Arc::clone(&shared),
+                        start,
+                        used: self.clock,
+                    },
+                );
+            }
+            if let Some(&movement) = moves.get(start) {
+                if !board.apply_move(movement) {
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            len: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    fn evict(&mut self) {
+        if let Some((_, board)) = self.recency.pop_first() {
+            self.entries.remove(&board);
+        }
+    }
+}
+
+/// Turns the process-wide cache on with room for `capacity` boards,
+/// replacing any earlier one along with its contents and stats.
+pub fn enable(capacity: usize) {
+    *lock() = Some(SolveCache::new(capacity));
+}
+
+/// Turns the cache off and frees its contents.
+pub fn disable() {
+    *lock() = None;
+}
+
+/// Empties the cache, keeping it on and its stats running.
+pub fn clear() {
+    if let Some(cache) = lock().as_mut() {
+        cache.clear();
+    }
+}
+
+/// Hits, misses and size of the cache, or `None` while it is off.
+pub fn stats() -> Option<CacheStats> {
+    lock().as_ref().map(SolveCache::stats)
+}
+
+/// The cached solution for `puzzle`, or `None` on a miss or while the cache
+/// is off.
+pub(crate) fn get(puzzle: &Puzzle) -> Option<Vec<Move>> {
+    lock().as_mut()?.get(puzzle)
+}
+
+/// Stores a solution while the cache is on.
+pub(crate) fn insert(puzzle: &Puzzle, moves: &[Move]) {
+    if let Some(cache) = lock().as_mut() {
+        cache.insert(puzzle, moves);
+    }
+}
+
+fn lock() -> MutexGuard<'static, Option<SolveCache>> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,173 @@
+//! A common interface over the crate's search engines, so callers can swap
+//! algorithms without changing how they call them.
+
+use crate::adaptive::Budget;
+use crate::astar::AStarSearch;
+use crate::external::ExternalOptions;
+use crate::heuristic::{self, Heuristic};
+use crate::pdb::PatternDatabase;
+use crate::puzzle::{Move, Puzzle};
+use crate::registry::{DEFAULT_BEAM_WIDTH, DEFAULT_WEIGHT};
+use crate::search::{self, IdaStarSearch};
+use crate::solution::Solution;
+use std::sync::Arc;
+
+pub trait Solver {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str>;
+}
+
+/// Plain functions and closures can be used wherever a solver is expected.
+impl<F> Solver for F
+where
+    F: Fn(&Puzzle) -> Result<Vec<Move>, &'static str>,
+{
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        self(puzzle).map(Solution::new)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdaStar {
+    /// Memory for a transposition table, or 0 for none; see
+    /// `IdaStarSearch::with_transposition_table`.
+    pub table_bytes: usize,
+    /// Searches with this database's estimate, which is admissible and much
+    /// stronger on 4x4 boards, and the Manhattan distance on other boards.
+    pub pattern_database: Option<Arc<PatternDatabase>>,
+    /// Searches with the estimate of this name from `heuristic::NAMES`
+    /// instead, overriding `pattern_database`, except that
+    /// `pattern-database` reads `pattern_database` when it is set rather
+    /// than the installed database.
+    pub heuristic: Option<&'static str>,
+}
+
+impl IdaStar {
+    /// The search this solver runs, for callers that step it themselves.
+    pub fn search(&self, puzzle: &Puzzle) -> Result<IdaStarSearch, &'static str> {
+        let search = match (self.heuristic, &self.pattern_database) {
+            (None | Some("pattern-database"), Some(database)) => {
+                let database = Arc::clone(database);
+                IdaStarSearch::with_heuristic(puzzle, move |puzzle: &Puzzle| {
+                    database
+                        .estimate(puzzle)
+                        .unwrap_or_else(|| puzzle.manhattan_distance())
+                })?
+            }
+            (Some(name), _) => IdaStarSearch::with_heuristic(puzzle, heuristic_named(name)?)?,
+            (None, None) => IdaStarSearch::new(puzzle)?,
+        };
+        Ok(search.with_transposition_table(self.table_bytes))
+    }
+}
+
+impl Solver for IdaStar {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        search::run(self.search(puzzle)?, |_| true).map(Solution::new)
+    }
+}
+
+/// Classic A* with a closed set, see `Puzzle::solve_astar`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AStar {
+    /// Searches with the estimate of this name instead, as `IdaStar` does.
+    pub heuristic: Option<&'static str>,
+}
+
+impl AStar {
+    /// The search this solver runs, for callers that step it themselves.
+    pub fn search(&self, puzzle: &Puzzle) -> Result<AStarSearch, &'static str> {
+        if let Some(name) = self.heuristic {
+            AStarSearch::with_heuristic(puzzle, heuristic_named(name)?)
+        } else {
+            AStarSearch::new(puzzle)
+        }
+    }
+}
+
+impl Solver for AStar {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        search::run(self.search(puzzle)?, |_| true).map(Solution::new)
+    }
+}
+
+fn heuristic_named(name: &str) -> Result<Box<dyn Heuristic>, &'static str> {
+    heuristic::by_name(name).ok_or("Unknown heuristic")
+}
+
+/// Breadth-first search from both ends, see `Puzzle::solve_bidirectional`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bidirectional;
+
+impl Solver for Bidirectional {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        puzzle.solve_bidirectional().map(Solution::new)
+    }
+}
+
+/// Breadth-first search with its layers on disk, see
+/// `Puzzle::solve_astar_external`.
+#[derive(Debug, Clone, Default)]
+pub struct External {
+    pub options: ExternalOptions,
+}
+
+impl Solver for External {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        puzzle
+            .solve_astar_external(&self.options)
+            .map(Solution::new)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedAStar {
+    pub weight: f64,
+}
+
+impl Default for WeightedAStar {
+    fn default() -> Self {
+        Self {
+            weight: DEFAULT_WEIGHT,
+        }
+    }
+}
+
+impl Solver for WeightedAStar {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        puzzle.solve_weighted(self.weight).map(Solution::new)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Beam {
+    pub width: usize,
+}
+
+impl Default for Beam {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_BEAM_WIDTH,
+        }
+    }
+}
+
+impl Solver for Beam {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        puzzle.solve_beam(self.width).map(Solution::new)
+    }
+}
+
+/// IDA* that falls back to faster suboptimal engines when it would overrun
+/// its budget, see `Puzzle::solve_adaptive_within`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adaptive {
+    pub budget: Budget,
+}
+
+impl Solver for Adaptive {
+    fn solve(&self, puzzle: &Puzzle) -> Result<Solution, &'static str> {
+        puzzle
+            .solve_adaptive_within(self.budget)
+            .map(|solution| Solution::new(solution.moves))
+    }
+}
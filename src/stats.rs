@@ -0,0 +1,163 @@
+use crate::progress::ProgressTracker;
+use crate::puzzle::{Move, Puzzle};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Profile of one IDA* search, for comparing pruning techniques by more than
+/// their total node count.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolveStats {
+    pub nodes: u64,
+    /// Nodes visited in each bound iteration.
+    pub iteration_nodes: Vec<u64>,
+    /// Expanded nodes at each depth, summed over all iterations.
+    pub depth_nodes: Vec<u64>,
+    /// Expanded nodes by heuristic value, summed over all iterations.
+    pub heuristic_nodes: Vec<u64>,
+    /// The branching factor `b` of a uniform tree as deep as the solution
+    /// with as many nodes as the search visited, i.e. `1 + b + ... + b^d = nodes`.
+    /// `None` if no solution was found or the start was already solved.
+    pub effective_branching_factor: Option<f64>,
+    pub elapsed: Duration,
+}
+
+impl SolveStats {
+    pub(crate) fn from_tracker(tracker: &ProgressTracker, solution_length: Option<usize>) -> Self {
+        let profile = tracker.profile().cloned().unwrap_or_default();
+        let nodes = tracker.nodes();
+        Self {
+            nodes,
+            iteration_nodes: tracker.iteration_nodes().to_vec(),
+            depth_nodes: profile.depth_nodes,
+            heuristic_nodes: profile.heuristic_nodes,
+            effective_branching_factor: solution_length
+                .filter(|&depth| depth > 0)
+                .map(|depth| effective_branching_factor(nodes, depth)),
+            elapsed: tracker.elapsed(),
+        }
+    }
+}
+
+/// Solves `1 + b + ... + b^depth = nodes` for `b` by bisection.
+fn effective_branching_factor(nodes: u64, depth: usize) -> f64 {
+    let tree_size = |b: f64| (0..=depth).map(|i| b.powi(i as i32)).sum::<f64>();
+    let (mut low, mut high) = (0.0, nodes as f64);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if tree_size(mid) < nodes as f64 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nodes: {} in {:.2?}", self.nodes, self.elapsed)?;
+        if let Some(branching) = self.effective_branching_factor {
+            writeln!(f, "Effective branching factor: {:.3}", branching)?;
+        }
+        writeln!(f, "Nodes per iteration: {:?}", self.iteration_nodes)?;
+        writeln!(f, "Expanded nodes per depth: {:?}", self.depth_nodes)?;
+        write!(
+            f,
+            "Expanded nodes per heuristic value: {:?}",
+            self.heuristic_nodes
+        )
+    }
+}
+
+/// Totals for one board size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SizeStats {
+    pub solves: u64,
+    pub failures: u64,
+    /// Sum of solution lengths over successful solves.
+    pub total_length: u64,
+    /// Time spent on all attempts, successful or not.
+    pub total_time: Duration,
+}
+
+impl SizeStats {
+    pub fn attempts(&self) -> u64 {
+        self.solves + self.failures
+    }
+
+    pub fn average_length(&self) -> Option<f64> {
+        (self.solves > 0).then(|| self.total_length as f64 / self.solves as f64)
+    }
+
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.attempts() > 0).then(|| self.total_time / self.attempts() as u32)
+    }
+}
+
+/// In-process usage counters for applications embedding the crate. Nothing
+/// is collected unless the application records outcomes itself, and the
+/// crate never reads or reports these numbers anywhere.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CrateStats {
+    pub sizes: BTreeMap<usize, SizeStats>,
+    /// How often each solver error occurred.
+    pub failure_reasons: BTreeMap<&'static str, u64>,
+}
+
+impl CrateStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the outcome of one solve that took `elapsed`.
+    pub fn record(
+        &mut self,
+        puzzle: &Puzzle,
+        outcome: &Result<Vec<Move>, &'static str>,
+        elapsed: Duration,
+    ) {
+        let size = self.sizes.entry(puzzle.size()).or_default();
+        size.total_time += elapsed;
+        match outcome {
+            Ok(moves) => {
+                size.solves += 1;
+                size.total_length += moves.len() as u64;
+            }
+            Err(err) => {
+                size.failures += 1;
+                *self.failure_reasons.entry(err).or_default() += 1;
+            }
+        }
+    }
+
+    /// Folds in counters collected elsewhere, e.g. on another thread.
+    pub fn merge(&mut self, other: &CrateStats) {
+        for (&size, stats) in &other.sizes {
+            let ours = self.sizes.entry(size).or_default();
+            ours.solves += stats.solves;
+            ours.failures += stats.failures;
+            ours.total_length += stats.total_length;
+            ours.total_time += stats.total_time;
+        }
+        for (&reason, &count) in &other.failure_reasons {
+            *self.failure_reasons.entry(reason).or_default() += count;
+        }
+    }
+
+    /// Totals across all board sizes.
+    pub fn total(&self) -> SizeStats {
+        self.sizes
+            .values()
+            .fold(SizeStats::default(), |total, stats| SizeStats {
+                solves: total.solves + stats.solves,
+                failures: total.failures + stats.failures,
+                total_length: total.total_length + stats.total_length,
+                total_time: total.total_time + stats.total_time,
+            })
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,39 @@
+//! Proptest strategies for boards, for property tests of solvers.
+//!
+//! Boards are generated as a `repro::Scramble`, a size and a walk from the
+//! solved board, so when a property fails proptest shrinks toward fewer
+//! rows and columns and shorter walks: small boards a few moves from
+//! solved, which are the easiest failures to follow by hand.
+
+use crate::puzzle::{Move, Puzzle};
+use crate::repro::Scramble;
+use proptest::prelude::*;
+
+/// Scrambles of boards from 2x2 up to `max_rows` by `max_cols`, walked up
+/// to `max_moves` moves from solved.
+pub fn scrambles(
+    max_rows: usize,
+    max_cols: usize,
+    max_moves: usize,
+) -> impl Strategy<Value = Scramble> {
+    (
+        2..=max_rows.max(2),
+        2..=max_cols.max(2),
+        proptest::collection::vec(0..Move::ALL.len(), 0..=max_moves),
+    )
+        .prop_map(|(rows, cols, moves)| Scramble {
+            rows,
+            cols,
+            moves: moves.into_iter().map(|index| Move::ALL[index]).collect(),
+        })
+}
+
+/// Solvable boards generated by `scrambles`.
+pub fn puzzles(
+    max_rows: usize,
+    max_cols: usize,
+    max_moves: usize,
+) -> impl Strategy<Value = Puzzle> {
+    scrambles(max_rows, max_cols, max_moves)
+        .prop_map(|scramble| scramble.puzzle().expect("scrambles are at least 2x2"))
+}
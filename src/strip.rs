@@ -0,0 +1,225 @@
+//! Dedicated solver for boards with two rows and any number of columns.
+//!
+//! Columns are fixed from left to right: a small search places the two tiles
+//! of the leftmost unsolved column while treating every other tile as
+//! interchangeable, which is cheap however wide the strip is. The last three
+//! columns are then solved exactly. Solutions are short but not guaranteed to
+//! be optimal.
+
+use crate::puzzle::Move;
+use std::collections::{HashMap, VecDeque};
+
+const ROWS: usize = 2;
+
+/// Number of columns left for the exact endgame search.
+const ENDGAME_COLS: usize = 3;
+
+/// Solves a 2 x `cols` board given row by row, with 0 for the empty space.
+/// The goal is the usual ordering with the empty space bottom-right.
+pub fn solve_strip(cols: usize, tiles: &[u32]) -> Result<Vec<Move>, &'static str> {
+    if cols == 0 {
+        return Err("Board size must be positive");
+    }
+    if ROWS.checked_mul(cols) != Some(tiles.len()) {
+        return Err("Wrong number of tiles for board size");
+    }
+    let mut seen = vec![false; tiles.len()];
+    for &value in tiles {
+        match seen.get_mut(value as usize) {
+            Some(slot) if !*slot => *slot = true,
+            Some(_) => return Err("Duplicate tile"),
+            None => return Err("Tile value out of range"),
+        }
+    }
+    if !is_solvable(cols, tiles) {
+        return Err("Puzzle is not solvable");
+    }
+
+    let mut strip = Strip {
+        cols,
+        board: tiles.to_vec(),
+        blank: tiles.iter().position(|&value| value == 0).unwrap(),
+        moves: Vec::new(),
+    };
+
+    for col in 0..cols.saturating_sub(ENDGAME_COLS) {
+        strip.place_column(col)?;
+    }
+    strip.endgame(cols.saturating_sub(ENDGAME_COLS))?;
+
+    Ok(strip.moves)
+}
+
+fn is_solvable(cols: usize, tiles: &[u32]) -> bool {
+    let inversions: usize = tiles
+        .iter()
+        .enumerate()
+        .filter(|&(_, &value)| value != 0)
+        .map(|(i, &value)| {
+            tiles[i + 1..]
+                .iter()
+                .filter(|&&next| next != 0 && next < value)
+                .count()
+        })
+        .sum();
+    let blank_row = tiles.iter().position(|&value| value == 0).unwrap() / cols;
+
+    if cols % 2 == 1 {
+        inversions.is_multiple_of(2)
+    } else {
+        (inversions + blank_row) % 2 == 1
+    }
+}
+
+fn goal_value(cols: usize, index: usize) -> u32 {
+    if index == ROWS * cols - 1 {
+        0
+    } else {
+        index as u32 + 1
+    }
+}
+
+struct Strip {
+    cols: usize,
+    board: Vec<u32>,
+    blank: usize,
+    moves: Vec<Move>,
+}
+
+impl Strip {
+    fn apply(&mut self, movement: Move) {
+        let (dx, dy) = movement.as_offset();
+        let row = (self.blank / self.cols).wrapping_add_signed(dx);
+        let col = (self.blank % self.cols).wrapping_add_signed(dy);
+        let target = row * self.cols + col;
+        self.board.swap(self.blank, target);
+        self.blank = target;
+        self.moves.push(movement);
+    }
+
+    /// Brings the two goal tiles of column `first` into place using only the
+    /// columns from `first` onwards, by a breadth-first search over the
+    /// positions of those two tiles and the empty space.
+    fn place_column(&mut self, first: usize) -> Result<(), &'static str> {
+        let width = self.cols - first;
+        let cells = ROWS * width;
+        let to_region = |index: usize| (index / self.cols) * width + index % self.cols - first;
+
+        let top = goal_value(self.cols, first);
+        let bottom = goal_value(self.cols, self.cols + first);
+        let position = |value| self.board.iter().position(|&v| v == value).unwrap();
+        let encode =
+            |top: usize, bottom: usize, blank: usize| (top * cells + bottom) * cells + blank;
+
+        let start = encode(
+            to_region(position(top)),
+            to_region(position(bottom)),
+            to_region(self.blank),
+        );
+        let done = |state: usize| state / cells == width;
+        if done(start) {
+            return Ok(());
+        }
+
+        // How each state was first reached; NONE marks unvisited states.
+        const NONE: u8 = u8::MAX;
+        let mut via = vec![NONE; cells * cells * cells];
+        let mut queue = VecDeque::from([start]);
+        via[start] = 0;
+        let mut found = None;
+
+        while let Some(state) = queue.pop_front() {
+            if done(state) {
+                found = Some(state);
+                break;
+            }
+            let (top, bottom, blank) =
+                (state / cells / cells, state / cells % cells, state % cells);
+            for (i, movement) in Move::ALL.iter().enumerate() {
+                let Some(next_blank) = step(width, blank, *movement) else {
+                    continue;
+                };
+                let follow = |tile: usize| if tile == next_blank { blank } else { tile };
+                let next = encode(follow(top), follow(bottom), next_blank);
+                if via[next] == NONE {
+                    via[next] = i as u8;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut state = found.ok_or("No solution found")?;
+        let mut path = Vec::new();
+        while state != start {
+            let movement = Move::ALL[via[state] as usize];
+            let (top, bottom, blank) =
+                (state / cells / cells, state / cells % cells, state % cells);
+            let previous_blank = step(width, blank, movement.opposite()).unwrap();
+            let back = |tile: usize| if tile == previous_blank { blank } else { tile };
+            state = encode(back(top), back(bottom), previous_blank);
+            path.push(movement);
+        }
+
+        for &movement in path.iter().rev() {
+            self.apply(movement);
+        }
+        Ok(())
+    }
+
+    /// Solves the columns from `first` onwards exactly.
+    fn endgame(&mut self, first: usize) -> Result<(), &'static str> {
+        let width = self.cols - first;
+        let region = |board: &[u32]| -> Vec<u32> {
+            (0..ROWS)
+                .flat_map(|row| board[row * self.cols + first..(row + 1) * self.cols].to_vec())
+                .collect()
+        };
+        let start = region(&self.board);
+        let goal: Vec<u32> = (0..ROWS)
+            .flat_map(|row| (first..self.cols).map(move |col| (row, col)))
+            .map(|(row, col)| goal_value(self.cols, row * self.cols + col))
+            .collect();
+
+        let mut via: HashMap<Vec<u32>, Option<Move>> = HashMap::from([(start.clone(), None)]);
+        let mut queue = VecDeque::from([start.clone()]);
+        while let Some(state) = queue.pop_front() {
+            if state == goal {
+                break;
+            }
+            let blank = state.iter().position(|&value| value == 0).unwrap();
+            for movement in Move::ALL {
+                if let Some(target) = step(width, blank, movement) {
+                    let mut next = state.clone();
+                    next.swap(blank, target);
+                    if !via.contains_key(&next) {
+                        via.insert(next.clone(), Some(movement));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let mut state = goal;
+        let mut path = Vec::new();
+        while let Some(movement) = *via.get(&state).ok_or("No solution found")? {
+            let blank = state.iter().position(|&value| value == 0).unwrap();
+            let previous = step(width, blank, movement.opposite()).unwrap();
+            state.swap(blank, previous);
+            path.push(movement);
+        }
+
+        for &movement in path.iter().rev() {
+            self.apply(movement);
+        }
+        Ok(())
+    }
+}
+
+/// Where the empty space at `blank` goes after `movement` on a board `width`
+/// columns wide, or `None` if the move leaves the board.
+fn step(width: usize, blank: usize, movement: Move) -> Option<usize> {
+    let (dx, dy) = movement.as_offset();
+    let row = (blank / width).checked_add_signed(dx)?;
+    let col = (blank % width).checked_add_signed(dy)?;
+    (row < ROWS && col < width).then_some(row * width + col)
+}
@@ -0,0 +1,295 @@
+use crate::progress::{Progress, ProgressTracker};
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, Search, SearchStatus, HEURISTIC_GOAL_MISMATCH};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Upper limit on stored states for the suboptimal engines, to keep memory bounded.
+const MAX_NODES: usize = 5_000_000;
+
+struct Node {
+    parent: Option<usize>,
+    movement: Option<Move>,
+    g: usize,
+}
+
+impl Puzzle {
+    /// Weighted A*: expands nodes by `g + weight * h`. With `weight > 1` the
+    /// search is much faster but the solution may be up to `weight` times
+    /// longer than optimal.
+    pub fn solve_weighted(&self, weight: f64) -> Result<Vec<Move>, &'static str> {
+        self.solve_weighted_with_progress(weight, |_| true)
+    }
+
+    /// Like `solve_weighted`, reporting progress to `callback`; returning
+    /// `false` from the callback cancels the search.
+    pub fn solve_weighted_with_progress<F>(
+        &self,
+        weight: f64,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(WeightedAStarSearch::new(self, weight)?, callback)
+    }
+
+    /// Beam search: a breadth-first search that only keeps the `width` states
+    /// with the lowest heuristic at each depth. Fast and memory-bounded, but
+    /// with no guarantee on solution length (or of finding one at all).
+    pub fn solve_beam(&self, width: usize) -> Result<Vec<Move>, &'static str> {
+        self.solve_beam_with_progress(width, |_| true)
+    }
+
+    /// Like `solve_beam`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_beam_with_progress<F>(
+        &self,
+        width: usize,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(BeamSearch::new(self, width)?, callback)
+    }
+}
+
+/// Resumable state of a weighted A* search; see `Puzzle::solve_weighted`.
+pub struct WeightedAStarSearch {
+    weight: f64,
+    nodes: Vec<Node>,
+    states: Vec<Puzzle>,
+    best_g: HashMap<Puzzle, usize>,
+    open: BinaryHeap<(Reverse<u64>, usize)>,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus>,
+}
+
+impl WeightedAStarSearch {
+    pub fn new(start: &Puzzle, weight: f64) -> Result<Self, &'static str> {
+        if !start.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+
+        let mut best_g = HashMap::new();
+        best_g.insert(start.clone(), 0);
+        let mut open = BinaryHeap::new();
+        open.push((Reverse(priority(0, start.heuristic(), weight)), 0));
+
+        Ok(Self {
+            weight,
+            nodes: vec![Node {
+                parent: None,
+                movement: None,
+                g: 0,
+            }],
+            states: vec![start.clone()],
+            best_g,
+            open,
+            tracker: ProgressTracker::new(),
+            outcome: None,
+        })
+    }
+
+    fn finish(&mut self, status: SearchStatus) -> SearchStatus {
+        self.outcome = Some(status.clone());
+        status
+    }
+}
+
+impl Search for WeightedAStarSearch {
+    fn step(&mut self, nodes: u64) -> SearchStatus {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        while visited < nodes {
+            let Some((_, index)) = self.open.pop() else {
+                return self.finish(SearchStatus::Failed("No solution found"));
+            };
+            let state = self.states[index].clone();
+            let g = self.nodes[index].g;
+            if g > self.best_g[&state] {
+                // A shorter path to this state was found after it was queued
+                continue;
+            }
+            self.tracker.node();
+            visited += 1;
+            if state.is_solved() {
+                if state.heuristic() != 0 {
+                    return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+                }
+                return self.finish(SearchStatus::Solved(trace_path(&self.nodes, index)));
+            }
+            if self.nodes.len() > MAX_NODES {
+                return self.finish(SearchStatus::Failed("Node limit exceeded"));
+            }
+
+            for dir in Move::ALL {
+                let mut next = state.clone();
+                if !next.apply_move(dir) {
+                    continue;
+                }
+                if self.best_g.get(&next).is_some_and(|&seen| seen <= g + 1) {
+                    continue;
+                }
+
+                self.nodes.push(Node {
+                    parent: Some(index),
+                    movement: Some(dir),
+                    g: g + 1,
+                });
+                let h = next.heuristic();
+                if h == 0 && !next.is_solved() {
+                    return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+                }
+                let priority = priority(g + 1, h, self.weight);
+                self.best_g.insert(next.clone(), g + 1);
+                self.states.push(next);
+                self.open.push((Reverse(priority), self.nodes.len() - 1));
+            }
+        }
+
+        SearchStatus::Running
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
+
+/// Resumable state of a beam search; see `Puzzle::solve_beam`.
+pub struct BeamSearch {
+    width: usize,
+    nodes: Vec<Node>,
+    seen: HashSet<Puzzle>,
+    layer: Vec<(usize, Puzzle)>,
+    /// How many states of `layer` have been expanded into `candidates`.
+    expanded: usize,
+    candidates: Vec<(usize, usize, Move, Puzzle)>,
+    tracker: ProgressTracker,
+    outcome: Option<SearchStatus>,
+}
+
+impl BeamSearch {
+    pub fn new(start: &Puzzle, width: usize) -> Result<Self, &'static str> {
+        if !start.is_current_state_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        if width == 0 {
+            return Err("Beam width must be positive");
+        }
+
+        Ok(Self {
+            width,
+            nodes: vec![Node {
+                parent: None,
+                movement: None,
+                g: 0,
+            }],
+            seen: HashSet::from([start.clone()]),
+            layer: vec![(0, start.clone())],
+            expanded: 0,
+            candidates: Vec::new(),
+            tracker: ProgressTracker::new(),
+            outcome: None,
+        })
+    }
+
+    fn finish(&mut self, status: SearchStatus) -> SearchStatus {
+        self.outcome = Some(status.clone());
+        status
+    }
+
+    /// Keeps the best candidates as the next layer.
+    fn next_layer(&mut self) {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        candidates.sort_by_key(|&(h, ..)| h);
+        candidates.truncate(self.width);
+
+        self.layer = candidates
+            .into_iter()
+            .map(|(_, parent, dir, state)| {
+                self.nodes.push(Node {
+                    parent: Some(parent),
+                    movement: Some(dir),
+                    g: self.nodes[parent].g + 1,
+                });
+                (self.nodes.len() - 1, state)
+            })
+            .collect();
+        self.expanded = 0;
+    }
+}
+
+impl Search for BeamSearch {
+    fn step(&mut self, nodes: u64) -> SearchStatus {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let mut visited = 0;
+        loop {
+            if self.expanded == 0 {
+                if self.layer.is_empty() {
+                    return self.finish(SearchStatus::Failed("No solution found"));
+                }
+                if let Some((index, state)) = self.layer.iter().find(|(_, state)| state.is_solved())
+                {
+                    if state.heuristic() != 0 {
+                        return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+                    }
+                    let moves = trace_path(&self.nodes, *index);
+                    return self.finish(SearchStatus::Solved(moves));
+                }
+                if self.nodes.len() > MAX_NODES {
+                    return self.finish(SearchStatus::Failed("Node limit exceeded"));
+                }
+            }
+
+            if self.expanded == self.layer.len() {
+                self.next_layer();
+                continue;
+            }
+            if visited >= nodes {
+                return SearchStatus::Running;
+            }
+
+            let (index, state) = &self.layer[self.expanded];
+            self.tracker.node();
+            visited += 1;
+            for dir in Move::ALL {
+                let mut next = state.clone();
+                if next.apply_move(dir) && self.seen.insert(next.clone()) {
+                    let h = next.heuristic();
+                    if h == 0 && !next.is_solved() {
+                        return self.finish(SearchStatus::Failed(HEURISTIC_GOAL_MISMATCH));
+                    }
+                    self.candidates.push((h, *index, dir, next));
+                }
+            }
+            self.expanded += 1;
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        self.tracker.progress()
+    }
+}
+
+fn priority(g: usize, h: usize, weight: f64) -> u64 {
+    // Scaled to an integer so the priority queue has a total order
+    ((g as f64 + weight * h as f64) * 1000.0) as u64
+}
+
+fn trace_path(nodes: &[Node], mut index: usize) -> Vec<Move> {
+    let mut moves = Vec::new();
+    while let (Some(parent), Some(dir)) = (nodes[index].parent, nodes[index].movement) {
+        moves.push(dir);
+        index = parent;
+    }
+    moves.reverse();
+    moves
+}
@@ -0,0 +1,234 @@
+use crate::puzzle::{Move, Puzzle};
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const SIZE: usize = 3;
+const CELLS: usize = SIZE * SIZE;
+/// 9! permutations of the cells, half of which are reachable from any goal.
+const STATES: usize = 362_880;
+const UNREACHABLE: u8 = u8::MAX;
+
+/// One distance table per blank cell of the target arrangement, built on first use.
+static TABLES: [OnceLock<Vec<u8>>; CELLS] = [const { OnceLock::new() }; CELLS];
+
+/// Exact number of moves between two 3x3 states, or `None` if either board
+/// is not 3x3 or `b` cannot be reached from `a`.
+///
+/// Tiles are relabelled so that `b` becomes the ordered arrangement with its
+/// blank in the same cell, which lets the query be answered from a table of
+/// distances to that arrangement.
+pub fn distance_between(a: &Puzzle, b: &Puzzle) -> Option<usize> {
+    let a = cells(a)?;
+    let b = cells(b)?;
+    let blank = b.iter().position(|&value| value == 0)?;
+    let target = ordered_with_blank(blank);
+
+    // relabel[value in b] = value at the same cell of the target
+    let mut relabel = [0; CELLS];
+    for cell in 0..CELLS {
+        relabel[b[cell] as usize] = target[cell];
+    }
+    let relative = a.map(|value| relabel[value as usize]);
+
+    let distance = table(blank)[rank(&relative)];
+    (distance != UNREACHABLE).then_some(distance as usize)
+}
+
+/// Exact number of moves from a 3x3 state to its goal.
+pub fn distance_to_goal(puzzle: &Puzzle) -> Option<usize> {
+    distance_between(puzzle, &puzzle.goal())
+}
+
+/// Identifies a saved table and its layout.
+const MAGIC: &[u8; 5] = b"SPTB1";
+/// The blank cell of the standard goal.
+const GOAL_BLANK: usize = CELLS - 1;
+
+/// Distances to the standard 3x3 goal in a form that can be saved to disk,
+/// so short-lived processes can load the table instead of rebuilding it.
+///
+/// Only the reachable half of the states is stored, one byte each, indexed
+/// by `compact_rank`.
+pub struct Tablebase {
+    distances: Vec<u8>,
+}
+
+impl Tablebase {
+    pub fn build() -> Self {
+        let distances = breadth_first(ordered_with_blank(GOAL_BLANK), compact_rank, STATES / 2);
+        Self { distances }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let distances = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| invalid("Not a tablebase file"))?;
+        if distances.len() != STATES / 2 {
+            return Err(invalid("Tablebase file has the wrong length"));
+        }
+        if distances.contains(&UNREACHABLE) {
+            return Err(invalid("Tablebase file is corrupt"));
+        }
+        Ok(Self {
+            distances: distances.to_vec(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + self.distances.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.distances);
+        fs::write(path, bytes)
+    }
+
+    /// Loads the table from `path`, or builds it and tries to save it there
+    /// if the file is missing or unreadable. A failed save is ignored, as the
+    /// table is still usable.
+    pub fn load_or_build(path: &Path) -> Self {
+        Self::load(path).unwrap_or_else(|_| {
+            let table = Self::build();
+            let _ = table.save(path);
+            table
+        })
+    }
+
+    /// Exact number of moves to the goal, or `None` if the board is not 3x3
+    /// or cannot be solved. The table only holds distances to the standard
+    /// goal, so boards with a custom goal are answered by `distance_to_goal`.
+    pub fn distance(&self, puzzle: &Puzzle) -> Option<usize> {
+        if !puzzle.has_standard_goal() {
+            return distance_to_goal(puzzle);
+        }
+        let cells = cells(puzzle)?;
+        if !puzzle.is_current_state_solvable() {
+            return None;
+        }
+        Some(self.distances[compact_rank(&cells)] as usize)
+    }
+
+    /// Every move that starts a shortest solution, empty if the board is
+    /// solved or has no distance.
+    pub fn best_moves(&self, puzzle: &Puzzle) -> Vec<Move> {
+        let Some(distance) = self.distance(puzzle) else {
+            return Vec::new();
+        };
+        Move::ALL
+            .into_iter()
+            .filter(|&movement| {
+                let mut next = puzzle.clone();
+                next.apply_move(movement) && self.distance(&next) == Some(distance.wrapping_sub(1))
+            })
+            .collect()
+    }
+}
+
+fn cells(puzzle: &Puzzle) -> Option<[u8; CELLS]> {
+    if puzzle.rows() != SIZE || puzzle.cols() != SIZE {
+        return None;
+    }
+    let mut cells = [0; CELLS];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = puzzle.tile_at(i / SIZE, i % SIZE) as u8;
+    }
+    Some(cells)
+}
+
+/// Tiles 1 to 8 in reading order, skipping the blank cell.
+fn ordered_with_blank(blank: usize) -> [u8; CELLS] {
+    let mut cells = [0; CELLS];
+    let mut value = 1;
+    for (i, cell) in cells.iter_mut().enumerate() {
+        if i != blank {
+            *cell = value;
+            value += 1;
+        }
+    }
+    cells
+}
+
+fn table(blank: usize) -> &'static [u8] {
+    TABLES[blank].get_or_init(|| build_table(blank))
+}
+
+fn build_table(blank: usize) -> Vec<u8> {
+    breadth_first(ordered_with_blank(blank), rank, STATES)
+}
+
+/// Distances from `goal` to every state, stored at `index(state)` in a table
+/// of `len` entries.
+fn breadth_first(goal: [u8; CELLS], index: fn(&[u8; CELLS]) -> usize, len: usize) -> Vec<u8> {
+    let mut distances = vec![UNREACHABLE; len];
+    let mut queue = VecDeque::new();
+    distances[index(&goal)] = 0;
+    queue.push_back(goal);
+
+    while let Some(state) = queue.pop_front() {
+        let depth = distances[index(&state)];
+        let blank = state.iter().position(|&value| value == 0).unwrap();
+        let (row, col) = (blank / SIZE, blank % SIZE);
+
+        let neighbours = [
+            (row > 0).then(|| blank - SIZE),
+            (row + 1 < SIZE).then(|| blank + SIZE),
+            (col > 0).then(|| blank - 1),
+            (col + 1 < SIZE).then(|| blank + 1),
+        ];
+
+        for neighbour in neighbours.into_iter().flatten() {
+            let mut next = state;
+            next.swap(blank, neighbour);
+            let index = index(&next);
+            if distances[index] == UNREACHABLE {
+                distances[index] = depth + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Lehmer-code rank of a permutation of `0..9`.
+fn rank(cells: &[u8; CELLS]) -> usize {
+    let mut index = 0;
+    for i in 0..CELLS {
+        let smaller = cells[i + 1..]
+            .iter()
+            .filter(|&&value| value < cells[i])
+            .count();
+        index = index * (CELLS - i) + smaller;
+    }
+    index
+}
+
+/// Dense rank of a solvable state: the blank cell, then the Lehmer rank of
+/// the eight tiles halved. On a board of odd width the tiles of a solvable
+/// state form an even permutation, and swapping the last two tiles changes
+/// both the parity and the rank by one, so `2k` and `2k + 1` hold exactly
+/// one solvable arrangement.
+fn compact_rank(cells: &[u8; CELLS]) -> usize {
+    let blank = cells.iter().position(|&value| value == 0).unwrap();
+    let mut tiles = [0; CELLS - 1];
+    let mut next = 0;
+    for &value in cells {
+        if value != 0 {
+            tiles[next] = value;
+            next += 1;
+        }
+    }
+
+    let mut index = 0;
+    for i in 0..tiles.len() {
+        let smaller = tiles[i + 1..]
+            .iter()
+            .filter(|&&value| value < tiles[i])
+            .count();
+        index = index * (tiles.len() - i) + smaller;
+    }
+    blank * (STATES / CELLS / 2) + index / 2
+}
@@ -0,0 +1,112 @@
+use crate::json::Json;
+use crate::puzzle::Puzzle;
+use crate::schema;
+use crate::solution::Solution;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TileStat {
+    pub tile: u32,
+    /// Number of moves that slid this tile.
+    pub moves: usize,
+    /// Total number of cells the tile travelled.
+    pub distance: usize,
+    /// Manhattan distance between the tile's start and end cells.
+    pub net_displacement: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TileStats {
+    /// One entry per tile, ordered by tile number.
+    pub tiles: Vec<TileStat>,
+}
+
+impl Solution {
+    /// Counts how often and how far each tile moves when this solution is
+    /// applied to `start`. Returns `None` if the solution contains an illegal move.
+    pub fn tile_stats(&self, start: &Puzzle) -> Option<TileStats> {
+        let tile_count = start.rows() * start.cols() - 1;
+        let mut stats: Vec<TileStat> = (1..=tile_count as u32)
+            .map(|tile| TileStat {
+                tile,
+                ..Default::default()
+            })
+            .collect();
+
+        let mut puzzle = start.clone();
+        for &item in self.moves() {
+            let (row, col) = puzzle.moved_tile_position(item)?;
+            let stat = &mut stats[puzzle.tile_at(row, col) as usize - 1];
+            stat.moves += 1;
+            stat.distance += 1;
+            puzzle.apply_move(item);
+        }
+
+        for row in 0..start.rows() {
+            for col in 0..start.cols() {
+                let tile = start.tile_at(row, col);
+                if tile == 0 {
+                    continue;
+                }
+                let (end_row, end_col) = Self::find_tile(&puzzle, tile);
+                stats[tile as usize - 1].net_displacement =
+                    row.abs_diff(end_row) + col.abs_diff(end_col);
+            }
+        }
+
+        Some(TileStats { tiles: stats })
+    }
+
+    fn find_tile(puzzle: &Puzzle, tile: u32) -> (usize, usize) {
+        let cols = puzzle.cols();
+        (0..puzzle.rows() * cols)
+            .map(|i| (i / cols, i % cols))
+            .find(|&(row, col)| puzzle.tile_at(row, col) == tile)
+            .unwrap()
+    }
+}
+
+impl TileStats {
+    pub fn total_moves(&self) -> usize {
+        self.tiles.iter().map(|stat| stat.moves).sum()
+    }
+
+    pub fn to_json(&self) -> Json {
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|stat| {
+                Json::object([
+                    ("tile", Json::from(stat.tile)),
+                    ("moves", Json::from(stat.moves)),
+                    ("distance", Json::from(stat.distance)),
+                    ("net_displacement", Json::from(stat.net_displacement)),
+                ])
+            })
+            .collect();
+        schema::document("tile_stats", [("tiles", Json::Array(tiles))])
+    }
+
+    /// The tile that was moved most often.
+    pub fn busiest(&self) -> Option<&TileStat> {
+        self.tiles.iter().max_by_key(|stat| stat.moves)
+    }
+}
+
+impl fmt::Display for TileStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>6} {:>8} {:>4}",
+            "Tile", "Moves", "Distance", "Net"
+        )?;
+        for stat in &self.tiles {
+            writeln!(
+                f,
+                "{:>4} {:>6} {:>8} {:>4}",
+                stat.tile, stat.moves, stat.distance, stat.net_displacement
+            )?;
+        }
+        Ok(())
+    }
+}
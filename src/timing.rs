@@ -0,0 +1,111 @@
+use crate::json::Json;
+use crate::puzzle::Move;
+use crate::schema;
+use crate::solution::Solution;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingProfile {
+    /// Every move takes the same amount of time.
+    Constant { move_ms: u64 },
+    /// Moves start and finish slowly, speeding up towards the middle of the solution.
+    EaseInOut { min_ms: u64, max_ms: u64 },
+    /// Runs of identical moves are animated as one multi-tile push whose
+    /// duration grows with the number of tiles pushed.
+    DistanceBased { base_ms: u64, per_tile_ms: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedMove {
+    pub movement: Move,
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    /// Number of tiles in the push this move belongs to.
+    pub tiles: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Timeline {
+    pub moves: Vec<TimedMove>,
+    pub total_ms: u64,
+}
+
+impl Solution {
+    pub fn with_timings(&self, profile: TimingProfile) -> Timeline {
+        let moves = self.moves();
+        let mut timeline = Timeline::default();
+
+        match profile {
+            TimingProfile::Constant { move_ms } => {
+                for &movement in moves {
+                    timeline.push(movement, timeline.total_ms, move_ms, 1);
+                    timeline.total_ms += move_ms;
+                }
+            }
+            TimingProfile::EaseInOut { min_ms, max_ms } => {
+                let last = moves.len().saturating_sub(1).max(1) as f64;
+                for (i, &movement) in moves.iter().enumerate() {
+                    // 0 at both ends of the solution, 1 in the middle
+                    let t = i as f64 / last;
+                    let speed = 1.0 - (2.0 * t - 1.0).powi(2);
+                    let duration = max_ms as f64 - (max_ms.saturating_sub(min_ms)) as f64 * speed;
+                    let duration = duration.round() as u64;
+
+                    timeline.push(movement, timeline.total_ms, duration, 1);
+                    timeline.total_ms += duration;
+                }
+            }
+            TimingProfile::DistanceBased {
+                base_ms,
+                per_tile_ms,
+            } => {
+                let mut i = 0;
+                while i < moves.len() {
+                    let run = moves[i..].iter().take_while(|&&m| m == moves[i]).count();
+                    let duration = base_ms + per_tile_ms * run as u64;
+
+                    for &movement in &moves[i..i + run] {
+                        timeline.push(movement, timeline.total_ms, duration, run);
+                    }
+                    timeline.total_ms += duration;
+                    i += run;
+                }
+            }
+        }
+
+        timeline
+    }
+}
+
+impl Timeline {
+    fn push(&mut self, movement: Move, start_ms: u64, duration_ms: u64, tiles: usize) {
+        self.moves.push(TimedMove {
+            movement,
+            start_ms,
+            duration_ms,
+            tiles,
+        });
+    }
+
+    pub fn to_json(&self) -> Json {
+        let moves = self
+            .moves
+            .iter()
+            .map(|item| {
+                Json::object([
+                    ("move", Json::from(item.movement.to_string())),
+                    ("start_ms", Json::from(item.start_ms)),
+                    ("duration_ms", Json::from(item.duration_ms)),
+                    ("tiles", Json::from(item.tiles)),
+                ])
+            })
+            .collect();
+
+        schema::document(
+            "timeline",
+            [
+                ("total_ms", Json::from(self.total_ms)),
+                ("moves", Json::Array(moves)),
+            ],
+        )
+    }
+}
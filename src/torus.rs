@@ -0,0 +1,256 @@
+//! The sliding puzzle on a torus, where a tile can slide across an edge of
+//! the board into an empty space on the opposite edge.
+//!
+//! Wraparound gives the empty space a neighbour on every side, and on a
+//! board with an odd number of rows or columns it can travel once around
+//! and back to where it started in an odd number of moves, which breaks the
+//! parity rule of the flat board: every arrangement can then be solved.
+//! Distances are measured the short way round.
+
+use crate::progress::Progress;
+use crate::puzzle::{Move, MoveSet, Puzzle};
+use crate::search::{self, SearchState, StateSearch};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TorusBoard {
+    rows: usize,
+    cols: usize,
+    /// Tiles row by row, with 0 for the empty space.
+    cells: Vec<u32>,
+    blank: (usize, usize),
+}
+
+impl TorusBoard {
+    /// The solved board, with the empty space in the bottom right corner.
+    pub fn new(rows: usize, cols: usize) -> Result<Self, &'static str> {
+        Ok(Self::from_puzzle(&Puzzle::new_rectangular(rows, cols)?))
+    }
+
+    /// Builds a board from its tiles listed row by row, with 0 for the
+    /// empty space, as `Puzzle::from_tiles_rectangular` does.
+    pub fn from_tiles(rows: usize, cols: usize, tiles: &[u32]) -> Result<Self, &'static str> {
+        Ok(Self::from_puzzle(&Puzzle::from_tiles_rectangular(
+            rows, cols, tiles,
+        )?))
+    }
+
+    /// The same arrangement as `puzzle`, played with wraparound. The goal is
+    /// always the standard one.
+    pub fn from_puzzle(puzzle: &Puzzle) -> Self {
+        let (rows, cols) = (puzzle.rows(), puzzle.cols());
+        let cells = (0..rows * cols)
+            .map(|cell| puzzle.tile_at(cell / cols, cell % cols))
+            .collect();
+        Self {
+            rows,
+            cols,
+            cells,
+            blank: puzzle.blank_position(),
+        }
+    }
+
+    /// The same arrangement as a flat board.
+    pub fn to_puzzle(&self) -> Puzzle {
+        Puzzle::from_tiles_rectangular(self.rows, self.cols, &self.cells)
+            .expect("A torus board holds a valid arrangement")
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn tile_at(&self, row: usize, col: usize) -> u32 {
+        self.cells[row * self.cols + col]
+    }
+
+    pub fn blank_position(&self) -> (usize, usize) {
+        self.blank
+    }
+
+    /// Row and column of the tile that `movement` would slide, wrapping
+    /// across the edges, or `None` on a board a single cell across in that
+    /// direction.
+    pub fn moved_tile_position(&self, movement: Move) -> Option<(usize, usize)> {
+        let (dx, dy) = movement.as_offset();
+        let (row, col) = self.blank;
+        let row = wrap(row, dx, self.rows);
+        let col = wrap(col, dy, self.cols);
+        ((row, col) != self.blank).then_some((row, col))
+    }
+
+    /// Moves in `Move::ALL` order that slide a tile. Every direction is
+    /// legal unless the board is a single cell across.
+    pub fn legal_moves(&self) -> MoveSet {
+        Move::ALL
+            .into_iter()
+            .filter(|&movement| self.moved_tile_position(movement).is_some())
+            .collect()
+    }
+
+    /// Slides a tile into the empty space, returning whether the move was
+    /// legal.
+    pub fn apply_move(&mut self, movement: Move) -> bool {
+        let Some((row, col)) = self.moved_tile_position(movement) else {
+            return false;
+        };
+        let (blank_row, blank_col) = self.blank;
+        self.cells
+            .swap(blank_row * self.cols + blank_col, row * self.cols + col);
+        self.blank = (row, col);
+        true
+    }
+
+    /// Applies all of `moves` or none of them. On failure, returns the
+    /// position of the first illegal move.
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), usize> {
+        for (index, &movement) in moves.iter().enumerate() {
+            if !self.apply_move(movement) {
+                for &done in moves[..index].iter().rev() {
+                    self.apply_move(done.opposite());
+                }
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells[..self.cells.len() - 1]
+            .iter()
+            .zip(1..)
+            .all(|(&value, expected)| value == expected)
+    }
+
+    /// Whether the goal can be reached. A board a single cell across is a
+    /// ring whose tiles keep their cyclic order. Otherwise every arrangement
+    /// can be solved if the rows or the columns are odd in number, and with
+    /// both even the flat board's parity rule still holds.
+    pub fn is_solvable(&self) -> bool {
+        if self.rows == 1 || self.cols == 1 {
+            let tiles: Vec<u32> = self.cells.iter().copied().filter(|&v| v != 0).collect();
+            let Some(first) = tiles.iter().position(|&value| value == 1) else {
+                return true;
+            };
+            return tiles[first..]
+                .iter()
+                .chain(&tiles[..first])
+                .zip(1..)
+                .all(|(&value, expected)| value == expected);
+        }
+        if self.rows % 2 == 1 || self.cols % 2 == 1 {
+            return true;
+        }
+        self.to_puzzle().is_current_state_solvable()
+    }
+
+    /// Sum of the distances of the tiles from their goal cells, each the
+    /// short way round. Never overestimates.
+    pub fn manhattan_distance(&self) -> usize {
+        (0..self.cells.len())
+            .filter(|&cell| self.cells[cell] != 0)
+            .map(|cell| {
+                let goal = self.cells[cell] as usize - 1;
+                cyclic(cell / self.cols, goal / self.cols, self.rows)
+                    + cyclic(cell % self.cols, goal % self.cols, self.cols)
+            })
+            .sum()
+    }
+
+    /// A shortest solution, by IDA* guided by `manhattan_distance`. Fails if
+    /// the board cannot be solved or the search grows past a fixed node
+    /// limit.
+    pub fn solve(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_with_progress(|_| true)
+    }
+
+    /// Like `solve`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if !self.is_solvable() {
+            return Err("Puzzle is not solvable");
+        }
+        search::run(StateSearch::new(self.clone()), callback)
+    }
+}
+
+/// `index` stepped by `offset` around a ring of `len` cells.
+fn wrap(index: usize, offset: isize, len: usize) -> usize {
+    (index + len).wrapping_add_signed(offset) % len
+}
+
+/// Steps between two cells of a ring of `len` cells, the short way round.
+fn cyclic(a: usize, b: usize, len: usize) -> usize {
+    let direct = a.abs_diff(b);
+    direct.min(len - direct)
+}
+
+impl SearchState for TorusBoard {
+    type Move = Move;
+
+    /// On a board two cells across, both directions along it slide the
+    /// same tile, so only the first in `Move::ALL` order is listed.
+    fn moves(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = Vec::new();
+        for movement in self.legal_moves() {
+            let target = self.moved_tile_position(movement);
+            if moves
+                .iter()
+                .all(|&listed| self.moved_tile_position(listed) != target)
+            {
+                moves.push(movement);
+            }
+        }
+        moves
+    }
+
+    fn apply(&mut self, movement: Move) -> Move {
+        let from = self.blank;
+        self.apply_move(movement);
+        SearchState::moves(self)
+            .into_iter()
+            .find(|&back| self.moved_tile_position(back) == Some(from))
+            .unwrap_or(movement.opposite())
+    }
+
+    fn is_solved(&self) -> bool {
+        TorusBoard::is_solved(self)
+    }
+
+    fn estimate(&self) -> usize {
+        self.manhattan_distance()
+    }
+}
+
+impl fmt::Display for TorusBoard {
+    /// Draws the board as `Puzzle` does, including the single-line form
+    /// with the alternate flag (`{:#}`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self.to_puzzle())
+        } else {
+            write!(f, "{}", self.to_puzzle())
+        }
+    }
+}
+
+impl FromStr for TorusBoard {
+    type Err = &'static str;
+
+    /// Parses any board text `Puzzle` accepts.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let puzzle: Puzzle = s.parse()?;
+        if !puzzle.has_standard_goal() {
+            return Err("Torus boards only support the standard goal");
+        }
+        Ok(Self::from_puzzle(&puzzle))
+    }
+}
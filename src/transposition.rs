@@ -0,0 +1,76 @@
+use crate::puzzle::Puzzle;
+use std::collections::HashMap;
+use std::mem;
+
+/// Lowest cost at which a state has been reached in an IDA* iteration.
+#[derive(Clone, Copy)]
+struct Entry {
+    iteration: u32,
+    g: u32,
+}
+
+/// Hash map growth can leave up to half its slots empty, so budget each
+/// entry at twice its size.
+const ENTRY_BYTES: usize = 2 * mem::size_of::<(u128, Entry)>();
+
+/// Remembers the lowest `g` at which each state was reached in the current
+/// IDA* iteration, so the search can skip a state it reaches again at the
+/// same or a higher cost: everything below it was already searched with at
+/// least as much of the bound left.
+pub(crate) struct TranspositionTable {
+    entries: HashMap<u128, Entry>,
+    max_entries: usize,
+}
+
+impl TranspositionTable {
+    /// A table that holds as many states as fit in `bytes`. Once full, new
+    /// states are no longer recorded but known ones are still updated.
+    pub(crate) fn new(bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries: bytes / ENTRY_BYTES,
+        }
+    }
+
+    /// Records `state` at cost `g` and returns whether it still needs to be
+    /// searched. Boards too large to pack are always searched.
+    pub(crate) fn visit(&mut self, state: &Puzzle, g: usize, iteration: usize) -> bool {
+        let Some(key) = pack(state) else {
+            return true;
+        };
+        let seen = Entry {
+            iteration: iteration as u32,
+            g: g as u32,
+        };
+        match self.entries.get_mut(&key) {
+            Some(entry) if entry.iteration == seen.iteration && entry.g <= seen.g => false,
+            Some(entry) => {
+                *entry = seen;
+                true
+            }
+            None => {
+                if self.entries.len() < self.max_entries {
+                    self.entries.insert(key, seen);
+                }
+                true
+            }
+        }
+    }
+}
+
+/// The tiles packed into as few bits each as the largest tile needs, or
+/// `None` if the board does not fit in 128 bits, as for 6x6 and larger.
+fn pack(puzzle: &Puzzle) -> Option<u128> {
+    let cells = puzzle.rows() * puzzle.cols();
+    let bits = (usize::BITS - (cells - 1).leading_zeros()) as usize;
+    if bits * cells > 128 {
+        return None;
+    }
+    let mut key = 0u128;
+    for row in 0..puzzle.rows() {
+        for col in 0..puzzle.cols() {
+            key = (key << bits) | puzzle.tile_at(row, col) as u128;
+        }
+    }
+    Some(key)
+}
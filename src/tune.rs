@@ -0,0 +1,161 @@
+use crate::clock::Instant;
+use crate::config::Config;
+use crate::puzzle::Puzzle;
+use std::fmt;
+use std::time::Duration;
+
+const WEIGHTS: [f64; 6] = [1.0, 1.25, 1.5, 2.0, 3.0, 5.0];
+const BEAM_WIDTHS: [usize; 4] = [10, 100, 1000, 10000];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TuneSetting {
+    Weight(f64),
+    BeamWidth(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneCandidate {
+    pub setting: TuneSetting,
+    /// Whether every sample was solved within the candidate's share of the budget.
+    pub completed: bool,
+    pub mean_time: Duration,
+    pub mean_length: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneReport {
+    pub candidates: Vec<TuneCandidate>,
+    /// Indices of completed candidates that no other candidate beats on both
+    /// time and solution length, fastest first.
+    pub frontier: Vec<usize>,
+}
+
+/// Runs every weighted A* weight and beam width on `samples`, giving each
+/// setting an equal share of `budget`, and reports the time/length trade-off.
+pub fn tune(samples: &[Puzzle], budget: Duration) -> TuneReport {
+    let settings: Vec<TuneSetting> = WEIGHTS
+        .iter()
+        .map(|&weight| TuneSetting::Weight(weight))
+        .chain(
+            BEAM_WIDTHS
+                .iter()
+                .map(|&width| TuneSetting::BeamWidth(width)),
+        )
+        .collect();
+    let share = budget / settings.len() as u32;
+
+    let candidates: Vec<TuneCandidate> = settings
+        .into_iter()
+        .map(|setting| evaluate(setting, samples, share))
+        .collect();
+
+    let mut frontier: Vec<usize> = (0..candidates.len())
+        .filter(|&i| candidates[i].completed)
+        .filter(|&i| {
+            !candidates.iter().any(|other| {
+                other.completed
+                    && other.mean_time <= candidates[i].mean_time
+                    && other.mean_length <= candidates[i].mean_length
+                    && (other.mean_time < candidates[i].mean_time
+                        || other.mean_length < candidates[i].mean_length)
+            })
+        })
+        .collect();
+    frontier.sort_by_key(|&i| candidates[i].mean_time);
+
+    TuneReport {
+        candidates,
+        frontier,
+    }
+}
+
+fn evaluate(setting: TuneSetting, samples: &[Puzzle], share: Duration) -> TuneCandidate {
+    let start = Instant::now();
+    let mut total_length = 0;
+    let mut completed = true;
+
+    for sample in samples {
+        let within_budget = |_: &_| start.elapsed() < share;
+        let result = match setting {
+            TuneSetting::Weight(weight) => {
+                sample.solve_weighted_with_progress(weight, within_budget)
+            }
+            TuneSetting::BeamWidth(width) => sample.solve_beam_with_progress(width, within_budget),
+        };
+
+        match result {
+            Ok(moves) if start.elapsed() < share => total_length += moves.len(),
+            _ => {
+                completed = false;
+                break;
+            }
+        }
+    }
+
+    let count = samples.len().max(1);
+    TuneCandidate {
+        setting,
+        completed,
+        mean_time: start.elapsed() / count as u32,
+        mean_length: total_length as f64 / count as f64,
+    }
+}
+
+impl TuneReport {
+    /// The frontier point with the shortest solutions.
+    pub fn best(&self) -> Option<&TuneCandidate> {
+        self.frontier.last().map(|&i| &self.candidates[i])
+    }
+
+    /// Points `config` at the best setting found, if any.
+    pub fn apply_best(&self, config: &mut Config) -> bool {
+        match self.best().map(|candidate| candidate.setting) {
+            Some(TuneSetting::Weight(weight)) => {
+                config.set("solver", "weighted-a-star");
+                config.set("weight", &weight.to_string());
+                true
+            }
+            Some(TuneSetting::BeamWidth(width)) => {
+                config.set("solver", "beam");
+                config.set("beam_width", &width.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for TuneSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuneSetting::Weight(weight) => write!(f, "weighted-a-star weight={}", weight),
+            TuneSetting::BeamWidth(width) => write!(f, "beam width={}", width),
+        }
+    }
+}
+
+impl fmt::Display for TuneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            let marker = if self.frontier.contains(&i) { "*" } else { " " };
+            if candidate.completed {
+                writeln!(
+                    f,
+                    "{} {:<28} {:>10.2?} {:>8.1} moves",
+                    marker,
+                    candidate.setting.to_string(),
+                    candidate.mean_time,
+                    candidate.mean_length
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "{} {:<28} over budget",
+                    marker,
+                    candidate.setting.to_string()
+                )?;
+            }
+        }
+        write!(f, "* Pareto frontier")
+    }
+}
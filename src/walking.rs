@@ -0,0 +1,136 @@
+//! Ken'ichiro Takahashi's walking distance heuristic.
+//!
+//! The row abstraction forgets which column each tile is in and which tile
+//! it is, keeping only how many tiles of each goal row sit in each row,
+//! and where the blank is. Each move takes a tile from the row above or
+//! below the blank into the blank's row, so a table of the fewest moves from
+//! every such arrangement to the goal's bounds the vertical moves needed.
+//! The same table read for columns bounds the horizontal moves, and as
+//! every move is one or the other the two can be added.
+//!
+//! Unlike the Manhattan distance, the table accounts for tiles of a row
+//! having to take turns passing through the blank's row, so the estimate is
+//! usually much stronger on 4x4 boards, for a table of about 25,000
+//! arrangements.
+
+use crate::progress::Progress;
+use crate::puzzle::{Move, Puzzle};
+use crate::search::{self, IdaStarSearch};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::sync::OnceLock;
+
+/// Largest board with a table. A 5x5 board has millions of arrangements,
+/// which makes its table slow to build and its lookups slow.
+pub const MAX_SIZE: usize = 4;
+
+/// Arrangements in the 4x4 table.
+const ARRANGEMENTS_4X4: usize = 24_964;
+
+/// One table per board size, built on first use.
+static TABLES: [OnceLock<HashMap<u64, u8>>; MAX_SIZE + 1] =
+    [const { OnceLock::new() }; MAX_SIZE + 1];
+
+/// Memory taken by the 4x4 table, the largest.
+pub fn table_bytes() -> usize {
+    ARRANGEMENTS_4X4 * mem::size_of::<(u64, u8)>()
+}
+
+impl Puzzle {
+    /// The walking distance for square boards up to `walking::MAX_SIZE` with
+    /// the standard goal, and the Manhattan distance for others. Never
+    /// overestimates.
+    pub fn walking_distance(&self) -> usize {
+        let size = self.size();
+        let Some(table) = TABLES
+            .get(size)
+            .filter(|_| self.is_square() && self.has_standard_goal())
+        else {
+            return self.manhattan_distance();
+        };
+        let table = table.get_or_init(|| build(size));
+
+        let goals = self.goals();
+        let mut by_row = [0; MAX_SIZE * MAX_SIZE];
+        let mut by_col = [0; MAX_SIZE * MAX_SIZE];
+        for row in 0..size {
+            for col in 0..size {
+                let value = self.tile_at(row, col);
+                if value != 0 {
+                    let (goal_row, goal_col) = goals[value as usize];
+                    by_row[row * size + goal_row] += 1;
+                    by_col[col * size + goal_col] += 1;
+                }
+            }
+        }
+
+        let (blank_row, blank_col) = self.blank_position();
+        let lines = size * size;
+        let vertical = table[&pack(&by_row[..lines], blank_row)];
+        let horizontal = table[&pack(&by_col[..lines], blank_col)];
+        (vertical + horizontal) as usize
+    }
+
+    /// Optimal IDA* guided by `walking_distance`, which expands far fewer
+    /// nodes than `solve_optimal` on 4x4 boards without needing a pattern
+    /// database.
+    pub fn solve_walking_distance(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_walking_distance_with_progress(|_| true)
+    }
+
+    pub fn solve_walking_distance_with_progress<F>(
+        &self,
+        callback: F,
+    ) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        search::run(
+            IdaStarSearch::with_heuristic(self, Puzzle::walking_distance)?,
+            callback,
+        )
+    }
+}
+
+/// The count of tiles in each line by goal line, line by line, and the
+/// blank's line, three bits each.
+fn pack(counts: &[u8], blank: usize) -> u64 {
+    counts
+        .iter()
+        .fold(blank as u64, |packed, &count| packed << 3 | count as u64)
+}
+
+/// Fewest moves to the goal from every arrangement reachable from it, found
+/// by breadth-first search back from the goal.
+fn build(size: usize) -> HashMap<u64, u8> {
+    let mut goal = vec![0; size * size];
+    for line in 0..size {
+        goal[line * size + line] = size as u8;
+    }
+    // The blank takes one of the last row's places
+    goal[size * size - 1] -= 1;
+    let goal_blank = size - 1;
+
+    let mut distances = HashMap::from([(pack(&goal, goal_blank), 0)]);
+    let mut queue = VecDeque::from([(goal, goal_blank)]);
+    while let Some((counts, blank)) = queue.pop_front() {
+        let depth = distances[&pack(&counts, blank)];
+        let neighbours = [blank.checked_sub(1), Some(blank + 1).filter(|&n| n < size)];
+        for neighbour in neighbours.into_iter().flatten() {
+            for goal_line in 0..size {
+                if counts[neighbour * size + goal_line] == 0 {
+                    continue;
+                }
+                let mut next = counts.clone();
+                next[neighbour * size + goal_line] -= 1;
+                next[blank * size + goal_line] += 1;
+                if let Entry::Vacant(entry) = distances.entry(pack(&next, neighbour)) {
+                    entry.insert(depth + 1);
+                    queue.push_back((next, neighbour));
+                }
+            }
+        }
+    }
+    distances
+}
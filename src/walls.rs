@@ -0,0 +1,456 @@
+//! Boards with walls: cells no tile can enter, for level designs beyond
+//! the plain rectangle.
+//!
+//! Irregular boards with cells missing altogether, as in many physical
+//! puzzles, are the same thing: build them with `WalledBoard::from_mask`,
+//! which walls off every cell outside the shape.
+//!
+//! The goal numbers the open cells row by row, skipping walls, and leaves
+//! the empty space in the last open cell. Tiles travel around walls, so
+//! distances are measured along the open cells rather than straight across.
+//!
+//! Which boards can be solved depends on the shape of the open cells. Tiles
+//! can only move in the part the empty space can reach, so every other tile
+//! must already be home. Where that part is a single corridor or loop,
+//! tiles can never pass each other. Where it has no choke point, a cell
+//! whose loss would split it, the flat board's parity rule decides, by
+//! Wilson's theorem on sliding puzzles over graphs. Layouts with choke
+//! points fall between, and only a search can tell.
+
+use crate::geometry::{has_choke_point, keeps_order, reachable_from};
+use crate::progress::Progress;
+use crate::puzzle::{ApplyError, Move, MoveError, MoveSet, Puzzle, MAX_SIZE};
+use crate::search::{self, SearchState, StateSearch};
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Largest board, in cells, whose distances around walls are tabulated.
+/// Larger boards are estimated by Manhattan distance, which ignores walls.
+const DISTANCE_TABLE_CELLS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WalledBoard {
+    rows: usize,
+    cols: usize,
+    /// Tiles row by row, with 0 for the empty space and for walls.
+    cells: Vec<u32>,
+    blank: usize,
+    /// Fixed for the board's lifetime, so shared between copies.
+    layout: Arc<Layout>,
+}
+
+#[derive(Debug)]
+struct Layout {
+    walls: Vec<bool>,
+    /// The cell each tile belongs in, indexed by tile value. Entry 0 is the
+    /// empty space's goal.
+    goals: Vec<usize>,
+    /// Moves between each goal cell and every cell along open cells,
+    /// `u16::MAX` where walls cut them off, or empty on large boards.
+    distances: Vec<u16>,
+}
+
+impl WalledBoard {
+    /// The solved board with walls on `walls`, given as rows and columns.
+    pub fn new(rows: usize, cols: usize, walls: &[(usize, usize)]) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        let mut is_wall = vec![false; rows * cols];
+        for &(row, col) in walls {
+            if row >= rows || col >= cols {
+                return Err("Wall is off the board");
+            }
+            is_wall[row * cols + col] = true;
+        }
+        Self::solved(rows, cols, is_wall)
+    }
+
+    /// The solved board of an irregular shape, given by `mask` listing every
+    /// cell of the bounding rectangle row by row, `true` where the board has
+    /// a cell.
+    pub fn from_mask(rows: usize, cols: usize, mask: &[bool]) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        if mask.len() != rows * cols {
+            return Err("Wrong number of cells for board size");
+        }
+        Self::solved(rows, cols, mask.iter().map(|&present| !present).collect())
+    }
+
+    fn solved(rows: usize, cols: usize, is_wall: Vec<bool>) -> Result<Self, &'static str> {
+        let layout = Layout::new(rows, cols, is_wall)?;
+        let mut cells = vec![0; rows * cols];
+        for (value, &cell) in layout.goals.iter().enumerate().skip(1) {
+            cells[cell] = value as u32;
+        }
+        Ok(Self {
+            rows,
+            cols,
+            cells,
+            blank: layout.goals[0],
+            layout: Arc::new(layout),
+        })
+    }
+
+    /// Builds a board from its cells row by row: `None` for a wall, 0 for
+    /// the empty space, and each tile from 1 to the number of tiles once.
+    pub fn from_cells(
+        rows: usize,
+        cols: usize,
+        cells: &[Option<u32>],
+    ) -> Result<Self, &'static str> {
+        check_size(rows, cols)?;
+        if cells.len() != rows * cols {
+            return Err("Wrong number of tiles for board size");
+        }
+        let layout = Layout::new(rows, cols, cells.iter().map(Option::is_none).collect())?;
+        let mut seen = vec![false; layout.goals.len()];
+        for &value in cells.iter().flatten() {
+            match seen.get_mut(value as usize) {
+                Some(slot) if !*slot => *slot = true,
+                Some(_) => return Err("Duplicate tile"),
+                None => return Err("Tile value out of range"),
+            }
+        }
+        let tiles: Vec<u32> = cells.iter().map(|cell| cell.unwrap_or(0)).collect();
+        let blank = (0..cells.len())
+            .find(|&cell| cells[cell] == Some(0))
+            .ok_or("Board needs an empty space")?;
+        Ok(Self {
+            rows,
+            cols,
+            cells: tiles,
+            blank,
+            layout: Arc::new(layout),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The tile at a cell, 0 for the empty space, or `None` for a wall.
+    pub fn tile_at(&self, row: usize, col: usize) -> Option<u32> {
+        let cell = row * self.cols + col;
+        (!self.layout.walls[cell]).then_some(self.cells[cell])
+    }
+
+    pub fn is_wall(&self, row: usize, col: usize) -> bool {
+        self.layout.walls[row * self.cols + col]
+    }
+
+    /// The board's shape, row by row, `true` for each cell that is not a
+    /// wall, as taken by `from_mask`.
+    pub fn mask(&self) -> Vec<bool> {
+        self.layout.walls.iter().map(|&wall| !wall).collect()
+    }
+
+    pub fn blank_position(&self) -> (usize, usize) {
+        (self.blank / self.cols, self.blank % self.cols)
+    }
+
+    /// Row and column of the tile that `movement` would slide into the
+    /// empty space, or why there is none.
+    pub fn moved_tile(&self, movement: Move) -> Result<(usize, usize), MoveError> {
+        let (dx, dy) = movement.as_offset();
+        let (row, col) = self.blank_position();
+        let (Some(row), Some(col)) = (row.checked_add_signed(dx), col.checked_add_signed(dy))
+        else {
+            return Err(MoveError::OutOfBounds(movement));
+        };
+        if row >= self.rows || col >= self.cols {
+            return Err(MoveError::OutOfBounds(movement));
+        }
+        if self.is_wall(row, col) {
+            return Err(MoveError::Wall(movement));
+        }
+        Ok((row, col))
+    }
+
+    /// The moves that are legal on this board, in `Move::ALL` order.
+    pub fn legal_moves(&self) -> MoveSet {
+        Move::ALL
+            .into_iter()
+            .filter(|&movement| self.moved_tile(movement).is_ok())
+            .collect()
+    }
+
+    /// Slides a tile into the empty space, returning whether the move was
+    /// legal.
+    pub fn apply_move(&mut self, movement: Move) -> bool {
+        self.try_apply(movement).is_ok()
+    }
+
+    /// Like `apply_move`, but says why an illegal move was rejected.
+    pub fn try_apply(&mut self, movement: Move) -> Result<(), MoveError> {
+        let (row, col) = self.moved_tile(movement)?;
+        let cell = row * self.cols + col;
+        self.cells.swap(self.blank, cell);
+        self.blank = cell;
+        Ok(())
+    }
+
+    /// Applies all of `moves` or none of them: if one is illegal, the moves
+    /// before it are undone and its position is reported.
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), ApplyError> {
+        for (index, &movement) in moves.iter().enumerate() {
+            if let Err(error) = self.try_apply(movement) {
+                for &done in moves[..index].iter().rev() {
+                    self.try_apply(done.opposite())
+                        .expect("undoing a legal move is legal");
+                }
+                return Err(ApplyError { index, error });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.layout
+            .goals
+            .iter()
+            .enumerate()
+            .all(|(value, &cell)| self.cells[cell] == value as u32)
+    }
+
+    /// Whether the goal can be reached, or `None` for a layout with a choke
+    /// point where only a search can tell; see the module notes.
+    pub fn is_solvable(&self) -> Option<bool> {
+        let neighbours = |cell: usize| self.open_neighbours(cell);
+        let open = reachable_from(self.cells.len(), self.blank, neighbours);
+        // Tiles the empty space can never reach must already be home
+        let stuck_home = (0..self.cells.len())
+            .filter(|&cell| !open[cell] && !self.layout.walls[cell])
+            .all(|cell| self.layout.goals[self.cells[cell] as usize] == cell);
+        if !stuck_home {
+            return Some(false);
+        }
+
+        let component: Vec<usize> = (0..self.cells.len()).filter(|&cell| open[cell]).collect();
+        let degree = |cell: usize| self.open_neighbours(cell).count();
+        if component.iter().all(|&cell| degree(cell) <= 2) {
+            let mut goal = vec![0; self.cells.len()];
+            for (value, &cell) in self.layout.goals.iter().enumerate() {
+                goal[cell] = value as u32;
+            }
+            return Some(keeps_order(&component, neighbours, &self.cells, &goal));
+        }
+
+        // Each move is one swap with the empty space and moves it to a cell
+        // of the other colour, so the two parities change together
+        let ranks: Vec<u32> = component
+            .iter()
+            .map(|&cell| match self.cells[cell] {
+                0 => u32::MAX,
+                value => value,
+            })
+            .collect();
+        let swaps = Puzzle::count_inversions(&ranks);
+        let colour = |cell: usize| (cell / self.cols + cell % self.cols) % 2;
+        let moved = colour(self.blank) != colour(self.layout.goals[0]);
+        if swaps.is_multiple_of(2) == moved {
+            return Some(false);
+        }
+        if has_choke_point(self.cells.len(), &component, neighbours) {
+            None
+        } else {
+            Some(true)
+        }
+    }
+
+    /// Sum of the moves each tile needs to reach its goal cell around the
+    /// walls. Never overestimates. Large boards fall back to Manhattan
+    /// distance, and a tile walled off from its goal counts as `u16::MAX`
+    /// moves.
+    pub fn distance_estimate(&self) -> usize {
+        let cells = self.cells.len();
+        (0..cells)
+            .filter(|&cell| self.cells[cell] != 0)
+            .map(|cell| {
+                let goal = self.layout.goals[self.cells[cell] as usize];
+                match self.layout.distances.get(goal * cells + cell) {
+                    Some(&distance) => distance as usize,
+                    None => {
+                        (cell / self.cols).abs_diff(goal / self.cols)
+                            + (cell % self.cols).abs_diff(goal % self.cols)
+                    }
+                }
+            })
+            .sum()
+    }
+
+    /// A shortest solution, by IDA* guided by `distance_estimate`. Fails if
+    /// the board cannot be solved, or if the search grows past a fixed node
+    /// limit, which is how unsolvable layouts with choke points end.
+    pub fn solve(&self) -> Result<Vec<Move>, &'static str> {
+        self.solve_with_progress(|_| true)
+    }
+
+    /// Like `solve`, reporting progress to `callback`; returning `false`
+    /// from the callback cancels the search.
+    pub fn solve_with_progress<F>(&self, callback: F) -> Result<Vec<Move>, &'static str>
+    where
+        F: FnMut(&Progress) -> bool,
+    {
+        if self.is_solvable() == Some(false) {
+            return Err("Puzzle is not solvable");
+        }
+        search::run(StateSearch::new(self.clone()), callback)
+    }
+
+    fn open_neighbours(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        open_neighbours(self.rows, self.cols, &self.layout.walls, cell)
+    }
+}
+
+/// The rest of a layout follows from its walls.
+impl PartialEq for Layout {
+    fn eq(&self, other: &Self) -> bool {
+        self.walls == other.walls
+    }
+}
+
+impl Eq for Layout {}
+
+impl Hash for Layout {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.walls.hash(state);
+    }
+}
+
+impl Layout {
+    fn new(rows: usize, cols: usize, walls: Vec<bool>) -> Result<Self, &'static str> {
+        let open: Vec<usize> = (0..walls.len()).filter(|&cell| !walls[cell]).collect();
+        let Some(&last) = open.last() else {
+            return Err("Board needs an open cell");
+        };
+        let goals: Vec<usize> = std::iter::once(last)
+            .chain(open[..open.len() - 1].iter().copied())
+            .collect();
+
+        let cells = walls.len();
+        let mut distances = Vec::new();
+        if cells <= DISTANCE_TABLE_CELLS {
+            distances = vec![u16::MAX; cells * cells];
+            for &goal in &open {
+                let row = &mut distances[goal * cells..(goal + 1) * cells];
+                row[goal] = 0;
+                let mut queue = VecDeque::from([goal]);
+                while let Some(cell) = queue.pop_front() {
+                    for next in open_neighbours(rows, cols, &walls, cell) {
+                        if row[next] == u16::MAX {
+                            row[next] = row[cell] + 1;
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            walls,
+            goals,
+            distances,
+        })
+    }
+}
+
+fn check_size(rows: usize, cols: usize) -> Result<(), &'static str> {
+    if rows == 0 || cols == 0 {
+        return Err("Board size must be positive");
+    }
+    if rows > MAX_SIZE || cols > MAX_SIZE {
+        return Err("Board size is too large");
+    }
+    Ok(())
+}
+
+fn open_neighbours(
+    rows: usize,
+    cols: usize,
+    walls: &[bool],
+    cell: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    let (row, col) = (cell / cols, cell % cols);
+    Move::ALL.into_iter().filter_map(move |movement| {
+        let (dx, dy) = movement.as_offset();
+        let row = row.checked_add_signed(dx)?;
+        let col = col.checked_add_signed(dy)?;
+        let next = row * cols + col;
+        (row < rows && col < cols && !walls[next]).then_some(next)
+    })
+}
+
+impl SearchState for WalledBoard {
+    type Move = Move;
+
+    fn moves(&self) -> Vec<Move> {
+        self.legal_moves().collect()
+    }
+
+    fn apply(&mut self, movement: Move) -> Move {
+        self.apply_move(movement);
+        movement.opposite()
+    }
+
+    fn is_solved(&self) -> bool {
+        WalledBoard::is_solved(self)
+    }
+
+    fn estimate(&self) -> usize {
+        self.distance_estimate()
+    }
+}
+
+impl fmt::Display for WalledBoard {
+    /// Draws the board as a grid with `#` for walls. The alternate flag
+    /// (`{:#}`) instead writes the single-line form `rowsxcols:cell,...`,
+    /// with 0 for the empty space and `#` for walls, which parses back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = |cell: usize| match self.layout.walls[cell] {
+            true => "#".to_string(),
+            false => self.cells[cell].to_string(),
+        };
+        if f.alternate() {
+            let cells: Vec<String> = (0..self.cells.len()).map(label).collect();
+            return write!(f, "{}x{}:{}", self.rows, self.cols, cells.join(","));
+        }
+        let width = (self.layout.goals.len() - 1).to_string().len().max(2);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                write!(f, "{:>width$} ", label(row * self.cols + col))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for WalledBoard {
+    type Err = &'static str;
+
+    /// Parses a `size:` or `rowsxcols:` header followed by the cells row by
+    /// row, separated by whitespace or commas, with 0 for the empty space
+    /// and `#` for walls.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, cells) = s.split_once(':').ok_or("Missing size header")?;
+        let parse = |text: &str| text.trim().parse().map_err(|_| "Invalid board size");
+        let (rows, cols) = match header.split_once('x') {
+            Some((rows, cols)) => (parse(rows)?, parse(cols)?),
+            None => (parse(header)?, parse(header)?),
+        };
+        let cells = cells
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| match token {
+                "#" => Ok(None),
+                _ => token.parse().map(Some).map_err(|_| "Invalid tile value"),
+            })
+            .collect::<Result<Vec<Option<u32>>, _>>()?;
+        Self::from_cells(rows, cols, &cells)
+    }
+}
@@ -0,0 +1,124 @@
+//! Change notifications for frontends that bind to a board's state.
+
+use crate::puzzle::{ApplyError, Move, MoveError, Puzzle};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardChange {
+    /// A tile slid from `from` into the empty space at `to`, as (row, col).
+    TileMoved {
+        tile: u32,
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    /// The whole board was replaced, for example by a shuffle.
+    Reset,
+}
+
+type Listener = Box<dyn FnMut(&BoardChange)>;
+
+/// A puzzle that tells its listeners about every change. Mutations go
+/// through this wrapper; reads go through `puzzle`.
+pub struct WatchedPuzzle {
+    puzzle: Puzzle,
+    listeners: Vec<Listener>,
+}
+
+impl WatchedPuzzle {
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    pub fn into_inner(self) -> Puzzle {
+        self.puzzle
+    }
+
+    /// Calls `callback` after every change, in the order listeners were added.
+    pub fn on_change<F>(&mut self, callback: F)
+    where
+        F: FnMut(&BoardChange) + 'static,
+    {
+        self.listeners.push(Box::new(callback));
+    }
+
+    /// Sends every change to the returned channel. Changes are dropped once
+    /// the receiver is gone.
+    pub fn subscribe(&mut self) -> Receiver<BoardChange> {
+        let (sender, receiver) = mpsc::channel();
+        self.on_change(move |change| {
+            let _ = sender.send(change.clone());
+        });
+        receiver
+    }
+
+    pub fn try_apply(&mut self, movement: Move) -> Result<(), MoveError> {
+        let change = moved(&self.puzzle, movement);
+        self.puzzle.try_apply(movement)?;
+        if let Some(change) = change {
+            self.notify(&change);
+        }
+        Ok(())
+    }
+
+    pub fn apply_move(&mut self, movement: Move) -> bool {
+        self.try_apply(movement).is_ok()
+    }
+
+    /// Applies all of `moves` or none of them, like `Puzzle::apply_moves`.
+    /// Listeners only hear about the moves once the whole sequence succeeded.
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), ApplyError> {
+        let mut replay = self.puzzle.clone();
+        self.puzzle.apply_moves(moves)?;
+        for &movement in moves {
+            if let Some(change) = moved(&replay, movement) {
+                self.notify(&change);
+            }
+            replay.apply_move(movement);
+        }
+        Ok(())
+    }
+
+    pub fn shuffle(&mut self) {
+        self.puzzle.shuffle();
+        self.notify(&BoardChange::Reset);
+    }
+
+    /// Replaces the board, for example with one loaded from a file.
+    pub fn set(&mut self, puzzle: Puzzle) {
+        self.puzzle = puzzle;
+        self.notify(&BoardChange::Reset);
+    }
+
+    fn notify(&mut self, change: &BoardChange) {
+        for listener in &mut self.listeners {
+            listener(change);
+        }
+    }
+}
+
+impl fmt::Debug for WatchedPuzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedPuzzle")
+            .field("puzzle", &self.puzzle)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+/// The change `movement` would make to `puzzle`, if the move is possible.
+fn moved(puzzle: &Puzzle, movement: Move) -> Option<BoardChange> {
+    let from = puzzle.moved_tile_position(movement)?;
+    Some(BoardChange::TileMoved {
+        tile: puzzle.tile_at(from.0, from.1),
+        from,
+        to: puzzle.blank_position(),
+    })
+}
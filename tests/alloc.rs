@@ -0,0 +1,122 @@
+//! The interactive paths documented as allocation-free, checked with an
+//! allocator that counts the allocations of the calling thread.
+
+use slider_puzzle::hints::CachedHint;
+use slider_puzzle::puzzle::{Move, Puzzle};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::hint::black_box;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    // Fails quietly while the thread is being torn down
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Allocations `f` makes on this thread.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn scrambled(size: usize) -> Puzzle {
+    let mut puzzle = Puzzle::new(size).unwrap();
+    while puzzle.is_solved() {
+        puzzle.shuffle();
+    }
+    puzzle
+}
+
+#[test]
+fn apply_move_does_not_allocate() {
+    let mut puzzle = scrambled(4);
+    let count = allocations(|| {
+        for index in 0..1000 {
+            black_box(puzzle.apply_move(Move::ALL[index % 4]));
+        }
+        black_box(puzzle.apply_move(Move::Up) && puzzle.apply_move(Move::Down));
+        black_box(puzzle.apply_moves(&[Move::Left, Move::Right]).is_ok());
+    });
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn legal_moves_does_not_allocate() {
+    let mut puzzle = scrambled(5);
+    let count = allocations(|| {
+        for index in 0..1000 {
+            for movement in puzzle.legal_moves() {
+                black_box(movement);
+            }
+            puzzle.apply_move(Move::ALL[index % 4]);
+        }
+    });
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn hint_does_not_allocate() {
+    let start = scrambled(3);
+    let mut hint = CachedHint::new(&start).unwrap();
+    let mut puzzle = start.clone();
+    let strayed = {
+        let mut strayed = start.clone();
+        let first = hint.hint(&start).unwrap();
+        let other = strayed
+            .legal_moves()
+            .into_iter()
+            .find(|&movement| movement != first)
+            .unwrap();
+        strayed.apply_move(other);
+        strayed
+    };
+
+    let count = allocations(|| {
+        // Follow the hints, taking each move back once along the way
+        while let Some(movement) = hint.hint(&puzzle) {
+            puzzle.apply_move(movement);
+            black_box(hint.hint(&puzzle));
+            puzzle.apply_move(movement.opposite());
+            black_box(hint.hint(&puzzle));
+            puzzle.apply_move(movement);
+        }
+    });
+    assert!(puzzle.is_solved());
+    assert_eq!(count, 0);
+
+    let mut hint = CachedHint::new(&start).unwrap();
+    let count = allocations(|| {
+        black_box(hint.hint(&strayed));
+    });
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,31 @@
+//! `Puzzle::solve_astar` checked against breadth-first search on small
+//! boards.
+
+mod common;
+
+use common::check_puzzle_engine;
+use slider_puzzle::puzzle::{Move, Puzzle};
+
+#[test]
+fn solves_small_boards_optimally() {
+    for (rows, cols, step) in [
+        (2, 2, 1),
+        (1, 4, 1),
+        (2, 3, 1),
+        (3, 2, 1),
+        (2, 4, 61),
+        (3, 3, 3637),
+    ] {
+        let goal = Puzzle::new_rectangular(rows, cols).unwrap();
+        check_puzzle_engine(&goal, step, true, Puzzle::solve_astar);
+    }
+}
+
+#[test]
+fn solves_towards_custom_goals() {
+    let mut goal = Puzzle::new_rectangular(2, 3).unwrap();
+    goal.apply_moves(&[Move::Right, Move::Down, Move::Right])
+        .unwrap();
+    let goal = goal.clone().with_goal(&goal).unwrap();
+    check_puzzle_engine(&goal, 1, true, Puzzle::solve_astar);
+}
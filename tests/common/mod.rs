@@ -0,0 +1,100 @@
+//! Exhaustive breadth-first search, the reference the engine tests check
+//! solvability and solution lengths against.
+
+// Each test file uses only some of these
+#![allow(dead_code)]
+
+use slider_puzzle::puzzle::{Move, Puzzle};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The fewest moves from `start` to every state reachable from it, where
+/// `next` gives the states one move away. Searched from the goal, this is
+/// every solvable state and its optimal solution length, as long as every
+/// move can be taken back.
+pub fn distances<S, F>(start: S, next: F) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> Vec<S>,
+{
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let distance = seen[&state];
+        for neighbour in next(&state) {
+            if !seen.contains_key(&neighbour) {
+                seen.insert(neighbour.clone(), distance + 1);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    seen
+}
+
+/// Every ordering of `0..len`, in lexicographic order.
+pub fn permutations(len: u32) -> Vec<Vec<u32>> {
+    if len == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for first in 0..len {
+        for rest in permutations(len - 1) {
+            let mut order = vec![first];
+            order.extend(
+                rest.into_iter()
+                    .map(|value| value + (value >= first) as u32),
+            );
+            all.push(order);
+        }
+    }
+    all
+}
+
+/// The boards one move from `puzzle`.
+pub fn neighbours(puzzle: &Puzzle) -> Vec<Puzzle> {
+    puzzle
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            let mut next = puzzle.clone();
+            next.apply_move(movement);
+            next
+        })
+        .collect()
+}
+
+/// Checks `solve` on every `step`th arrangement of a `rows` by `cols` board
+/// towards `goal`: it must fail exactly on the boards that cannot reach the
+/// goal, and otherwise give legal moves ending on the goal, in the fewest
+/// moves if `optimal`.
+pub fn check_puzzle_engine<F>(goal: &Puzzle, step: usize, optimal: bool, solve: F)
+where
+    F: Fn(&Puzzle) -> Result<Vec<Move>, &'static str>,
+{
+    let reachable = distances(goal.clone(), neighbours);
+    let (rows, cols) = (goal.rows(), goal.cols());
+    let plain_goal = Puzzle::from_tiles_rectangular(rows, cols, &goal.tiles()).unwrap();
+    for tiles in permutations((rows * cols) as u32).iter().step_by(step) {
+        let puzzle = Puzzle::from_tiles_rectangular(rows, cols, tiles)
+            .unwrap()
+            .with_goal(&plain_goal)
+            .unwrap();
+        let Some(&distance) = reachable.get(&puzzle) else {
+            assert!(solve(&puzzle).is_err(), "Solved unsolvable {:#}", puzzle);
+            continue;
+        };
+        let moves = solve(&puzzle).unwrap_or_else(|err| panic!("{:#}: {}", puzzle, err));
+        let mut end = puzzle.clone();
+        assert!(
+            end.apply_moves(&moves).is_ok(),
+            "Illegal moves for {:#}",
+            puzzle
+        );
+        assert!(end.is_solved(), "Moves do not solve {:#}", puzzle);
+        if optimal {
+            assert_eq!(moves.len(), distance, "Not shortest for {:#}", puzzle);
+        } else {
+            assert!(moves.len() >= distance);
+        }
+    }
+}
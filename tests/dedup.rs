@@ -0,0 +1,45 @@
+//! Boards are duplicates only if they match, goal included, exactly or
+//! after reflection.
+
+use slider_puzzle::dedup::{canonical_form, find_duplicates, transposed};
+use slider_puzzle::puzzle::{Move, Puzzle};
+
+fn with_goal(tiles: &[u32], goal_moves: &[Move]) -> Puzzle {
+    let mut goal = Puzzle::new(3).unwrap();
+    goal.apply_moves(goal_moves).unwrap();
+    Puzzle::from_tiles(3, tiles)
+        .unwrap()
+        .with_goal(&goal)
+        .unwrap()
+}
+
+#[test]
+fn reflections_keep_the_goal() {
+    let puzzle = with_goal(&[4, 1, 3, 7, 2, 6, 0, 5, 8], &[Move::Right, Move::Down]);
+    let mirror = transposed(&puzzle);
+    assert!(!mirror.has_standard_goal());
+    assert_eq!(transposed(&mirror), puzzle);
+    assert_eq!(canonical_form(&mirror), canonical_form(&puzzle));
+    assert_eq!(
+        mirror.solve().map(|moves| moves.len()),
+        puzzle.solve().map(|moves| moves.len())
+    );
+}
+
+#[test]
+fn custom_goals_are_not_duplicates_of_standard_boards() {
+    let tiles = [4, 1, 3, 7, 2, 6, 0, 5, 8];
+    let standard = Puzzle::from_tiles(3, &tiles).unwrap();
+    let custom = with_goal(&tiles, &[Move::Right, Move::Down]);
+    let entries = [
+        Ok(standard.clone()),
+        Ok(transposed(&standard)),
+        Ok(custom.clone()),
+        Ok(transposed(&custom)),
+    ];
+    let pairs: Vec<(usize, usize)> = find_duplicates(&entries)
+        .iter()
+        .map(|duplicate| (duplicate.index, duplicate.original))
+        .collect();
+    assert_eq!(pairs, [(1, 0), (3, 2)]);
+}
@@ -0,0 +1,29 @@
+//! Results that must not depend on the platform or on how many boards are
+//! solved at once.
+
+use slider_puzzle::config::Config;
+use slider_puzzle::determinism;
+use slider_puzzle::puzzle::Puzzle;
+use slider_puzzle::registry::INTERRUPTIBLE_ENGINES;
+
+#[test]
+fn fingerprint_matches_the_reference_platform() {
+    assert_eq!(
+        determinism::fingerprint(),
+        determinism::REFERENCE_FINGERPRINT,
+        "seeded boards or engine solutions differ from the reference platform"
+    );
+}
+
+#[test]
+fn results_do_not_depend_on_the_number_of_workers() {
+    let boards: Vec<Puzzle> = (0..10)
+        .map(|i| Puzzle::daily_in(3, &format!("threads-{}", i), "determinism").unwrap())
+        .collect();
+    let mut config = Config::default();
+    config.set("deterministic", "true");
+    for engine in INTERRUPTIBLE_ENGINES {
+        let mismatches = determinism::compare_worker_counts(engine, &config, &boards, &[1, 2, 4]);
+        assert!(mismatches.is_empty(), "{}: {:?}", engine, mismatches);
+    }
+}
@@ -0,0 +1,40 @@
+//! `explain` on boards with custom goals.
+
+use slider_puzzle::explain::explain;
+use slider_puzzle::puzzle::{Move, Puzzle};
+
+fn custom_goal() -> Puzzle {
+    let mut goal = Puzzle::new_rectangular(3, 3).unwrap();
+    goal.apply_moves(&[Move::Right, Move::Down, Move::Right])
+        .unwrap();
+    goal.clone().with_goal(&goal).unwrap()
+}
+
+#[test]
+fn custom_goal_is_the_solved_pattern() {
+    let goal = custom_goal();
+    assert_eq!(explain(&goal).nearest_pattern, ("Solved", 0));
+
+    let mut near = goal.clone();
+    near.apply_move(Move::Left);
+    assert_eq!(explain(&near).nearest_pattern, ("Solved", 2));
+}
+
+#[test]
+fn solvability_follows_custom_goal() {
+    let goal = custom_goal();
+    let mut swapped = goal.tiles();
+    swapped.swap(0, 1);
+    let swapped = Puzzle::from_tiles_rectangular(3, 3, &swapped)
+        .unwrap()
+        .with_goal(&goal)
+        .unwrap();
+    for (puzzle, solvable) in [(goal, true), (swapped, false)] {
+        let explanation = explain(&puzzle);
+        assert_eq!(explanation.solvable, solvable);
+        assert!(explanation
+            .to_string()
+            .contains(&format!("Solvable: {} (", solvable)));
+        assert!(explanation.to_string().contains("custom goal"));
+    }
+}
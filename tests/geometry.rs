@@ -0,0 +1,116 @@
+//! `GeometryBoard` checked against breadth-first search on small shapes.
+
+mod common;
+
+use common::{distances, permutations};
+use slider_puzzle::geometry::{Geometry, GeometryBoard, HexGrid, SquareGrid, TriangleGrid};
+use std::hash::Hash;
+
+/// A shape given by its adjacency lists.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Graph(Vec<Vec<usize>>);
+
+impl Geometry for Graph {
+    fn cells(&self) -> usize {
+        self.0.len()
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        self.0[cell].clone()
+    }
+}
+
+fn neighbours<G: Geometry + Clone>(board: &GeometryBoard<G>) -> Vec<GeometryBoard<G>> {
+    board
+        .moves()
+        .iter()
+        .map(|&cell| {
+            let mut next = board.clone();
+            next.apply_move(cell);
+            next
+        })
+        .collect()
+}
+
+/// Checks every `step`th arrangement on `geometry`. Where `is_solvable`
+/// cannot tell, only solvable boards are solved, as unsolvable ones run
+/// the search to its node limit.
+fn check<G: Geometry + Clone + Eq + Hash>(geometry: G, step: usize) {
+    let goal = GeometryBoard::new(geometry.clone()).unwrap();
+    let reachable = distances(goal, neighbours);
+    for tiles in permutations(geometry.cells() as u32).iter().step_by(step) {
+        let board = GeometryBoard::from_tiles(geometry.clone(), tiles).unwrap();
+        let distance = reachable.get(&board);
+        if let Some(solvable) = board.is_solvable() {
+            assert_eq!(solvable, distance.is_some(), "{:?}", tiles);
+        }
+        let Some(&distance) = distance else {
+            if board.is_solvable().is_some() {
+                assert!(board.solve().is_err());
+            }
+            continue;
+        };
+        let moves = board.solve().unwrap();
+        let mut end = board.clone();
+        assert_eq!(end.apply_moves(&moves), Ok(()));
+        assert!(end.is_solved());
+        assert_eq!(moves.len(), distance, "{:?}", tiles);
+    }
+}
+
+#[test]
+fn square_grids() {
+    check(SquareGrid::new(2, 3).unwrap(), 1);
+    check(SquareGrid::new(1, 4).unwrap(), 1);
+}
+
+#[test]
+fn hexagons() {
+    check(HexGrid::new(1).unwrap(), 1);
+}
+
+#[test]
+fn triangles() {
+    check(TriangleGrid::new(2, 3).unwrap(), 1);
+    check(TriangleGrid::new(2, 4).unwrap(), 13);
+}
+
+#[test]
+fn other_shapes() {
+    // A loop of six with a cell joining two opposite ones, Wilson's
+    // exception
+    check(
+        Graph(vec![
+            vec![1, 5, 6],
+            vec![0, 2],
+            vec![1, 3],
+            vec![2, 4, 6],
+            vec![3, 5],
+            vec![4, 0],
+            vec![0, 3],
+        ]),
+        1,
+    );
+    // A loop of five, where tiles keep their cyclic order
+    check(
+        Graph(vec![
+            vec![1, 4],
+            vec![0, 2],
+            vec![1, 3],
+            vec![2, 4],
+            vec![3, 0],
+        ]),
+        1,
+    );
+    // A triangle with a tail, which has a choke point
+    check(
+        Graph(vec![
+            vec![1, 2],
+            vec![0, 2],
+            vec![0, 1, 3],
+            vec![2, 4],
+            vec![3],
+        ]),
+        1,
+    );
+}
@@ -0,0 +1,25 @@
+//! `Json::parse` rejects input nested too deeply to parse instead of
+//! overflowing the stack.
+
+use slider_puzzle::json::Json;
+
+#[test]
+fn deep_nesting_is_an_error() {
+    for open in ["[", "{\"a\":", "[{\"a\":"] {
+        let text = open.repeat(200_000);
+        assert_eq!(Json::parse(&text), Err("JSON nested too deeply"));
+    }
+}
+
+#[test]
+fn moderate_nesting_parses() {
+    let text = format!("{}{}", "[".repeat(100), "]".repeat(100));
+    let mut value = Json::parse(&text).unwrap();
+    for _ in 1..100 {
+        let Json::Array(mut items) = value else {
+            panic!("Expected an array");
+        };
+        value = items.pop().unwrap();
+    }
+    assert_eq!(value, Json::Array(Vec::new()));
+}
@@ -0,0 +1,60 @@
+//! `BlockBoard::solve` checked against breadth-first search on small
+//! boards.
+
+mod common;
+
+use common::distances;
+use slider_puzzle::klotski::BlockBoard;
+
+fn neighbours(board: &BlockBoard) -> Vec<BlockBoard> {
+    board
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            let mut next = board.clone();
+            next.apply_move(movement);
+            next
+        })
+        .collect()
+}
+
+/// `layout` with each of `targets`, a piece and the row and column its
+/// top-left cell must reach.
+fn board(layout: &str, targets: &[(usize, usize, usize)]) -> BlockBoard {
+    targets.iter().fold(
+        layout.parse().unwrap(),
+        |board: BlockBoard, &(piece, row, col)| board.with_target(piece, row, col).unwrap(),
+    )
+}
+
+#[test]
+fn solutions_match_breadth_first_search() {
+    let boards = [
+        board("AB\nC.", &[(0, 1, 1)]),
+        // Can never move the long piece
+        board("AA\nB.", &[(0, 1, 0)]),
+        board("AAB\n.CD", &[(0, 1, 1)]),
+        // Two targets, and two interchangeable free pieces
+        board("AAB\nAAC\nD..", &[(0, 1, 1), (3, 0, 0)]),
+        board("AB.\nCCD\nEF.", &[(2, 2, 1)]),
+        // The large block blocked in by a lying piece
+        board("ABBC\nABBC\n.DD.", &[(1, 1, 1)]),
+        board("ABC\nDEF\nGH.", &[(0, 2, 2), (7, 0, 0)]),
+    ];
+    for start in boards {
+        let shortest = distances(start.clone(), neighbours)
+            .into_iter()
+            .filter(|(board, _)| board.is_solved())
+            .map(|(_, distance)| distance)
+            .min();
+        let Some(shortest) = shortest else {
+            assert!(start.solve().is_err(), "Solved unsolvable\n{}", start);
+            continue;
+        };
+        let moves = start.solve().unwrap();
+        let mut end = start.clone();
+        assert_eq!(end.apply_moves(&moves), Ok(()));
+        assert!(end.is_solved());
+        assert_eq!(moves.len(), shortest, "\n{}", start);
+    }
+}
@@ -0,0 +1,37 @@
+//! Heuristics checked against the published optimal lengths of Korf's 100
+//! instances.
+
+use slider_puzzle::catalog;
+use slider_puzzle::heuristic;
+use slider_puzzle::korf::{self, KORF_INSTANCE_COUNT};
+
+#[test]
+fn admissible_heuristics_never_exceed_the_optimal_length() {
+    assert_eq!(korf::korf_instances().count(), KORF_INSTANCE_COUNT);
+    for info in catalog::heuristics() {
+        if !info.admissible {
+            continue;
+        }
+        let estimate = heuristic::by_name(info.name).unwrap();
+        for (n, (puzzle, length)) in (1..).zip(korf::korf_instances()) {
+            let bound = estimate.estimate(&puzzle);
+            assert!(
+                bound <= length,
+                "{} estimates {} for instance {}, solved in {}",
+                info.name,
+                bound,
+                n,
+                length
+            );
+        }
+    }
+}
+
+#[test]
+fn instances_are_numbered_from_one() {
+    assert!(korf::korf_instance(0).is_none());
+    assert!(korf::korf_instance(KORF_INSTANCE_COUNT + 1).is_none());
+    let (first, length) = korf::korf_instance(1).unwrap();
+    assert!(first.is_current_state_solvable());
+    assert_eq!(length, 57);
+}
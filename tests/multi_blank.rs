@@ -0,0 +1,65 @@
+//! `MultiBlankBoard` checked against breadth-first search on small boards.
+
+mod common;
+
+use common::{distances, permutations};
+use slider_puzzle::multi_blank::MultiBlankBoard;
+use std::collections::HashSet;
+
+fn neighbours(board: &MultiBlankBoard) -> Vec<MultiBlankBoard> {
+    board
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            let mut next = board.clone();
+            next.apply_move(movement);
+            next
+        })
+        .collect()
+}
+
+/// Every `step`th arrangement of a board with `blanks` empty cells.
+fn arrangements(rows: usize, cols: usize, blanks: u32, step: usize) -> Vec<MultiBlankBoard> {
+    let mut seen = HashSet::new();
+    permutations((rows * cols) as u32)
+        .iter()
+        .step_by(step)
+        .map(|order| {
+            let tiles: Vec<u32> = order
+                .iter()
+                .map(|&value| value.saturating_sub(blanks - 1))
+                .collect();
+            MultiBlankBoard::from_tiles(rows, cols, &tiles).unwrap()
+        })
+        .filter(|board| seen.insert(board.clone()))
+        .collect()
+}
+
+#[test]
+fn solvability_and_solutions_match_breadth_first_search() {
+    for (rows, cols, blanks, step) in [
+        (2, 2, 2, 1),
+        (1, 4, 2, 1),
+        (3, 1, 2, 1),
+        (2, 3, 1, 1),
+        (2, 3, 2, 1),
+        (2, 3, 3, 1),
+        (2, 4, 2, 4001),
+    ] {
+        let goal = MultiBlankBoard::new(rows, cols, blanks as usize).unwrap();
+        let reachable = distances(goal, neighbours);
+        for board in arrangements(rows, cols, blanks, step) {
+            let distance = reachable.get(&board);
+            assert_eq!(board.is_solvable(), distance.is_some(), "{}", board);
+            let Some(&distance) = distance else {
+                assert!(board.solve().is_err());
+                continue;
+            };
+            let moves = board.solve().unwrap();
+            let mut end = board.clone();
+            assert_eq!(end.apply_moves(&moves), Ok(()));
+            assert!(end.is_solved());
+            assert_eq!(moves.len(), distance, "{}", board);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Multi-tile solutions checked against a cheapest-first search over every
+//! board reachable by slides.
+
+use slider_puzzle::daily::seeded_rng;
+use slider_puzzle::multi_tile::{blank_travel, SlideCost};
+use slider_puzzle::puzzle::Puzzle;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const COSTS: [SlideCost; 4] = [
+    SlideCost::SLIDES,
+    SlideCost::TRAVEL,
+    SlideCost {
+        slides: 1,
+        travel: 1,
+    },
+    SlideCost {
+        slides: 3,
+        travel: 1,
+    },
+];
+
+/// The least `cost` of solving `puzzle`, by Dijkstra's algorithm.
+fn cheapest(puzzle: &Puzzle, cost: SlideCost) -> usize {
+    let mut best = HashMap::from([(puzzle.tiles(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((0, puzzle.tiles()))]);
+    while let Some(Reverse((spent, tiles))) = queue.pop() {
+        if best[&tiles] < spent {
+            continue;
+        }
+        let board = Puzzle::from_tiles_rectangular(puzzle.rows(), puzzle.cols(), &tiles).unwrap();
+        if board.is_solved() {
+            return spent;
+        }
+        for slide in board.legal_slides() {
+            let mut next = board.clone();
+            next.apply_slide(slide);
+            let total = spent + cost.of(slide);
+            if best.get(&next.tiles()).is_none_or(|&known| total < known) {
+                best.insert(next.tiles(), total);
+                queue.push(Reverse((total, next.tiles())));
+            }
+        }
+    }
+    panic!("Board is not solvable");
+}
+
+fn boards() -> Vec<Puzzle> {
+    let mut rng = seeded_rng("multi-tile");
+    let mut boards = Vec::new();
+    for (rows, cols) in [(2, 3), (2, 4), (3, 2)] {
+        for _ in 0..3 {
+            let mut puzzle = Puzzle::new_rectangular(rows, cols).unwrap();
+            puzzle.shuffle_with(&mut rng);
+            boards.push(puzzle);
+        }
+    }
+    boards
+}
+
+#[test]
+fn solutions_have_the_least_cost() {
+    for puzzle in boards() {
+        for cost in COSTS {
+            let slides = puzzle.solve_slides_by(cost).unwrap();
+            let mut end = puzzle.clone();
+            assert_eq!(end.apply_slides(&slides), Ok(()));
+            assert!(end.is_solved());
+            assert_eq!(cost.total(&slides), cheapest(&puzzle, cost), "{:?}", cost);
+        }
+    }
+}
+
+#[test]
+fn least_travel_is_a_shortest_solution() {
+    for puzzle in boards() {
+        let slides = puzzle.solve_slides_by(SlideCost::TRAVEL).unwrap();
+        assert_eq!(blank_travel(&slides), puzzle.solve().unwrap().len());
+    }
+}
+
+#[test]
+fn costs_must_not_all_be_zero() {
+    let free = SlideCost {
+        slides: 0,
+        travel: 0,
+    };
+    let puzzle = boards().remove(0);
+    assert!(puzzle.solve_slides_by(free).is_err());
+}
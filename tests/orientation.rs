@@ -0,0 +1,43 @@
+//! `Orientation` keeping boards and their goals together.
+
+use slider_puzzle::orientation::Orientation;
+use slider_puzzle::puzzle::{Move, Puzzle};
+
+fn orientations() -> impl Iterator<Item = Orientation> {
+    (0..8).map(|index| Orientation::new(index % 4, index >= 4))
+}
+
+#[test]
+fn goal_turns_with_the_tiles() {
+    let mut goal = Puzzle::new_rectangular(2, 3).unwrap();
+    goal.apply_moves(&[Move::Right, Move::Down, Move::Right])
+        .unwrap();
+    let goal = goal.clone().with_goal(&goal).unwrap();
+    let mut puzzle = goal.clone();
+    puzzle.apply_moves(&[Move::Up, Move::Left]).unwrap();
+
+    for orientation in orientations() {
+        let seen = orientation.apply(&puzzle);
+        assert_eq!(orientation.normalize(&seen), puzzle);
+        assert!(!seen.is_solved());
+        assert_eq!(orientation.apply(&goal).goal(), orientation.apply(&goal));
+        assert!(orientation.apply(&goal).is_solved());
+
+        let mut solved = seen.clone();
+        let moves = orientation.map_solution(&puzzle.solve_astar().unwrap());
+        assert_eq!(solved.apply_moves(&moves), Ok(()));
+        assert!(solved.is_solved(), "{:?}", orientation);
+    }
+}
+
+#[test]
+fn standard_boards_round_trip() {
+    let mut puzzle = Puzzle::new(3).unwrap();
+    puzzle
+        .apply_moves(&[Move::Down, Move::Right, Move::Down])
+        .unwrap();
+    for orientation in orientations() {
+        assert_eq!(orientation.normalize(&orientation.apply(&puzzle)), puzzle);
+        assert!(orientation.goal(3).unwrap().is_solved());
+    }
+}
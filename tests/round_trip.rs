@@ -0,0 +1,59 @@
+//! The canonical text form (`{:#}`) parses back into an equal board, and a
+//! saved failing case reads back into an equal case.
+
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use proptest::sample::select;
+use slider_puzzle::puzzle::{Move, Puzzle};
+use slider_puzzle::repro::{FailingCase, Scramble};
+use slider_puzzle::strategy;
+
+fn round_trip(puzzle: &Puzzle) -> Result<Puzzle, &'static str> {
+    format!("{:#}", puzzle).parse()
+}
+
+fn with_walked_goal(puzzle: Puzzle, walk: Vec<Move>) -> Puzzle {
+    let mut goal = Puzzle::new_rectangular(puzzle.rows(), puzzle.cols()).unwrap();
+    for movement in walk {
+        goal.apply_move(movement);
+    }
+    puzzle.with_goal(&goal).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn square_boards(scramble in strategy::scrambles(8, 8, 60)) {
+        let square = Scramble { cols: scramble.rows, ..scramble };
+        let puzzle = square.puzzle().unwrap();
+        prop_assert_eq!(round_trip(&puzzle), Ok(puzzle));
+    }
+
+    #[test]
+    fn rectangular_boards(puzzle in strategy::puzzles(8, 8, 60)) {
+        prop_assert_eq!(round_trip(&puzzle), Ok(puzzle));
+    }
+
+    #[test]
+    fn boards_with_custom_goals(
+        puzzle in strategy::puzzles(6, 6, 40),
+        walk in prop::collection::vec(select(Move::ALL.to_vec()), 1..40),
+    ) {
+        let puzzle = with_walked_goal(puzzle, walk);
+        prop_assert_eq!(round_trip(&puzzle), Ok(puzzle));
+    }
+
+    #[test]
+    fn failing_cases(
+        puzzle in strategy::puzzles(6, 6, 40),
+        walk in prop::collection::vec(select(Move::ALL.to_vec()), 0..40),
+    ) {
+        let case = FailingCase {
+            solver: "ida-star".to_string(),
+            require_optimal: true,
+            puzzle: with_walked_goal(puzzle, walk),
+            reason: "Solution has 3 moves, the shortest has 1".to_string(),
+        };
+        prop_assert_eq!(FailingCase::from_json(&case.to_json()), Ok(case));
+    }
+}
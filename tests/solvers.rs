@@ -0,0 +1,20 @@
+//! Solver answers checked by `repro::check_answer` on generated boards. A
+//! failing board is shrunk toward the smallest and least scrambled one.
+
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use slider_puzzle::config::Config;
+use slider_puzzle::registry::SolverRegistry;
+use slider_puzzle::repro::check_answer;
+use slider_puzzle::strategy;
+
+proptest! {
+    #[test]
+    fn ida_star_is_optimal(puzzle in strategy::puzzles(3, 4, 40)) {
+        let solve = SolverRegistry::with_builtins()
+            .create("ida-star", &Config::default())
+            .unwrap();
+        prop_assert_eq!(check_answer(&puzzle, solve(&puzzle), true), Ok(()));
+    }
+}
@@ -0,0 +1,34 @@
+//! `solve_strip` checked against breadth-first search on two-row boards.
+
+mod common;
+
+use common::check_puzzle_engine;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use slider_puzzle::puzzle::Puzzle;
+use slider_puzzle::strip::solve_strip;
+
+#[test]
+fn solves_two_row_boards() {
+    for (cols, step) in [(1, 1), (2, 1), (3, 1), (4, 7)] {
+        let goal = Puzzle::new_rectangular(2, cols).unwrap();
+        check_puzzle_engine(&goal, step, false, |puzzle| {
+            solve_strip(cols, &puzzle.tiles())
+        });
+    }
+}
+
+#[test]
+fn solves_wide_boards() {
+    let mut rng = ChaCha8Rng::seed_from_u64(235);
+    for cols in [5, 8, 13] {
+        for _ in 0..20 {
+            let mut puzzle = Puzzle::new_rectangular(2, cols).unwrap();
+            puzzle.shuffle_with(&mut rng);
+            let moves = solve_strip(cols, &puzzle.tiles()).unwrap();
+            let mut end = puzzle.clone();
+            assert!(end.apply_moves(&moves).is_ok(), "{:#}", puzzle);
+            assert!(end.is_solved(), "{:#}", puzzle);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! `TorusBoard` checked against breadth-first search on small boards.
+
+mod common;
+
+use common::{distances, permutations};
+use slider_puzzle::torus::TorusBoard;
+
+fn neighbours(board: &TorusBoard) -> Vec<TorusBoard> {
+    board
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            let mut next = board.clone();
+            next.apply_move(movement);
+            next
+        })
+        .collect()
+}
+
+#[test]
+fn solvability_and_solutions_match_breadth_first_search() {
+    for (rows, cols, step) in [
+        (1, 4, 1),
+        (4, 1, 1),
+        (2, 2, 1),
+        (2, 3, 1),
+        (3, 2, 1),
+        (2, 4, 397),
+        (3, 3, 3637),
+    ] {
+        let reachable = distances(TorusBoard::new(rows, cols).unwrap(), neighbours);
+        for tiles in permutations((rows * cols) as u32).iter().step_by(step) {
+            let board = TorusBoard::from_tiles(rows, cols, tiles).unwrap();
+            let distance = reachable.get(&board);
+            assert_eq!(board.is_solvable(), distance.is_some(), "{}", board);
+            let Some(&distance) = distance else {
+                assert!(board.solve().is_err());
+                continue;
+            };
+            let moves = board.solve().unwrap();
+            let mut end = board.clone();
+            assert_eq!(end.apply_moves(&moves), Ok(()));
+            assert!(end.is_solved());
+            assert_eq!(moves.len(), distance, "{}", board);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! `WalledBoard` checked against breadth-first search on small layouts.
+
+mod common;
+
+use common::{distances, permutations};
+use slider_puzzle::walls::WalledBoard;
+
+fn neighbours(board: &WalledBoard) -> Vec<WalledBoard> {
+    board
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            let mut next = board.clone();
+            next.apply_move(movement);
+            next
+        })
+        .collect()
+}
+
+/// Every `step`th arrangement of the tiles over the open cells of `goal`.
+fn arrangements(goal: &WalledBoard, step: usize) -> Vec<WalledBoard> {
+    let (rows, cols) = (goal.rows(), goal.cols());
+    let open: Vec<usize> = (0..rows * cols)
+        .filter(|&cell| !goal.is_wall(cell / cols, cell % cols))
+        .collect();
+    permutations(open.len() as u32)
+        .iter()
+        .step_by(step)
+        .map(|order| {
+            let mut cells = vec![None; rows * cols];
+            for (&cell, &value) in open.iter().zip(order) {
+                cells[cell] = Some(value);
+            }
+            WalledBoard::from_cells(rows, cols, &cells).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn solvability_and_solutions_match_breadth_first_search() {
+    let layouts = [
+        // A loop round the middle, where tiles keep their cyclic order
+        (3, 3, &[(1, 1)][..], 97),
+        // Without a corner, which has no choke point
+        (3, 3, &[(0, 0)][..], 97),
+        // The top middle cell hangs off the centre, a choke point
+        (3, 3, &[(0, 0), (0, 2)][..], 7),
+        // A corridor
+        (2, 3, &[(0, 1), (0, 2)][..], 1),
+        // A cell walled off on its own
+        (2, 3, &[(0, 1), (1, 0)][..], 1),
+        // The top left cell hangs off the one below it
+        (2, 4, &[(0, 1)][..], 13),
+    ];
+    for (rows, cols, walls, step) in layouts {
+        let goal = WalledBoard::new(rows, cols, walls).unwrap();
+        let reachable = distances(goal.clone(), neighbours);
+        for board in arrangements(&goal, step) {
+            let distance = reachable.get(&board);
+            if let Some(solvable) = board.is_solvable() {
+                assert_eq!(solvable, distance.is_some(), "{}", board);
+            }
+            let Some(&distance) = distance else {
+                if board.is_solvable().is_some() {
+                    assert!(board.solve().is_err());
+                }
+                continue;
+            };
+            let moves = board.solve().unwrap();
+            let mut end = board.clone();
+            assert_eq!(end.apply_moves(&moves), Ok(()));
+            assert!(end.is_solved());
+            assert_eq!(moves.len(), distance, "{}", board);
+        }
+    }
+}
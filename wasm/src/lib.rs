@@ -0,0 +1,39 @@
+//! The solver for web pages, compiled to WebAssembly with wasm-bindgen, for
+//! example by `wasm-pack build wasm`.
+//!
+//! Boards are passed as text in any form `Puzzle` parses, such as
+//! `3:1,2,3,4,5,6,7,8,0`, and moves come back as their names separated by
+//! spaces. Errors reach JavaScript as exceptions carrying the message. The
+//! searches block for up to their budget, so pages should call them from a
+//! Web Worker.
+
+use slider_puzzle::puzzle::Puzzle;
+use slider_puzzle::solution::Solution;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+/// The board in canonical form, or why it is not a valid board.
+#[wasm_bindgen]
+pub fn parse(board: &str) -> Result<String, String> {
+    let puzzle: Puzzle = board.parse()?;
+    Ok(format!("{:#}", puzzle))
+}
+
+/// A solution of the board, shortest if one is found within `budget_ms`
+/// milliseconds and otherwise the best one found in the rest of the budget.
+#[wasm_bindgen]
+pub fn solve(board: &str, budget_ms: u32) -> Result<String, String> {
+    let puzzle: Puzzle = board.parse()?;
+    let solution = puzzle.solve_adaptive(Duration::from_millis(budget_ms.into()))?;
+    Ok(Solution::new(solution.moves).to_string())
+}
+
+/// The best next move that can be found within `budget_ms` milliseconds.
+#[wasm_bindgen]
+pub fn hint(board: &str, budget_ms: u32) -> Result<String, String> {
+    let puzzle: Puzzle = board.parse()?;
+    let hint = puzzle
+        .hint_within(Duration::from_millis(budget_ms.into()))
+        .ok_or("No move helps: the board is solved or cannot be solved")?;
+    Ok(hint.movement.to_string())
+}
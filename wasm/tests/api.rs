@@ -0,0 +1,23 @@
+//! The exported functions, called natively.
+
+use slider_wasm::{hint, parse, solve};
+
+#[test]
+fn parse_solve_and_hint() {
+    assert_eq!(
+        parse("1 2 3 4 5 6 0 7 8"),
+        Ok("3:1,2,3,4,5,6,0,7,8".to_string())
+    );
+    assert_eq!(
+        solve("3:1,2,3,4,5,6,0,7,8", 1000),
+        Ok("Left Left".to_string())
+    );
+    assert_eq!(hint("3:1,2,3,4,5,6,0,7,8", 100), Ok("Left".to_string()));
+}
+
+#[test]
+fn errors_are_messages() {
+    assert!(parse("1 2 3").is_err());
+    assert!(solve("3:1,2,3,4,5,6,8,7,0", 1000).is_err());
+    assert!(hint("3:1,2,3,4,5,6,7,8,0", 100).is_err());
+}